@@ -172,6 +172,13 @@ fn bench_parse_integer_separator_checked_9_digits_simd(b: &mut Bencher) {
     b.iter(|| parse_integer_separator(black_box(&case), b',', b'\n'))
 }
 
+#[bench]
+fn bench_parse_integer_separator_checked_10_digits_simd(b: &mut Bencher) {
+    let case = "1700000000,1111111111111111111111";
+    b.bytes = case.len() as u64;
+    b.iter(|| parse_integer_separator(black_box(&case), b',', b'\n'))
+}
+
 #[bench]
 #[cfg(feature = "benchmark")]
 fn bench_parse_integer_separator_10_digits_sse41(b: &mut Bencher) {
@@ -278,6 +285,13 @@ fn bench_parse_integer_checked_9_digits_simd(b: &mut Bencher) {
     b.iter(|| parse_integer(black_box(&case)))
 }
 
+#[bench]
+fn bench_parse_integer_checked_10_digits_simd(b: &mut Bencher) {
+    let case = "1700000000,1000000111111111111111";
+    b.bytes = case.len() as u64;
+    b.iter(|| parse_integer(black_box(&case)))
+}
+
 #[bench]
 #[cfg(feature = "benchmark")]
 fn bench_parse_integer_10_digits_sse41(b: &mut Bencher) {
@@ -294,5 +308,493 @@ fn bench_parse_integer_10_digits_avx(b: &mut Bencher) {
     b.iter(|| safe_parse_integer_avx2(black_box(&case)))
 }
 
+// ===== `parse_u64` bench =====
+
+#[bench]
+fn bench_parse_u64_20_digits(b: &mut Bencher) {
+    let case = "12345678901234567890,11111111111";
+    b.bytes = case.len() as u64;
+    b.iter(|| parse_u64(black_box(&case)))
+}
+
+#[bench]
+fn bench_parse_u64_separator_20_digits(b: &mut Bencher) {
+    let case = "12345678901234567890,11111111111";
+    b.bytes = case.len() as u64;
+    b.iter(|| parse_u64_separator(black_box(&case), b',', b'\n'))
+}
+
+#[bench]
+fn bench_standard_parse_u64_20_digits(b: &mut Bencher) {
+    let case = "12345678901234567890";
+    b.bytes = case.len() as u64;
+    b.iter(|| black_box(&case).parse::<u64>())
+}
+
+// ===== `parse_delimited_into` bench =====
+
+/// Builds a table of `rows` rows of short integers
+fn delimited_table(rows: u32) -> String {
+    (0..rows)
+        .map(|i| format!("{},{},{},{}\n", i, i * 7, i * 131, i * 4099))
+        .collect()
+}
+
+#[bench]
+fn bench_parse_delimited_into(b: &mut Bencher) {
+    let case = delimited_table(1000);
+    let mut out = vec![0; 4000];
+    b.bytes = case.len() as u64;
+    b.iter(|| parse_delimited_into(black_box(&case), b',', b'\n', &mut out))
+}
+
+#[bench]
+fn bench_integer_iter(b: &mut Bencher) {
+    let case = delimited_table(1000);
+    b.bytes = case.len() as u64;
+    b.iter(|| {
+        IntegerIter::new(black_box(case.as_bytes()), b',', b'\n')
+            .map(Result::unwrap)
+            .fold(0u32, u32::wrapping_add)
+    })
+}
+
+#[bench]
+fn bench_parse_integer_separator_per_field(b: &mut Bencher) {
+    let case = delimited_table(1000);
+    let mut out = vec![0; 4000];
+    b.bytes = case.len() as u64;
+    b.iter(|| {
+        let s = black_box(&case).as_bytes();
+        let mut start = 0;
+        for n in out.iter_mut() {
+            let len = last_byte_without_separator_bytes(&s[start..], b',', b'\n') as usize;
+            *n = parse_integer_separator_bytes(&s[start..], b',', b'\n').unwrap();
+            start += len + 1;
+        }
+    })
+}
+
+#[bench]
+fn bench_with_best_parser_per_field(b: &mut Bencher) {
+    let case = delimited_table(1000);
+    let mut out = vec![0; 4000];
+    b.bytes = case.len() as u64;
+    b.iter(|| {
+        let s = black_box(&case).as_bytes();
+        with_best_parser!(|parser| {
+            let mut start = 0;
+            for n in out.iter_mut() {
+                let len = parser.last_byte_without_separator(&s[start..], b',', b'\n') as usize;
+                *n = parser.parse_integer_separator(&s[start..], b',', b'\n').unwrap();
+                start += len + 1;
+            }
+        })
+    })
+}
+
+// ===== SSE2 backend against SSE4.1 =====
+
+#[bench]
+fn bench_parse_integer_separator_5_digits_sse2(b: &mut Bencher) {
+    let parser = Parser::<Sse2>::new().unwrap();
+    let case = "12345,6789012345";
+    b.bytes = case.len() as u64;
+    b.iter(|| parser.run(|p| p.parse_integer_separator(black_box(case.as_bytes()), b',', b'\n')))
+}
+
+#[bench]
+fn bench_parse_integer_separator_5_digits_sse41_parser(b: &mut Bencher) {
+    let parser = Parser::<Sse41>::new().unwrap();
+    let case = "12345,6789012345";
+    b.bytes = case.len() as u64;
+    b.iter(|| parser.run(|p| p.parse_integer_separator(black_box(case.as_bytes()), b',', b'\n')))
+}
+
+#[bench]
+fn bench_parse_u64_16_digits_sse2(b: &mut Bencher) {
+    let parser = Parser::<Sse2>::new().unwrap();
+    let case = "1234567890123456,";
+    b.bytes = case.len() as u64;
+    b.iter(|| parser.run(|p| p.parse_u64(black_box(case.as_bytes()))))
+}
+
+#[bench]
+fn bench_parse_u64_16_digits_sse41_parser(b: &mut Bencher) {
+    let parser = Parser::<Sse41>::new().unwrap();
+    let case = "1234567890123456,";
+    b.bytes = case.len() as u64;
+    b.iter(|| parser.run(|p| p.parse_u64(black_box(case.as_bytes()))))
+}
+
+#[bench]
+fn bench_parse_delimited_into_sse2(b: &mut Bencher) {
+    let parser = Parser::<Sse2>::new().unwrap();
+    let case = delimited_table(1000);
+    let mut out = vec![0; 4000];
+    b.bytes = case.len() as u64;
+    b.iter(|| parser.parse_delimited_into(black_box(case.as_bytes()), b',', b'\n', &mut out))
+}
+
+#[bench]
+fn bench_parse_delimited_into_sse41_parser(b: &mut Bencher) {
+    let parser = Parser::<Sse41>::new().unwrap();
+    let case = delimited_table(1000);
+    let mut out = vec![0; 4000];
+    b.bytes = case.len() as u64;
+    b.iter(|| parser.parse_delimited_into(black_box(case.as_bytes()), b',', b'\n', &mut out))
+}
+
+// ===== portable backend, against the SSE2 benches above =====
+
+#[bench]
+fn bench_parse_integer_separator_5_digits_portable(b: &mut Bencher) {
+    let parser = Parser::<Portable>::new().unwrap();
+    let case = "12345,6789012345";
+    b.bytes = case.len() as u64;
+    b.iter(|| parser.run(|p| p.parse_integer_separator(black_box(case.as_bytes()), b',', b'\n')))
+}
+
+#[bench]
+fn bench_parse_u64_16_digits_portable(b: &mut Bencher) {
+    let parser = Parser::<Portable>::new().unwrap();
+    let case = "1234567890123456,";
+    b.bytes = case.len() as u64;
+    b.iter(|| parser.run(|p| p.parse_u64(black_box(case.as_bytes()))))
+}
+
+#[bench]
+fn bench_parse_delimited_into_portable(b: &mut Bencher) {
+    let parser = Parser::<Portable>::new().unwrap();
+    let case = delimited_table(1000);
+    let mut out = vec![0; 4000];
+    b.bytes = case.len() as u64;
+    b.iter(|| parser.parse_delimited_into(black_box(case.as_bytes()), b',', b'\n', &mut out))
+}
+
+// ===== separator sets, SSE4.2 string instructions against SSE4.1 =====
+
+/// Returns `count` fields with 1 to 9 digits, each terminated by one of
+/// `separators`, along with the offset of each field
+fn separated_fields(count: u32, separators: &[u8]) -> (Vec<u8>, Vec<usize>) {
+    let mut buf = Vec::new();
+    let mut starts = Vec::new();
+    for i in 0..count {
+        starts.push(buf.len());
+        let digits = i % 9 + 1;
+        buf.extend(format!("{}", (i as u64 * 2_654_435_761) % 10u64.pow(digits)).bytes());
+        buf.push(separators[i as usize * 7 % separators.len()]);
+    }
+    (buf, starts)
+}
+
+/// Parses all the fields of `separated_fields` with `parser`
+fn bench_separator_set<B: ParserBackend>(b: &mut Bencher, parser: Parser<B>, separators: &[u8]) {
+    let set = SeparatorSet::new(separators).unwrap();
+    let (buf, starts) = separated_fields(1000, separators);
+    b.bytes = buf.len() as u64;
+    b.iter(|| {
+        for &start in &starts {
+            let _ = black_box(parser.try_parse_integer_separator_set(black_box(&buf[start..]), &set));
+        }
+    })
+}
+
+#[bench]
+fn bench_try_parse_integer_separator_set_4_sse42(b: &mut Bencher) {
+    bench_separator_set(b, Parser::<Sse42>::new().unwrap(), b",; \n");
+}
+
+#[bench]
+fn bench_try_parse_integer_separator_set_4_sse41(b: &mut Bencher) {
+    bench_separator_set(b, Parser::<Sse41>::new().unwrap(), b",; \n");
+}
+
+#[bench]
+fn bench_try_parse_integer_separator_set_16_sse42(b: &mut Bencher) {
+    bench_separator_set(b, Parser::<Sse42>::new().unwrap(), b",;|: \t\r\n!#$%&*+/");
+}
+
+#[bench]
+fn bench_try_parse_integer_separator_set_16_sse41(b: &mut Bencher) {
+    bench_separator_set(b, Parser::<Sse41>::new().unwrap(), b",;|: \t\r\n!#$%&*+/");
+}
+
+#[bench]
+fn bench_try_parse_integer_separator_set_16_fallback(b: &mut Bencher) {
+    bench_separator_set(b, Parser::<Fallback>::new().unwrap(), b",;|: \t\r\n!#$%&*+/");
+}
+
+// ===== SWAR fallback against the byte by byte loop =====
+
+/// Returns a line of `count` fields with 1 to 10 digits, along with the offset
+/// of each field: every field is parsed from its offset up to the end of the
+/// line, as a tokenizer does
+fn mixed_length_fields(count: u32) -> (String, Vec<usize>) {
+    let mut line = String::new();
+    let mut starts = Vec::new();
+    for i in 0..count {
+        starts.push(line.len());
+        let digits = i % 10 + 1;
+        let value = (i as u64 * 2_654_435_761) % 10u64.pow(digits);
+        line += &format!("{},", value.min(u32::MAX as u64));
+    }
+    line.push('\n');
+    (line, starts)
+}
+
+#[bench]
+fn bench_parse_integer_fallback_swar(b: &mut Bencher) {
+    let (line, starts) = mixed_length_fields(1000);
+    b.bytes = line.len() as u64;
+    b.iter(|| {
+        for &start in &starts {
+            black_box(fallback::parse_integer_bytes(black_box(&line.as_bytes()[start..])));
+        }
+    })
+}
+
+#[cfg(feature = "benchmark")]
+#[bench]
+fn bench_parse_integer_fallback_byte_by_byte(b: &mut Bencher) {
+    let (line, starts) = mixed_length_fields(1000);
+    b.bytes = line.len() as u64;
+    b.iter(|| {
+        for &start in &starts {
+            let s = black_box(&line.as_bytes()[start..]);
+            black_box(fallback::parse_integer_byte_by_byte(s));
+        }
+    })
+}
+
+#[bench]
+fn bench_parse_integer_separator_fallback_swar(b: &mut Bencher) {
+    let (line, starts) = mixed_length_fields(1000);
+    b.bytes = line.len() as u64;
+    b.iter(|| {
+        for &start in &starts {
+            let s = black_box(&line.as_bytes()[start..]);
+            black_box(fallback::parse_integer_separator_bytes(s, b',', b'\n'));
+        }
+    })
+}
+
+#[cfg(feature = "benchmark")]
+#[bench]
+fn bench_parse_integer_separator_fallback_byte_by_byte(b: &mut Bencher) {
+    let (line, starts) = mixed_length_fields(1000);
+    b.bytes = line.len() as u64;
+    b.iter(|| {
+        for &start in &starts {
+            let s = black_box(&line.as_bytes()[start..]);
+            black_box(fallback::parse_integer_separator_byte_by_byte(s, b',', b'\n'));
+        }
+    })
+}
+
+#[bench]
+fn bench_parse_delimited_into_fallback(b: &mut Bencher) {
+    let case = delimited_table(1000);
+    let mut out = vec![0; 4000];
+    b.bytes = case.len() as u64;
+    b.iter(|| fallback::parse_delimited_into(black_box(case.as_bytes()), b',', b'\n', &mut out))
+}
+
+// ===== short fields, loaded past their end, against the SWAR fallback =====
+
+/// Returns `count` fields with 1 to 10 digits, already split from their
+/// separators, as the ranges of a single buffer
+fn split_fields(count: u32) -> (String, Vec<std::ops::Range<usize>>) {
+    let (line, starts) = mixed_length_fields(count);
+    let fields = starts
+        .iter()
+        .map(|&start| start..start + line[start..].find(',').unwrap())
+        .collect();
+    (line, fields)
+}
+
+/// Parses all the fields of `split_fields` with `parser`
+fn bench_short_fields<B: ParserBackend>(b: &mut Bencher, parser: Parser<B>) {
+    let (line, fields) = split_fields(1000);
+    b.bytes = line.len() as u64;
+    b.iter(|| {
+        for field in &fields {
+            black_box(parser.parse_integer(black_box(&line.as_bytes()[field.clone()])));
+        }
+    })
+}
+
+#[bench]
+fn bench_parse_integer_short_fields_sse41(b: &mut Bencher) {
+    bench_short_fields(b, Parser::<Sse41>::new().unwrap());
+}
+
+#[bench]
+fn bench_parse_integer_short_fields_sse2(b: &mut Bencher) {
+    bench_short_fields(b, Parser::<Sse2>::new().unwrap());
+}
+
+#[bench]
+fn bench_parse_integer_short_fields_portable(b: &mut Bencher) {
+    bench_short_fields(b, Parser::<Portable>::new().unwrap());
+}
+
+#[bench]
+fn bench_parse_integer_short_fields_fallback(b: &mut Bencher) {
+    bench_short_fields(b, Parser::<Fallback>::new().unwrap());
+}
+
+// ===== padded input, against the short fields above =====
+
+#[bench]
+fn bench_padded_input_short_fields(b: &mut Bencher) {
+    let (line, fields) = split_fields(1000);
+    let mut buf = line.clone().into_bytes();
+    buf.resize(line.len() + PADDING, 0);
+    let input = PaddedInput::new(&buf, line.len()).unwrap();
+    b.bytes = line.len() as u64;
+    b.iter(|| {
+        for field in &fields {
+            black_box(input.parse_integer(black_box(field.start)));
+        }
+    })
+}
+
+// ===== checked parsing of mixed length fields, validated and parsed at once =====
+
+/// Parses all the fields of `mixed_length_fields` with the checked functions of
+/// `parser`, with or without the separators
+fn bench_checked_fields<B: ParserBackend>(b: &mut Bencher, parser: Parser<B>, separator: bool) {
+    let (line, starts) = mixed_length_fields(1000);
+    b.bytes = line.len() as u64;
+    b.iter(|| {
+        for &start in &starts {
+            let s = black_box(&line.as_bytes()[start..]);
+            if separator {
+                black_box(parser.parse_integer_separator(s, b',', b'\n'));
+            } else {
+                black_box(parser.parse_integer(s));
+            }
+        }
+    })
+}
+
+#[bench]
+fn bench_parse_integer_checked_fields_avx2(b: &mut Bencher) {
+    bench_checked_fields(b, Parser::<Avx2>::new().unwrap(), false);
+}
+
+#[bench]
+fn bench_parse_integer_checked_fields_sse41(b: &mut Bencher) {
+    bench_checked_fields(b, Parser::<Sse41>::new().unwrap(), false);
+}
+
+#[bench]
+fn bench_parse_integer_separator_checked_fields_avx2(b: &mut Bencher) {
+    bench_checked_fields(b, Parser::<Avx2>::new().unwrap(), true);
+}
+
+#[bench]
+fn bench_parse_integer_separator_checked_fields_sse41(b: &mut Bencher) {
+    bench_checked_fields(b, Parser::<Sse41>::new().unwrap(), true);
+}
+
+// ===== fields of random length, parsed by a single kernel for every length =====
+
+/// Returns a line of `count` fields with 1 to 10 digits, like
+/// `mixed_length_fields`, but the number of digits of each field is drawn at
+/// random, so that it can't be predicted from the previous fields
+fn random_length_fields(count: u32) -> (String, Vec<usize>) {
+    // xorshift with a fixed seed, so that every run parses the same line
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    let mut line = String::new();
+    let mut starts = Vec::new();
+    for _ in 0..count {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        starts.push(line.len());
+        let digits = (state % 10) as u32 + 1;
+        // the leading digit is never zero, so the field has exactly `digits`
+        let low = 10u64.pow(digits - 1);
+        let value = low + (state >> 8) % (9 * low);
+        line += &format!("{},", value.min(u32::MAX as u64));
+    }
+    line.push('\n');
+    (line, starts)
+}
+
+/// Parses all the fields of `random_length_fields` up to their separator with
+/// `parser`, either checked or unchecked
+fn bench_random_fields<B: ParserBackend>(b: &mut Bencher, parser: Parser<B>, checked: bool) {
+    let (line, starts) = random_length_fields(1000);
+    b.bytes = line.len() as u64;
+    b.iter(|| {
+        for &start in &starts {
+            let s = black_box(&line.as_bytes()[start..]);
+            if checked {
+                black_box(parser.parse_integer_separator(s, b',', b'\n'));
+            } else {
+                black_box(unsafe { parser.parse_integer_separator_unchecked(s, b',', b'\n') });
+            }
+        }
+    })
+}
+
+#[bench]
+fn bench_parse_integer_separator_random_fields_avx2(b: &mut Bencher) {
+    bench_random_fields(b, Parser::<Avx2>::new().unwrap(), true);
+}
+
+#[bench]
+fn bench_parse_integer_separator_random_fields_sse41(b: &mut Bencher) {
+    bench_random_fields(b, Parser::<Sse41>::new().unwrap(), true);
+}
+
+#[bench]
+fn bench_parse_integer_separator_unchecked_random_fields_avx2(b: &mut Bencher) {
+    bench_random_fields(b, Parser::<Avx2>::new().unwrap(), false);
+}
+
+#[bench]
+fn bench_parse_integer_separator_unchecked_random_fields_sse41(b: &mut Bencher) {
+    bench_random_fields(b, Parser::<Sse41>::new().unwrap(), false);
+}
+
+// ===== scans of a whole line, on every backend =====
+
+/// Finds the end of a line of 4096 digits with `parser`
+fn bench_first_non_digit<B: ParserBackend>(b: &mut Bencher, parser: Parser<B>) {
+    let mut line = vec![b'7'; 4096];
+    line.push(b'\n');
+    b.bytes = line.len() as u64;
+    b.iter(|| parser.first_non_digit(black_box(&line)))
+}
+
+#[bench]
+fn bench_first_non_digit_avx2(b: &mut Bencher) {
+    bench_first_non_digit(b, Parser::<Avx2>::new().unwrap());
+}
+
+#[bench]
+fn bench_first_non_digit_sse41(b: &mut Bencher) {
+    bench_first_non_digit(b, Parser::<Sse41>::new().unwrap());
+}
+
+#[bench]
+fn bench_first_non_digit_sse2(b: &mut Bencher) {
+    bench_first_non_digit(b, Parser::<Sse2>::new().unwrap());
+}
+
+#[bench]
+fn bench_first_non_digit_portable(b: &mut Bencher) {
+    bench_first_non_digit(b, Parser::<Portable>::new().unwrap());
+}
+
+#[bench]
+fn bench_first_non_digit_fallback(b: &mut Bencher) {
+    bench_first_non_digit(b, Parser::<Fallback>::new().unwrap());
+}
+
 // compile command:
 // RUSTFLAGS='-C target-cpu=native' cargo bench
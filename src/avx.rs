@@ -3,27 +3,27 @@
 #[cfg(target_arch = "x86_64")]
 use std::arch::x86_64::*;
 
+use crate::scan;
+
 /// Size of _m256i register (32)
 pub(super) const VECTOR_SIZE: usize = std::mem::size_of::<__m256i>();
 
-/// Checks that all the bytes are valid digits
+/// Checks that all the bytes are valid digits, scanning the whole string like
+/// `first_non_digit`
 #[inline]
 #[cfg(target_arch = "x86_64")]
 #[target_feature(enable = "avx2")]
-pub(super) unsafe fn check_all_chars_are_valid(string: &str) -> bool {
-    if string.len() < VECTOR_SIZE {
-        return crate::fallback::check_all_chars_are_valid(string);
-    }
-    last_digit_byte(string) == 32
+pub(super) unsafe fn check_all_chars_are_valid(string: &[u8]) -> bool {
+    first_non_digit(string).is_none()
 }
 
 /// Returns the index of the last digit in the string
 #[inline]
 #[cfg(target_arch = "x86_64")]
 #[target_feature(enable = "avx2")]
-pub(super) unsafe fn last_digit_byte(string: &str) -> u32 {
+pub(super) unsafe fn last_digit_byte(string: &[u8]) -> u32 {
     if string.len() < VECTOR_SIZE {
-        return crate::fallback::last_digit_byte(string);
+        return crate::fallback::last_digit_byte_bytes(string);
     }
 
     let value = _mm256_loadu_si256(string.as_ptr() as *const _);
@@ -52,9 +52,9 @@ pub(super) unsafe fn last_digit_byte(string: &str) -> u32 {
 #[inline]
 #[cfg(target_arch = "x86_64")]
 #[target_feature(enable = "avx2")]
-pub(super) unsafe fn last_byte_without_separator(string: &str, separator: u8, eol: u8) -> u32 {
+pub(super) unsafe fn last_byte_without_separator(string: &[u8], separator: u8, eol: u8) -> u32 {
     if string.len() < VECTOR_SIZE {
-        return crate::fallback::last_byte_without_separator(
+        return crate::fallback::last_byte_without_separator_bytes(
             string,
             separator,
             eol);
@@ -82,177 +82,156 @@ pub(super) unsafe fn last_byte_without_separator(string: &str, separator: u8, eo
     movemask.trailing_zeros()
 }
 
-/// Parses 10 integers from input string using SIMD instructions.
+/// Returns the movemasks of the separators and of the non digit bytes in the
+/// first 32 chars of the string.
+///
+/// Both masks come from a single load, so that the checked parsing can locate
+/// the end of the field and the first invalid byte at once: their trailing
+/// zeros are the respective offsets.
 ///
 /// The input string *must have* at least 32 chars, otherwise the internal
 /// operations will load memory outside the string bound.
+#[inline]
 #[cfg(target_arch = "x86_64")]
 #[target_feature(enable = "avx2")]
-pub(super) unsafe fn parse_10_chars_simd(s: &str) -> u32 {
-    let mut chunk = _mm256_loadu_si256(s.as_ptr() as *const _);
-    let zeros = _mm256_set1_epi8(b'0' as i8);
-    chunk = _mm256_sub_epi8(chunk, zeros);
-
-    let mult = _mm256_set_epi8(
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 10, 1, 10, 1, 10, 1,
-        10, 1, 10,
-    );
-    chunk = _mm256_maddubs_epi16(chunk, mult);
-
-    let mult = _mm256_set_epi16(0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 100, 1, 100);
-    chunk = _mm256_madd_epi16(chunk, mult);
-
-    chunk = _mm256_packus_epi32(chunk, chunk);
-    let mult = _mm256_set_epi16(0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 10000);
-    chunk = _mm256_madd_epi16(chunk, mult);
-    let chunk = _mm256_extract_epi64(chunk, 0) as u64;
-    (((chunk & 0x00000000ffffffff) * 100) + (chunk >> 32)) as u32
+pub(super) unsafe fn separator_and_non_digit_masks(
+    string: &[u8],
+    separator: u8,
+    eol: u8
+) -> (u32, u32) {
+    masks_of(_mm256_loadu_si256(string.as_ptr() as *const _), separator, eol)
 }
 
-/// Parses 9 integers from input string using SIMD instructions.
-///
-/// The input string *must have* at least 32 chars, otherwise the internal
-/// operations will load memory outside the string bound.
+/// Returns the movemasks of the bytes of `value` equal to `separator` or
+/// `eol`, and of the ones which aren't digits
+#[inline]
 #[cfg(target_arch = "x86_64")]
 #[target_feature(enable = "avx2")]
-pub(super) unsafe fn parse_9_chars_simd(s: &str) -> u32 {
-    let mut chunk = _mm256_loadu_si256(s.as_ptr() as *const _);
-    let zeros = _mm256_set1_epi8(b'0' as i8);
-    chunk = _mm256_sub_epi8(chunk, zeros);
-
-    let mult = _mm256_set_epi8(
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 10, 1, 10, 1,
-        10, 1, 10,
+unsafe fn masks_of(value: __m256i, separator: u8, eol: u8) -> (u32, u32) {
+    // same comparisons of `last_byte_without_separator`
+    let separators = _mm256_or_si256(
+        _mm256_cmpeq_epi8(value, _mm256_set1_epi8(separator as i8)),
+        _mm256_cmpeq_epi8(value, _mm256_set1_epi8(eol as i8)),
+    );
+    // same comparisons of `last_digit_byte`
+    let non_digits = _mm256_or_si256(
+        _mm256_cmpgt_epi8(_mm256_set1_epi8(b'0' as i8), value),
+        _mm256_cmpgt_epi8(value, _mm256_set1_epi8(b'9' as i8)),
     );
-    chunk = _mm256_maddubs_epi16(chunk, mult);
-
-    let mult = _mm256_set_epi16(0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 100, 1, 100);
-    chunk = _mm256_madd_epi16(chunk, mult);
 
-    chunk = _mm256_packus_epi32(chunk, chunk);
-    let mult = _mm256_set_epi16(0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 10000);
-    chunk = _mm256_madd_epi16(chunk, mult);
-    let chunk = _mm256_extract_epi64(chunk, 0) as u64;
-    (((chunk & 0x00000000ffffffff) * 10) + (chunk >> 32)) as u32
+    (
+        _mm256_movemask_epi8(separators) as u32,
+        _mm256_movemask_epi8(non_digits) as u32,
+    )
 }
 
-/// Parses 7 integers from input string using SIMD instructions.
+/// Returns the offset of the first char of the string which isn't a digit, or
+/// None if they are all digits.
 ///
-/// The input string *must have* at least 32 chars, otherwise the internal
-/// operations will load memory outside the string bound.
+/// The whole string is scanned, 32 chars at a time.
+#[inline]
 #[cfg(target_arch = "x86_64")]
 #[target_feature(enable = "avx2")]
-pub(super) unsafe fn parse_7_chars_simd(s: &str) -> u32 {
-    let mut chunk = _mm256_loadu_si256(s.as_ptr() as *const _);
-    let zeros = _mm256_set1_epi8(b'0' as i8);
-    chunk = _mm256_sub_epi8(chunk, zeros);
-
-    let mult = _mm256_set_epi8(
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 10, 1, 10,
-        1, 10,
-    );
-    chunk = _mm256_maddubs_epi16(chunk, mult);
-
-    let mult = _mm256_set_epi16(0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 10, 1, 100);
-    chunk = _mm256_madd_epi16(chunk, mult);
-
-    chunk = _mm256_packus_epi32(chunk, chunk);
-    let mult = _mm256_set_epi16(0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1000);
-    chunk = _mm256_madd_epi16(chunk, mult);
+pub(super) unsafe fn first_non_digit(s: &[u8]) -> Option<usize> {
+    if s.len() < VECTOR_SIZE {
+        // every AVX2 CPU has SSE4.1, whose kernels need half the chars
+        return crate::sse41::first_non_digit(s);
+    }
+    scan::first_flagged(s.len(), VECTOR_SIZE, |offset| {
+        separator_and_non_digit_masks(&s[offset..], 0, 0).1
+    })
+}
 
-    _mm256_cvtsi256_si32(chunk) as u32
+/// Returns the offset of the first `separator` or `eol` in the string, or None
+/// if there is none, scanning it like `first_non_digit`
+#[inline]
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+pub(super) unsafe fn find_separator(s: &[u8], separator: u8, eol: u8) -> Option<usize> {
+    if s.len() < VECTOR_SIZE {
+        return crate::sse41::find_separator(s, separator, eol);
+    }
+    scan::first_flagged(s.len(), VECTOR_SIZE, |offset| {
+        separator_and_non_digit_masks(&s[offset..], separator, eol).0
+    })
 }
 
-/// Parses 6 integers from input string using SIMD instructions.
+/// Returns the number of leading digits among the first 32 chars of the
+/// string and, when there are at most 16, their value, in a single pass.
+///
+/// The digit range mask comes from the whole register, while the digits are
+/// right aligned and combined from its lower lane by the SSE4.1 kernel, with
+/// no second load.
 ///
 /// The input string *must have* at least 32 chars, otherwise the internal
 /// operations will load memory outside the string bound.
+#[inline]
 #[cfg(target_arch = "x86_64")]
 #[target_feature(enable = "avx2")]
-pub(super) unsafe fn parse_6_chars_simd(s: &str) -> u32 {
-    let mut chunk = _mm256_loadu_si256(s.as_ptr() as *const _);
-    let zeros = _mm256_set1_epi8(b'0' as i8);
-    chunk = _mm256_sub_epi8(chunk, zeros);
-
-    let mult = _mm256_set_epi8(
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 10, 1, 10,
-        1, 10,
-    );
-    chunk = _mm256_maddubs_epi16(chunk, mult);
-
-    let mult = _mm256_set_epi16(0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 100);
-    chunk = _mm256_madd_epi16(chunk, mult);
-
-    chunk = _mm256_packus_epi32(chunk, chunk);
-    let mult = _mm256_set_epi16(0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 100);
-    chunk = _mm256_madd_epi16(chunk, mult);
-
-    _mm256_cvtsi256_si32(chunk) as u32
+pub(super) unsafe fn parse_prefix_checked_simd(s: &[u8]) -> (u32, u64) {
+    let value = _mm256_loadu_si256(s.as_ptr() as *const _);
+    let (_, non_digits) = masks_of(value, 0, 0);
+    let digits = non_digits.trailing_zeros();
+    (digits, lower_lane_digits(value, digits))
 }
 
-/// Parses 5 integers from input string using SIMD instructions.
+/// Returns the offsets of the first `separator` or `eol` and of the first non
+/// digit among the first 32 chars of the string, 32 if there is none, along
+/// with the value of the chars before the terminator when there are at most
+/// 16, in a single pass like `parse_prefix_checked_simd`.
 ///
 /// The input string *must have* at least 32 chars, otherwise the internal
 /// operations will load memory outside the string bound.
+#[inline]
 #[cfg(target_arch = "x86_64")]
 #[target_feature(enable = "avx2")]
-pub(super) unsafe fn parse_5_chars_simd(s: &str) -> u32 {
-    let mut chunk = _mm256_loadu_si256(s.as_ptr() as *const _);
-    let zeros = _mm256_set1_epi8(b'0' as i8);
-    chunk = _mm256_sub_epi8(chunk, zeros);
-
-    let mult = _mm256_set_epi8(
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 10,
-        1, 10,
-    );
-    chunk = _mm256_maddubs_epi16(chunk, mult);
-
-    let mult = _mm256_set_epi16(0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 100);
-    chunk = _mm256_madd_epi16(chunk, mult);
-
-    chunk = _mm256_packus_epi32(chunk, chunk);
-    let mult = _mm256_set_epi16(0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 10);
-    chunk = _mm256_madd_epi16(chunk, mult);
+pub(super) unsafe fn parse_field_checked_simd(s: &[u8], separator: u8, eol: u8) -> (u32, u32, u64) {
+    let value = _mm256_loadu_si256(s.as_ptr() as *const _);
+    let (separators, non_digits) = masks_of(value, separator, eol);
+    let end = separators.trailing_zeros();
+    (end, non_digits.trailing_zeros(), lower_lane_digits(value, end))
+}
 
-    _mm256_cvtsi256_si32(chunk) as u32
+/// Combines the first `n` chars of the lower lane of `value`, meaningless when
+/// `n` is above 16
+#[inline]
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "avx2")]
+unsafe fn lower_lane_digits(value: __m256i, n: u32) -> u64 {
+    let n = (n as usize).min(crate::sse41::VECTOR_SIZE);
+    crate::sse41::combine_leading_digits(_mm256_castsi256_si128(value), n)
 }
 
-/// Parses 4 integers from input string using SIMD instructions.
+/// Parses the first `len` chars of the input string, which must be all digits,
+/// where `len` is at most 16.
 ///
-/// The input string *must have* at least 32 chars, otherwise the internal
-/// operations will load memory outside the string bound.
+/// Every length takes the same path: the SSE4.1 shuffle right aligns the
+/// digits, which are then combined by a single multiply-add reduction.
+///
+/// The input string *must have* at least 32 chars, like the other AVX2
+/// kernels, even if only the first 16 are loaded.
+#[inline]
 #[cfg(target_arch = "x86_64")]
 #[target_feature(enable = "avx2")]
-pub(super) unsafe fn parse_4_chars_simd(s: &str) -> u32 {
-    let mut chunk = _mm256_loadu_si256(s.as_ptr() as *const _);
-    let zeros = _mm256_set1_epi8(b'0' as i8);
-    chunk = _mm256_sub_epi8(chunk, zeros);
-
-    let mult = _mm256_set_epi8(
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 10,
-        1, 10,
-    );
-    chunk = _mm256_maddubs_epi16(chunk, mult);
-
-    let mult = _mm256_set_epi16(0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 100);
-    chunk = _mm256_madd_epi16(chunk, mult);
-    _mm256_cvtsi256_si32(chunk) as u32
+pub(super) unsafe fn parse_digits_simd(s: &[u8], len: usize) -> u64 {
+    crate::sse41::parse_digits_simd(s, len)
 }
 
-/// Parses 8 integers from input string using SIMD instructons.
-///
+/// Parses an u32 from a string padded with zeros.
+/// 
 /// The input string *must have* at least 32 chars, otherwise the internal
 /// operations will load memory outside the string bound.
+#[inline]
 #[cfg(target_arch = "x86_64")]
 #[target_feature(enable = "avx2")]
-pub(super) unsafe fn parse_8_chars_simd(s: &str) -> u32 {
+pub(super) unsafe fn parse_padded_integer_simd_all_numbers(s: &[u8]) -> u32 {
     let mut chunk = _mm256_loadu_si256(s.as_ptr() as *const _);
     let zeros = _mm256_set1_epi8(b'0' as i8);
-    chunk = _mm256_sub_epi64(chunk, zeros);
+    chunk = _mm256_sub_epi16(chunk, zeros);
 
     let mult = _mm256_set_epi8(
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 10, 1, 10, 1,
-        10, 1, 10,
+        1, 10, 1, 10, 1, 10, 1, 10, 1, 10, 1, 10, 1, 10, 1, 10, 1, 10, 1, 10, 1, 10, 1, 10, 1, 10,
+        1, 10, 1, 10, 1, 10,
     );
     chunk = _mm256_maddubs_epi16(chunk, mult);
 
@@ -262,24 +241,47 @@ pub(super) unsafe fn parse_8_chars_simd(s: &str) -> u32 {
     chunk = _mm256_madd_epi16(chunk, mult);
 
     chunk = _mm256_packus_epi32(chunk, chunk);
+
     let mult = _mm256_set_epi16(
         1, 10000, 1, 10000, 1, 10000, 1, 10000, 1, 10000, 1, 10000, 1, 10000, 1, 10000,
     );
     chunk = _mm256_madd_epi16(chunk, mult);
 
-    _mm256_cvtsi256_si32(chunk) as u32
+    // the index 3 is used because, since the string is padded with
+    // 0s, the number is located in the 64 rightmost bits
+    let chunk = _mm256_extract_epi64(chunk, 3) as u64;
+    (((chunk & 0xffffffff) * 100000000) + (chunk >> 32)) as u32
 }
 
-/// Parses an u32 from a string padded with zeros.
-/// 
+/// Parses an u64 from the first `len` chars of the input string, which must
+/// be all digits.
+///
+/// `len` can be at most 20, i.e. the number of digits of `u64::MAX`. If the
+/// number doesn't fit in an `u64`, `None` is returned.
+///
+/// The digits are split in two 16 bytes lanes, right aligned: the lower lane
+/// keeps the leading digits exceeding 16, while the upper lane keeps the last
+/// 16 digits. Both lanes are then reduced in parallel.
+///
 /// The input string *must have* at least 32 chars, otherwise the internal
 /// operations will load memory outside the string bound.
+#[inline]
 #[cfg(target_arch = "x86_64")]
 #[target_feature(enable = "avx2")]
-pub(super) unsafe fn parse_padded_integer_simd_all_numbers(s: &str) -> u32 {
-    let mut chunk = _mm256_loadu_si256(s.as_ptr() as *const _);
-    let zeros = _mm256_set1_epi8(b'0' as i8);
-    chunk = _mm256_sub_epi16(chunk, zeros);
+pub(super) unsafe fn parse_u64_chars_simd(s: &[u8], len: usize) -> Option<u64> {
+    let half = crate::sse41::VECTOR_SIZE;
+    let (high, low) = if len <= half {
+        (
+            _mm_setzero_si128(),
+            crate::sse41::load_right_aligned_digits(s.as_ptr(), len),
+        )
+    } else {
+        (
+            crate::sse41::load_right_aligned_digits(s.as_ptr(), len - half),
+            crate::sse41::load_right_aligned_digits(s.as_ptr().add(len - half), half),
+        )
+    };
+    let mut chunk = _mm256_set_m128i(low, high);
 
     let mult = _mm256_set_epi8(
         1, 10, 1, 10, 1, 10, 1, 10, 1, 10, 1, 10, 1, 10, 1, 10, 1, 10, 1, 10, 1, 10, 1, 10, 1, 10,
@@ -299,10 +301,13 @@ pub(super) unsafe fn parse_padded_integer_simd_all_numbers(s: &str) -> u32 {
     );
     chunk = _mm256_madd_epi16(chunk, mult);
 
-    // the index 3 is used because, since the string is padded with
-    // 0s, the number is located in the 64 rightmost bits
-    let chunk = _mm256_extract_epi64(chunk, 3) as u64;
-    (((chunk & 0xffffffff) * 100000000) + (chunk >> 32)) as u32
+    // each lane holds the first 8 digits in the lower 32 bits and the last 8
+    // digits in the upper 32 bits of its first 64 bits
+    let high = _mm256_extract_epi64(chunk, 0) as u64;
+    let low = _mm256_extract_epi64(chunk, 2) as u64;
+    let high = ((high & 0xffffffff) * 100000000) + (high >> 32);
+    let low = ((low & 0xffffffff) * 100000000) + (low >> 32);
+    high.checked_mul(10_000_000_000_000_000)?.checked_add(low)
 }
 
 #[cfg(test)]
@@ -311,7 +316,7 @@ mod tests {
 
     #[test]
     fn test_check_numbers_all_valid_when_true() {
-        let s = "11111111111111111111111111111111";
+        let s = b"11111111111111111111111111111111";
         unsafe {
             assert!(check_all_chars_are_valid(s));
         }
@@ -319,15 +324,24 @@ mod tests {
 
     #[test]
     fn test_check_numbers_all_valid_when_false() {
-        let s = "1111111=111111111111111111111111";
+        let s = b"1111111=111111111111111111111111";
+        unsafe {
+            assert!(!check_all_chars_are_valid(s));
+        }
+    }
+
+    #[test]
+    fn test_check_numbers_all_valid_past_the_first_vector() {
+        let s = b"11111111111111111111111111111111111a1111";
         unsafe {
+            assert!(check_all_chars_are_valid(&s[..35]));
             assert!(!check_all_chars_are_valid(s));
         }
     }
 
     #[test]
     fn test_last_byte_without_separator_below_10() {
-        let s = "1111111,111111111111111111111111";
+        let s = b"1111111,111111111111111111111111";
         unsafe {
             let last = last_byte_without_separator(s, b',', b'\n');
             assert_eq!(last, 7);
@@ -336,7 +350,7 @@ mod tests {
 
     #[test]
     fn test_last_byte_without_separator_no_sep() {
-        let s = "11111111111111111111111111111111";
+        let s = b"11111111111111111111111111111111";
         unsafe {
             let last = last_byte_without_separator(s, b',', b'\n');
             assert_eq!(last, 32);
@@ -345,7 +359,7 @@ mod tests {
 
     #[test]
     fn test_last_byte_without_separator_multiple_sep() {
-        let s = "11111,1111\n111111111111111111111";
+        let s = b"11111,1111\n111111111111111111111";
         unsafe {
             let last = last_byte_without_separator(s, b',', b'\n');
             assert_eq!(last, 5);
@@ -354,7 +368,7 @@ mod tests {
 
     #[test]
     fn last_digit_byte_no_digit() {
-        let s = "//:12340543905489012348908541009";
+        let s = b"//:12340543905489012348908541009";
         unsafe {
             assert_eq!(last_digit_byte(s), 0);
         }
@@ -362,7 +376,7 @@ mod tests {
 
     #[test]
     fn last_digit_byte_all_digits() {
-        let s = "12312340543905489012348908541009";
+        let s = b"12312340543905489012348908541009";
         unsafe {
             assert_eq!(last_digit_byte(s), 32);
         }
@@ -370,65 +384,93 @@ mod tests {
 
     #[test]
     fn last_digit_byte_some_digits() {
-        let s = "1231/:340543905489012348908541009";
+        let s = b"1231/:340543905489012348908541009";
         unsafe {
             assert_eq!(last_digit_byte(s), 4);
         }
     }
 
     #[test]
-    fn test_parse_10_chars_simd() {
-        let s = "12345678911111111111111111111111";
+    fn parse_prefix_checked_simd_lower_lane() {
+        let s = b"4294967295,111111111111111111111";
         unsafe {
-            assert_eq!(parse_10_chars_simd(s), 1234567891);
+            assert_eq!(parse_prefix_checked_simd(s), (10, 4294967295));
+            assert_eq!(parse_prefix_checked_simd(&[b'7'; 32]).0, 32);
+            assert_eq!(parse_prefix_checked_simd(&[b','; 32]), (0, 0));
+        }
+        let mut s = [b'1'; 32];
+        s[16] = b',';
+        assert_eq!(unsafe { parse_prefix_checked_simd(&s) }, (16, 1111111111111111));
+    }
+
+    #[test]
+    fn parse_field_checked_simd_terminators() {
+        let mut s = *b"123\n5678901234567890123456789012";
+        assert_eq!(unsafe { parse_field_checked_simd(&s, b',', b'\n') }, (3, 3, 123));
+        s[1] = b'x';
+        assert_eq!(unsafe { parse_field_checked_simd(&s, b',', b'\n') }.1, 1);
+        s[3] = b'9';
+        assert_eq!(unsafe { parse_field_checked_simd(&s, b',', b'\n') }.0, 32);
+    }
+
+    #[test]
+    fn parse_digits_simd_every_length() {
+        let s = b"12345678901234561111111111111111";
+        for len in 1..=16 {
+            let expected = s[..len].iter().fold(0, |a, c| a * 10 + (c - b'0') as u64);
+            assert_eq!(unsafe { parse_digits_simd(s, len) }, expected);
         }
     }
 
     #[test]
-    fn test_parse_9_chars_simd() {
-        let s = "12345678911111111111111111111111";
+    fn test_parse_u64_chars_simd_10_digits() {
+        let s = b"9876543210,111111111111111111111";
         unsafe {
-            assert_eq!(parse_9_chars_simd(s), 123456789);
+            assert_eq!(parse_u64_chars_simd(s, 10), Some(9876543210));
         }
     }
 
     #[test]
-    fn test_parse_8_chars_simd() {
-        let s = "12345678111111111111111111111111";
+    fn test_parse_u64_chars_simd_17_digits() {
+        let s = b"12345678901234567,11111111111111";
         unsafe {
-            assert_eq!(parse_8_chars_simd(s), 12345678);
+            assert_eq!(parse_u64_chars_simd(s, 17), Some(12345678901234567));
         }
     }
 
     #[test]
-    fn test_parse_7_chars_simd() {
-        let s = "12345678111111111111111111111111";
+    fn test_parse_u64_chars_simd_max_u64() {
+        let s = b"18446744073709551615,11111111111";
         unsafe {
-            assert_eq!(parse_7_chars_simd(s), 1234567);
+            assert_eq!(parse_u64_chars_simd(s, 20), Some(u64::MAX));
         }
     }
 
     #[test]
-    fn test_parse_6_chars_simd() {
-        let s = "12345678111111111111111111111111";
+    fn test_parse_u64_chars_simd_overflow() {
+        let s = b"18446744073709551616,11111111111";
         unsafe {
-            assert_eq!(parse_6_chars_simd(s), 123456);
+            assert_eq!(parse_u64_chars_simd(s, 20), None);
         }
     }
 
     #[test]
-    fn test_parse_5_chars_simd() {
-        let s = "12345678111111111111111111111111";
+    fn separator_and_non_digit_masks_invalid_before_separator() {
+        let s = b"12a4,678901234567890123456789012";
         unsafe {
-            assert_eq!(parse_5_chars_simd(s), 12345);
+            let (separators, non_digits) = separator_and_non_digit_masks(s, b',', b'\n');
+            assert_eq!(separators.trailing_zeros(), 4);
+            assert_eq!(non_digits.trailing_zeros(), 2);
         }
     }
 
     #[test]
-    fn test_parse_4_chars_simd() {
-        let s = "12345678111111111111111111111111";
+    fn separator_and_non_digit_masks_no_separator() {
+        let s = b"12345678901234567890123456789012";
         unsafe {
-            assert_eq!(parse_4_chars_simd(s), 1234);
+            let (separators, non_digits) = separator_and_non_digit_masks(s, b',', b'\n');
+            assert_eq!(separators.trailing_zeros(), 32);
+            assert_eq!(non_digits.trailing_zeros(), 32);
         }
     }
 }
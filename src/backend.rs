@@ -0,0 +1,849 @@
+//! Backends implementing the library API, and selection of the one used by the
+//! library.
+//!
+//! Every backend has a table with its implementation of each function of the
+//! API: the dispatchers of the library pick the functions from the table of
+//! `detected_backend()`, while a `Parser<Dynamic>` uses the table of the
+//! backend it was created with. The parsers of the zero sized backends call
+//! the same functions directly, without going through the table.
+//!
+//! The backend used by the library can be forced through the
+//! `SIMD_PARSING_BACKEND` environment variable, e.g. to reproduce a bug on the
+//! scalar path without recompiling.
+//!
+//! When the target features of a backend are enabled at compile time, e.g.
+//! with `-C target-cpu=native`, or when one of the `force-*` cargo features is
+//! enabled, the backend is fixed at compile time instead: the library calls
+//! its implementations directly, and the compiler can inline them in the
+//! caller.
+//!
+//! Each SIMD backend is compiled only with its cargo feature, i.e.
+//! `backend-avx2`, `backend-sse42`, `backend-sse41`, `backend-sse2` or
+//! `backend-portable`, all enabled by default:
+//! the backends left out are never selected. The fallback backend is always
+//! compiled.
+
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use crate::error::ParseError;
+use crate::fallback;
+#[cfg(all(target_arch = "x86_64", feature = "backend-avx2"))]
+use crate::avx;
+#[cfg(all(target_arch = "x86_64", any(feature = "backend-sse41", feature = "backend-sse42")))]
+use crate::sse41;
+#[cfg(all(target_arch = "x86_64", feature = "backend-sse42"))]
+use crate::sse42;
+#[cfg(all(target_arch = "x86_64", feature = "backend-sse2"))]
+use crate::sse2;
+#[cfg(feature = "backend-portable")]
+use crate::portable;
+
+/// Instruction set used to implement the library API
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Backend {
+    /// Scalar implementation, available on every CPU
+    Fallback,
+    /// Implementation written with the portable SIMD types of `std::simd`,
+    /// available on every architecture: on x86_64 the other SIMD backends
+    /// are faster, so it's selected only when requested
+    Portable,
+    /// SSE2 implementation, loading 16 bytes at a time: it's available on
+    /// every x86_64 CPU
+    Sse2,
+    /// SSE4.1 implementation, loading 16 bytes at a time
+    Sse41,
+    /// SSE4.2 implementation: it parses the numbers with the SSE4.1 kernels,
+    /// and compares the input against large separator sets with the string
+    /// instructions of SSE4.2
+    Sse42,
+    /// AVX2 implementation, loading 32 bytes at a time
+    Avx2,
+}
+
+impl Backend {
+    /// Every backend, from the fastest to the slowest
+    pub(crate) const ALL: [Backend; 6] = [
+        Backend::Avx2,
+        Backend::Sse42,
+        Backend::Sse41,
+        Backend::Sse2,
+        Backend::Portable,
+        Backend::Fallback,
+    ];
+
+    /// Returns whether the backend has been compiled in, through its cargo
+    /// feature
+    pub const fn is_compiled(self) -> bool {
+        match self {
+            Backend::Fallback => true,
+            Backend::Portable => cfg!(feature = "backend-portable"),
+            Backend::Sse2 => cfg!(all(target_arch = "x86_64", feature = "backend-sse2")),
+            Backend::Sse41 => cfg!(all(target_arch = "x86_64", feature = "backend-sse41")),
+            Backend::Sse42 => cfg!(all(target_arch = "x86_64", feature = "backend-sse42")),
+            Backend::Avx2 => cfg!(all(target_arch = "x86_64", feature = "backend-avx2")),
+        }
+    }
+
+    /// Returns whether the backend has been compiled in and the underlying CPU
+    /// supports it
+    pub fn is_available(self) -> bool {
+        match self {
+            Backend::Fallback => true,
+            #[cfg(feature = "backend-portable")]
+            Backend::Portable => true,
+            #[cfg(all(target_arch = "x86_64", feature = "backend-sse2"))]
+            Backend::Sse2 => is_x86_feature_detected!("sse2"),
+            #[cfg(all(target_arch = "x86_64", feature = "backend-sse41"))]
+            Backend::Sse41 => is_x86_feature_detected!("sse4.1"),
+            #[cfg(all(target_arch = "x86_64", feature = "backend-sse42"))]
+            Backend::Sse42 => {
+                is_x86_feature_detected!("sse4.1") && is_x86_feature_detected!("sse4.2")
+            }
+            // the AVX2 backend runs the SSE4.1 kernels on short inputs and
+            // the SSE4.2 ones on separator sets
+            #[cfg(all(target_arch = "x86_64", feature = "backend-avx2"))]
+            Backend::Avx2 => {
+                is_x86_feature_detected!("avx2")
+                    && is_x86_feature_detected!("sse4.1")
+                    && is_x86_feature_detected!("sse4.2")
+            }
+            #[allow(unreachable_patterns)]
+            _ => false,
+        }
+    }
+
+    /// Returns the backends compiled in and supported by the underlying CPU,
+    /// from the fastest to the slowest; the fallback one is always the last.
+    pub fn available() -> Vec<Backend> {
+        Self::ALL.iter().copied().filter(|b| b.is_available()).collect()
+    }
+
+    /// Returns the position of the backend in `Backend::ALL`
+    fn index(self) -> usize {
+        Self::ALL.iter().position(|&b| b == self).unwrap()
+    }
+
+    /// Returns the table with the implementations of the backend; the
+    /// backends not compiled in get the fallback one, although they are never
+    /// selected.
+    pub(crate) const fn functions(self) -> &'static Functions {
+        match self {
+            Backend::Fallback => &FALLBACK,
+            #[cfg(feature = "backend-portable")]
+            Backend::Portable => &PORTABLE,
+            #[cfg(all(target_arch = "x86_64", feature = "backend-sse2"))]
+            Backend::Sse2 => &SSE2,
+            #[cfg(all(target_arch = "x86_64", feature = "backend-sse41"))]
+            Backend::Sse41 => &SSE41,
+            #[cfg(all(target_arch = "x86_64", feature = "backend-sse42"))]
+            Backend::Sse42 => &SSE42,
+            #[cfg(all(target_arch = "x86_64", feature = "backend-avx2"))]
+            Backend::Avx2 => &AVX2,
+            #[allow(unreachable_patterns)]
+            _ => &FALLBACK,
+        }
+    }
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Backend::Fallback => "fallback",
+            Backend::Portable => "portable",
+            Backend::Sse2 => "sse2",
+            Backend::Sse41 => "sse41",
+            Backend::Sse42 => "sse42",
+            Backend::Avx2 => "avx2",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for Backend {
+    type Err = UnknownBackend;
+
+    /// Parses the name of a backend, as printed by `Display`, ignoring case
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|b| b.to_string().eq_ignore_ascii_case(s))
+            .ok_or_else(|| UnknownBackend { name: s.to_string() })
+    }
+}
+
+/// Backend fixed at compile time, if any: the fastest compiled in one either
+/// enabled by a `force-*` cargo feature or whose target features are enabled
+pub(crate) const STATIC_BACKEND: Option<Backend> = if cfg!(all(
+    target_arch = "x86_64",
+    feature = "backend-avx2",
+    any(
+        feature = "force-avx2",
+        all(target_feature = "avx2", target_feature = "sse4.1", target_feature = "sse4.2")
+    )
+)) {
+    Some(Backend::Avx2)
+} else if cfg!(all(
+    target_arch = "x86_64",
+    feature = "backend-sse42",
+    any(
+        feature = "force-sse42",
+        all(target_feature = "sse4.1", target_feature = "sse4.2")
+    )
+)) {
+    Some(Backend::Sse42)
+} else if cfg!(all(
+    target_arch = "x86_64",
+    feature = "backend-sse41",
+    any(feature = "force-sse41", target_feature = "sse4.1")
+)) {
+    Some(Backend::Sse41)
+} else if cfg!(all(
+    target_arch = "x86_64",
+    feature = "backend-sse2",
+    // SSE2 is enabled by default on x86_64, so its target feature can't fix
+    // the backend
+    feature = "force-sse2"
+)) {
+    Some(Backend::Sse2)
+} else if cfg!(all(feature = "backend-portable", feature = "force-portable")) {
+    Some(Backend::Portable)
+} else {
+    None
+};
+
+/// Implementations of `STATIC_BACKEND`, called directly by the library API
+pub(crate) const STATIC_FUNCTIONS: Option<&'static Functions> = match STATIC_BACKEND {
+    Some(backend) => Some(backend.functions()),
+    None => None,
+};
+
+/// Environment variable forcing the backend used by the library
+const BACKEND_ENV_VAR: &str = "SIMD_PARSING_BACKEND";
+
+/// Backend used by the library, stored as its position in `Backend::ALL` plus
+/// one, so that zero means that it hasn't been selected yet
+static SELECTED_BACKEND: AtomicU8 = AtomicU8::new(0);
+
+/// Returns the backend used by the functions of the library.
+///
+/// It's the fastest backend supported by the underlying CPU, unless the
+/// `SIMD_PARSING_BACKEND` environment variable requests a specific one with
+/// its name, i.e. `fallback`, `portable`, `sse2`, `sse41`, `sse42` or `avx2`.
+/// The variable is read only once, by the first call.
+///
+/// If the backend has been fixed at compile time, through the target features
+/// or a `force-*` cargo feature, that backend is returned, and the environment
+/// variable can only request that one.
+///
+/// # Panics
+///
+/// Panics if `SIMD_PARSING_BACKEND` isn't the name of a backend, if the
+/// requested backend isn't compiled in or supported by the CPU, or if it isn't
+/// the backend fixed at compile time.
+#[inline]
+pub fn detected_backend() -> Backend {
+    match SELECTED_BACKEND.load(Ordering::Relaxed) {
+        0 => {
+            let requested = env::var(BACKEND_ENV_VAR).ok();
+            let backend = match STATIC_BACKEND {
+                Some(fixed) => check_static_backend(fixed, requested.as_deref()),
+                None => select_backend(requested.as_deref()),
+            };
+            SELECTED_BACKEND.store(backend.index() as u8 + 1, Ordering::Relaxed);
+            backend
+        }
+        selected => Backend::ALL[selected as usize - 1],
+    }
+}
+
+/// Returns the `requested` backend, or the fastest available one if there is
+/// no request
+fn select_backend(requested: Option<&str>) -> Backend {
+    let name = match requested {
+        Some(name) if !name.is_empty() => name,
+        _ => {
+            return Backend::ALL
+                .iter()
+                .copied()
+                .find(|b| b.is_available())
+                .unwrap_or(Backend::Fallback)
+        }
+    };
+    let backend = parse_requested_backend(name);
+    if !backend.is_available() {
+        panic!("invalid {}: {}", BACKEND_ENV_VAR, UnsupportedBackend { backend });
+    }
+    backend
+}
+
+/// Returns the backend `fixed` at compile time, after checking that it's the
+/// `requested` one, if there is a request
+fn check_static_backend(fixed: Backend, requested: Option<&str>) -> Backend {
+    if let Some(name) = requested.filter(|name| !name.is_empty()) {
+        let backend = parse_requested_backend(name);
+        if backend != fixed {
+            panic!(
+                "invalid {}: the {} backend is fixed at compile time, {} can't be requested",
+                BACKEND_ENV_VAR, fixed, backend
+            );
+        }
+    }
+    fixed
+}
+
+/// Returns the backend named by the environment variable
+fn parse_requested_backend(name: &str) -> Backend {
+    match name.parse::<Backend>() {
+        Ok(backend) => backend,
+        Err(e) => panic!("invalid {}: {}", BACKEND_ENV_VAR, e),
+    }
+}
+
+/// Error returned when parsing the name of a backend that doesn't exist.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UnknownBackend {
+    /// The name that was parsed
+    name: String,
+}
+
+impl fmt::Display for UnknownBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown backend `{}`, expected one of ", self.name)?;
+        for (i, backend) in Backend::ALL.iter().rev().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{}", backend)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for UnknownBackend {}
+
+/// Error returned when a backend not compiled in, or not supported by the
+/// underlying CPU, is requested.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct UnsupportedBackend {
+    /// The requested backend
+    backend: Backend,
+}
+
+impl UnsupportedBackend {
+    /// Creates the error for the requested `backend`
+    #[inline]
+    pub(crate) fn new(backend: Backend) -> Self {
+        UnsupportedBackend { backend }
+    }
+
+    /// Returns the requested backend
+    #[inline]
+    pub fn backend(&self) -> Backend {
+        self.backend
+    }
+}
+
+impl fmt::Display for UnsupportedBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.backend.is_compiled() {
+            write!(f, "the {} backend is not supported by the CPU", self.backend)
+        } else {
+            write!(f, "the {} backend is not compiled in", self.backend)
+        }
+    }
+}
+
+impl Error for UnsupportedBackend {}
+
+/// Implementations of the library API provided by a backend, called through
+/// function pointers by the dispatchers and by the `Dynamic` parsers
+pub(crate) struct Functions {
+    /// See `last_byte_without_separator`
+    pub(crate) last_byte_without_separator: unsafe fn(&[u8], u8, u8) -> u32,
+    /// See `last_digit_byte`
+    pub(crate) last_digit_byte: unsafe fn(&[u8]) -> u32,
+    /// See `first_non_digit`
+    pub(crate) first_non_digit: unsafe fn(&[u8]) -> Option<usize>,
+    /// See `find_separator`
+    pub(crate) find_separator: unsafe fn(&[u8], u8, u8) -> Option<usize>,
+    /// See `check_all_chars_are_valid`
+    pub(crate) check_all_chars_are_valid: unsafe fn(&[u8]) -> bool,
+    /// See `parse_integer`
+    pub(crate) parse_integer: unsafe fn(&[u8]) -> Option<u32>,
+    /// See `parse_integer_separator`
+    pub(crate) parse_integer_separator: unsafe fn(&[u8], u8, u8) -> Option<u32>,
+    /// See `parse_integer_unchecked`
+    pub(crate) parse_integer_unchecked: unsafe fn(&[u8]) -> u32,
+    /// See `parse_integer_separator_unchecked`
+    pub(crate) parse_integer_separator_unchecked: unsafe fn(&[u8], u8, u8) -> u32,
+    /// See `parse_u64`
+    pub(crate) parse_u64: unsafe fn(&[u8]) -> Option<u64>,
+    /// See `parse_u64_separator`
+    pub(crate) parse_u64_separator: unsafe fn(&[u8], u8, u8) -> Option<u64>,
+    /// See `try_parse_integer`
+    pub(crate) try_parse_integer: unsafe fn(&[u8]) -> Result<u32, ParseError>,
+    /// See `try_parse_integer_separator`
+    pub(crate) try_parse_integer_separator: unsafe fn(&[u8], u8, u8) -> Result<u32, ParseError>,
+    /// See `try_parse_integer_separator_set`
+    pub(crate) try_parse_integer_separator_set: crate::TryParseIntegerSepSet,
+    /// See `parse_integer_prefix`
+    pub(crate) parse_integer_prefix: unsafe fn(&[u8]) -> Option<(u32, usize)>,
+    /// See `parse_integer_separator_prefix`
+    pub(crate) parse_integer_separator_prefix: crate::ParseIntegerSepPrefix,
+    /// See `parse_delimited_into`
+    pub(crate) parse_delimited_into: crate::ParseDelimitedInto,
+}
+
+/// Scalar implementations
+const FALLBACK: Functions = Functions {
+    last_byte_without_separator: fallback::last_byte_without_separator_bytes,
+    last_digit_byte: fallback::last_digit_byte_bytes,
+    first_non_digit: fallback::first_non_digit,
+    find_separator: fallback::find_separator,
+    check_all_chars_are_valid: fallback::check_all_chars_are_valid_bytes,
+    parse_integer: fallback::parse_integer_bytes,
+    parse_integer_separator: fallback::parse_integer_separator_bytes,
+    parse_integer_unchecked: fallback::parse_integer_unchecked_bytes,
+    parse_integer_separator_unchecked: fallback::parse_integer_separator_unchecked_bytes,
+    parse_u64: fallback::parse_u64_bytes,
+    parse_u64_separator: fallback::parse_u64_separator_bytes,
+    try_parse_integer: fallback::try_parse_integer,
+    try_parse_integer_separator: fallback::try_parse_integer_separator,
+    try_parse_integer_separator_set: fallback::try_parse_integer_separator_set,
+    parse_integer_prefix: fallback::parse_integer_prefix,
+    parse_integer_separator_prefix: fallback::parse_integer_separator_prefix,
+    parse_delimited_into: fallback::parse_delimited_into,
+};
+
+/// Declares the implementations of a backend, both as its table of `Functions`
+/// and as the `Kernels` methods of its zero sized parser backend, which call
+/// them directly
+macro_rules! backend_functions {
+    ($(#[$attr:meta])* $table:ident, $parser:ident { $($field:ident: $f:path,)* }) => {
+        $(#[$attr])*
+        const $table: Functions = Functions { $($field: $f,)* };
+
+        $(#[$attr])*
+        impl crate::parser::private::Kernels for crate::parser::$parser {
+            $(kernel!($field: $f);)*
+        }
+    };
+}
+
+/// Declares the `Kernels` method calling the implementation `$f` of the field
+/// of `Functions` with the same name
+#[cfg_attr(
+    not(any(
+        all(
+            target_arch = "x86_64",
+            any(
+                feature = "backend-sse41",
+                feature = "backend-sse42",
+                feature = "backend-avx2",
+                feature = "backend-sse2"
+            )
+        ),
+        feature = "backend-portable"
+    )),
+    allow(unused_macros)
+)]
+macro_rules! kernel {
+    (last_byte_without_separator: $f:path) => {
+        #[inline(always)]
+        unsafe fn last_byte_without_separator(&self, s: &[u8], separator: u8, eol: u8) -> u32 {
+            $f(s, separator, eol)
+        }
+    };
+    (last_digit_byte: $f:path) => {
+        #[inline(always)]
+        unsafe fn last_digit_byte(&self, s: &[u8]) -> u32 {
+            $f(s)
+        }
+    };
+    (first_non_digit: $f:path) => {
+        #[inline(always)]
+        unsafe fn first_non_digit(&self, s: &[u8]) -> Option<usize> {
+            $f(s)
+        }
+    };
+    (find_separator: $f:path) => {
+        #[inline(always)]
+        unsafe fn find_separator(&self, s: &[u8], separator: u8, eol: u8) -> Option<usize> {
+            $f(s, separator, eol)
+        }
+    };
+    (check_all_chars_are_valid: $f:path) => {
+        #[inline(always)]
+        unsafe fn check_all_chars_are_valid(&self, s: &[u8]) -> bool {
+            $f(s)
+        }
+    };
+    (parse_integer: $f:path) => {
+        #[inline(always)]
+        unsafe fn parse_integer(&self, s: &[u8]) -> Option<u32> {
+            $f(s)
+        }
+    };
+    (parse_integer_separator: $f:path) => {
+        #[inline(always)]
+        unsafe fn parse_integer_separator(&self, s: &[u8], separator: u8, eol: u8) -> Option<u32> {
+            $f(s, separator, eol)
+        }
+    };
+    (parse_integer_unchecked: $f:path) => {
+        #[inline(always)]
+        unsafe fn parse_integer_unchecked(&self, s: &[u8]) -> u32 {
+            $f(s)
+        }
+    };
+    (parse_integer_separator_unchecked: $f:path) => {
+        #[inline(always)]
+        unsafe fn parse_integer_separator_unchecked(
+            &self,
+            s: &[u8],
+            separator: u8,
+            eol: u8,
+        ) -> u32 {
+            $f(s, separator, eol)
+        }
+    };
+    (parse_u64: $f:path) => {
+        #[inline(always)]
+        unsafe fn parse_u64(&self, s: &[u8]) -> Option<u64> {
+            $f(s)
+        }
+    };
+    (parse_u64_separator: $f:path) => {
+        #[inline(always)]
+        unsafe fn parse_u64_separator(&self, s: &[u8], separator: u8, eol: u8) -> Option<u64> {
+            $f(s, separator, eol)
+        }
+    };
+    (try_parse_integer: $f:path) => {
+        #[inline(always)]
+        unsafe fn try_parse_integer(&self, s: &[u8]) -> Result<u32, ParseError> {
+            $f(s)
+        }
+    };
+    (try_parse_integer_separator: $f:path) => {
+        #[inline(always)]
+        unsafe fn try_parse_integer_separator(
+            &self,
+            s: &[u8],
+            separator: u8,
+            eol: u8,
+        ) -> Result<u32, ParseError> {
+            $f(s, separator, eol)
+        }
+    };
+    (try_parse_integer_separator_set: $f:path) => {
+        #[inline(always)]
+        unsafe fn try_parse_integer_separator_set(
+            &self,
+            s: &[u8],
+            separators: &crate::separators::SeparatorSet,
+        ) -> Result<u32, ParseError> {
+            $f(s, separators)
+        }
+    };
+    (parse_integer_prefix: $f:path) => {
+        #[inline(always)]
+        unsafe fn parse_integer_prefix(&self, s: &[u8]) -> Option<(u32, usize)> {
+            $f(s)
+        }
+    };
+    (parse_integer_separator_prefix: $f:path) => {
+        #[inline(always)]
+        unsafe fn parse_integer_separator_prefix(
+            &self,
+            s: &[u8],
+            separator: u8,
+            eol: u8,
+        ) -> Option<(u32, usize, crate::Terminator)> {
+            $f(s, separator, eol)
+        }
+    };
+    (parse_delimited_into: $f:path) => {
+        #[inline(always)]
+        unsafe fn parse_delimited_into(
+            &self,
+            buf: &[u8],
+            separator: u8,
+            eol: u8,
+            out: &mut [u32],
+        ) -> Result<(usize, usize), ParseError> {
+            $f(buf, separator, eol, out)
+        }
+    };
+}
+
+backend_functions! {
+    /// Portable SIMD implementations
+    #[cfg(feature = "backend-portable")]
+    PORTABLE, Portable {
+        last_byte_without_separator: portable::last_byte_without_separator,
+        last_digit_byte: portable::last_digit_byte,
+        first_non_digit: portable::first_non_digit,
+        find_separator: portable::find_separator,
+        check_all_chars_are_valid: portable::check_all_chars_are_valid,
+        parse_integer: crate::parse_integer_checked_portable,
+        parse_integer_separator: crate::parse_integer_sep_checked_portable,
+        parse_integer_unchecked: crate::parse_integer_portable,
+        parse_integer_separator_unchecked: crate::parse_integer_separator_portable,
+        parse_u64: crate::parse_u64_checked_portable,
+        parse_u64_separator: crate::parse_u64_sep_checked_portable,
+        try_parse_integer: crate::try_parse_integer_portable,
+        try_parse_integer_separator: crate::try_parse_integer_sep_portable,
+        // comparing the chars against one separator at a time isn't worth it
+        try_parse_integer_separator_set: fallback::try_parse_integer_separator_set,
+        parse_integer_prefix: crate::parse_integer_prefix_portable,
+        parse_integer_separator_prefix: crate::parse_integer_sep_prefix_portable,
+        parse_delimited_into: crate::parse_delimited_into_portable,
+    }
+}
+
+backend_functions! {
+    /// SSE2 implementations
+    #[cfg(all(target_arch = "x86_64", feature = "backend-sse2"))]
+    SSE2, Sse2 {
+        last_byte_without_separator: sse2::last_byte_without_separator,
+        last_digit_byte: sse2::last_digit_byte,
+        first_non_digit: sse2::first_non_digit,
+        find_separator: sse2::find_separator,
+        check_all_chars_are_valid: sse2::check_all_chars_are_valid,
+        parse_integer: crate::parse_integer_checked_sse2,
+        parse_integer_separator: crate::parse_integer_sep_checked_sse2,
+        parse_integer_unchecked: crate::parse_integer_sse2,
+        parse_integer_separator_unchecked: crate::parse_integer_separator_sse2,
+        parse_u64: crate::parse_u64_checked_sse2,
+        parse_u64_separator: crate::parse_u64_sep_checked_sse2,
+        try_parse_integer: crate::try_parse_integer_sse2,
+        try_parse_integer_separator: crate::try_parse_integer_sep_sse2,
+        // comparing the chars against one separator at a time isn't worth it
+        try_parse_integer_separator_set: fallback::try_parse_integer_separator_set,
+        parse_integer_prefix: crate::parse_integer_prefix_sse2,
+        parse_integer_separator_prefix: crate::parse_integer_sep_prefix_sse2,
+        parse_delimited_into: crate::parse_delimited_into_sse2,
+    }
+}
+
+backend_functions! {
+    /// SSE4.1 implementations
+    #[cfg(all(target_arch = "x86_64", feature = "backend-sse41"))]
+    SSE41, Sse41 {
+        last_byte_without_separator: sse41::last_byte_without_separator,
+        last_digit_byte: sse41::last_digit_byte,
+        first_non_digit: sse41::first_non_digit,
+        find_separator: sse41::find_separator,
+        check_all_chars_are_valid: sse41::check_all_chars_are_valid,
+        parse_integer: crate::parse_integer_checked_sse41,
+        parse_integer_separator: crate::parse_integer_sep_checked_sse41,
+        parse_integer_unchecked: crate::parse_integer_sse41,
+        parse_integer_separator_unchecked: crate::parse_integer_separator_sse41,
+        parse_u64: crate::parse_u64_checked_sse41,
+        parse_u64_separator: crate::parse_u64_sep_checked_sse41,
+        try_parse_integer: crate::try_parse_integer_sse41,
+        try_parse_integer_separator: crate::try_parse_integer_sep_sse41,
+        try_parse_integer_separator_set: crate::try_parse_integer_sep_set_sse41,
+        parse_integer_prefix: crate::parse_integer_prefix_sse41,
+        parse_integer_separator_prefix: crate::parse_integer_sep_prefix_sse41,
+        parse_delimited_into: crate::parse_delimited_into_sse41,
+    }
+}
+
+backend_functions! {
+    /// SSE4.2 implementations, which reuse the SSE4.1 parsing functions: with
+    /// just a separator and an end of line, the SSE4.1 comparisons are faster
+    /// than the string instructions
+    #[cfg(all(target_arch = "x86_64", feature = "backend-sse42"))]
+    SSE42, Sse42 {
+        last_byte_without_separator: sse42::last_byte_without_separator,
+        last_digit_byte: sse42::last_digit_byte,
+        first_non_digit: sse41::first_non_digit,
+        find_separator: sse41::find_separator,
+        check_all_chars_are_valid: sse42::check_all_chars_are_valid,
+        parse_integer: crate::parse_integer_checked_sse41,
+        parse_integer_separator: crate::parse_integer_sep_checked_sse41,
+        parse_integer_unchecked: crate::parse_integer_sse41,
+        parse_integer_separator_unchecked: crate::parse_integer_separator_sse41,
+        parse_u64: crate::parse_u64_checked_sse41,
+        parse_u64_separator: crate::parse_u64_sep_checked_sse41,
+        try_parse_integer: crate::try_parse_integer_sse41,
+        try_parse_integer_separator: crate::try_parse_integer_sep_sse41,
+        try_parse_integer_separator_set: crate::try_parse_integer_sep_set_sse42,
+        parse_integer_prefix: crate::parse_integer_prefix_sse41,
+        parse_integer_separator_prefix: crate::parse_integer_sep_prefix_sse41,
+        parse_delimited_into: crate::parse_delimited_into_sse41,
+    }
+}
+
+backend_functions! {
+    /// AVX2 implementations
+    #[cfg(all(target_arch = "x86_64", feature = "backend-avx2"))]
+    AVX2, Avx2 {
+        last_byte_without_separator: avx::last_byte_without_separator,
+        last_digit_byte: avx::last_digit_byte,
+        first_non_digit: avx::first_non_digit,
+        find_separator: avx::find_separator,
+        check_all_chars_are_valid: avx::check_all_chars_are_valid,
+        parse_integer: crate::parse_integer_checked_avx2,
+        parse_integer_separator: crate::parse_integer_sep_checked_avx2,
+        parse_integer_unchecked: crate::parse_integer_avx2,
+        parse_integer_separator_unchecked: crate::parse_integer_separator_avx2,
+        parse_u64: crate::parse_u64_checked_avx2,
+        parse_u64_separator: crate::parse_u64_sep_checked_avx2,
+        try_parse_integer: crate::try_parse_integer_avx2,
+        try_parse_integer_separator: crate::try_parse_integer_sep_avx2,
+        // AVX2 has no string instructions, so the backend requires SSE4.2
+        // as well, see `Backend::is_available`
+        try_parse_integer_separator_set: crate::try_parse_integer_sep_set_sse42,
+        parse_integer_prefix: crate::parse_integer_prefix_avx2,
+        parse_integer_separator_prefix: crate::parse_integer_sep_prefix_avx2,
+        parse_delimited_into: crate::parse_delimited_into_avx2,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::Parser;
+
+    #[test]
+    fn fallback_is_always_available() {
+        assert!(Backend::Fallback.is_available());
+        assert_eq!(Backend::available().last(), Some(&Backend::Fallback));
+    }
+
+    #[test]
+    fn select_backend_without_request_is_the_fastest_available() {
+        assert_eq!(select_backend(None), Backend::available()[0]);
+        assert_eq!(select_backend(Some("")), Backend::available()[0]);
+        assert_eq!(Parser::detected().backend(), detected_backend());
+    }
+
+    #[test]
+    fn select_backend_requested() {
+        assert_eq!(select_backend(Some("fallback")), Backend::Fallback);
+        for backend in Backend::available() {
+            assert_eq!(select_backend(Some(&backend.to_string())), backend);
+        }
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "invalid SIMD_PARSING_BACKEND: unknown backend `neon`, expected one of fallback, portable, sse2, sse41, sse42, avx2"
+    )]
+    fn select_backend_unknown() {
+        select_backend(Some("neon"));
+    }
+
+    #[test]
+    fn check_static_backend_requested() {
+        assert_eq!(check_static_backend(Backend::Avx2, None), Backend::Avx2);
+        assert_eq!(check_static_backend(Backend::Avx2, Some("")), Backend::Avx2);
+        assert_eq!(check_static_backend(Backend::Avx2, Some("AVX2")), Backend::Avx2);
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "invalid SIMD_PARSING_BACKEND: the avx2 backend is fixed at compile time, sse2 can't be requested"
+    )]
+    fn check_static_backend_other() {
+        check_static_backend(Backend::Avx2, Some("sse2"));
+    }
+
+    #[cfg(all(target_arch = "x86_64", target_feature = "avx2", feature = "backend-avx2"))]
+    #[test]
+    fn static_backend_avx2() {
+        assert_eq!(STATIC_BACKEND, Some(Backend::Avx2));
+        assert_eq!(detected_backend(), Backend::Avx2);
+    }
+
+    #[cfg(not(any(
+        feature = "force-avx2",
+        feature = "force-sse42",
+        feature = "force-sse41",
+        feature = "force-sse2",
+        feature = "force-portable",
+        all(target_arch = "x86_64", target_feature = "sse4.1")
+    )))]
+    #[test]
+    fn no_static_backend() {
+        assert_eq!(STATIC_BACKEND, None);
+        let requested = env::var(BACKEND_ENV_VAR).ok();
+        assert_eq!(detected_backend(), select_backend(requested.as_deref()));
+    }
+
+    #[test]
+    fn backend_from_str() {
+        assert_eq!("AVX2".parse(), Ok(Backend::Avx2));
+        assert_eq!("sse41".parse(), Ok(Backend::Sse41));
+        assert!("sse4".parse::<Backend>().is_err());
+    }
+
+    #[cfg(all(target_arch = "x86_64", feature = "backend-avx2"))]
+    #[test]
+    fn display_unsupported_backend() {
+        let e = UnsupportedBackend { backend: Backend::Avx2 };
+        assert_eq!(e.to_string(), "the avx2 backend is not supported by the CPU");
+    }
+
+    #[cfg(not(all(target_arch = "x86_64", feature = "backend-avx2")))]
+    #[test]
+    fn display_backend_not_compiled_in() {
+        let e = UnsupportedBackend { backend: Backend::Avx2 };
+        assert_eq!(e.to_string(), "the avx2 backend is not compiled in");
+    }
+
+    #[cfg(all(target_arch = "x86_64", feature = "backend-sse2"))]
+    #[test]
+    fn sse2_is_available_on_every_x86_64_cpu() {
+        assert!(Backend::Sse2.is_available());
+        assert_ne!(select_backend(None), Backend::Fallback);
+    }
+
+    #[cfg(feature = "backend-portable")]
+    #[test]
+    fn portable_is_available_when_compiled_in() {
+        assert!(Backend::Portable.is_available());
+        assert_eq!(select_backend(Some("portable")), Backend::Portable);
+        // it's the slowest vector backend, so only the fallback one follows it
+        let available = Backend::available();
+        assert_eq!(available[available.len() - 2], Backend::Portable);
+    }
+
+    #[test]
+    fn available_backends_are_compiled_in() {
+        assert!(Backend::available().iter().all(|b| b.is_compiled()));
+        assert_eq!(
+            Backend::Sse42.is_compiled(),
+            cfg!(all(target_arch = "x86_64", feature = "backend-sse42"))
+        );
+    }
+
+    #[cfg(not(any(
+        all(
+            target_arch = "x86_64",
+            any(
+                feature = "backend-avx2",
+                feature = "backend-sse42",
+                feature = "backend-sse41",
+                feature = "backend-sse2"
+            )
+        ),
+        feature = "backend-portable"
+    )))]
+    #[test]
+    fn only_fallback_compiled_in() {
+        assert_eq!(STATIC_BACKEND, None);
+        assert_eq!(Backend::available(), vec![Backend::Fallback]);
+        assert_eq!(detected_backend(), Backend::Fallback);
+        assert!(Parser::with_backend(Backend::Sse41).is_err());
+    }
+}
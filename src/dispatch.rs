@@ -0,0 +1,99 @@
+//! Thread-safe function pointers used by the single dispatch technique
+
+use std::marker::PhantomData;
+use std::mem;
+use std::sync::atomic::{AtomicPtr, Ordering};
+
+/// Function pointer of type `F` which can be loaded and replaced from any
+/// thread.
+///
+/// The pointer always refers to a valid function of type `F`: either the
+/// dispatcher or the implementation it selected. Since the functions live in
+/// static memory and never change, relaxed loads and stores are enough, and
+/// the steady state call costs the same plain load of a `static mut`.
+pub(crate) struct AtomicFn<F> {
+    /// Address of the function
+    ptr: AtomicPtr<()>,
+    /// Type of the function
+    marker: PhantomData<F>,
+}
+
+impl<F: Copy> AtomicFn<F> {
+    /// Creates a new pointer to `ptr`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must be a function pointer of type `F` cast to a raw pointer, as
+    /// done by the `atomic_fn!` macro.
+    pub(crate) const unsafe fn new(ptr: *mut ()) -> Self {
+        AtomicFn { ptr: AtomicPtr::new(ptr), marker: PhantomData }
+    }
+
+    /// Returns the function currently pointed
+    #[inline(always)]
+    pub(crate) fn get(&self) -> F {
+        debug_assert_eq!(mem::size_of::<F>(), mem::size_of::<*mut ()>());
+        let ptr = self.ptr.load(Ordering::Relaxed);
+        // SAFETY: `ptr` is always a function of type `F`, see `new` and `set`
+        unsafe { mem::transmute_copy(&ptr) }
+    }
+
+    /// Replaces the function pointed with `f`
+    #[inline]
+    pub(crate) fn set(&self, f: F) {
+        debug_assert_eq!(mem::size_of::<F>(), mem::size_of::<*mut ()>());
+        // SAFETY: `F` is a function pointer, as big as a raw pointer
+        let ptr: *mut () = unsafe { mem::transmute_copy(&f) };
+        self.ptr.store(ptr, Ordering::Relaxed);
+    }
+}
+
+/// Declares a static `AtomicFn` of the given function type, initialized with
+/// the given function; the function is coerced to the declared type, so any
+/// mismatch is a compile error.
+macro_rules! atomic_fn {
+    ($(#[$attr:meta])* static $name:ident: $ty:ty = $f:expr;) => {
+        $(#[$attr])*
+        static $name: $crate::dispatch::AtomicFn<$ty> =
+            unsafe { $crate::dispatch::AtomicFn::new($f as $ty as *mut ()) };
+    };
+}
+
+/// Calls the function `$field` of the backend fixed at compile time, if any,
+/// otherwise the function pointed by `$ptr`.
+///
+/// Since the backend table is a constant, the branch is resolved at compile
+/// time and the call to the fixed backend is a direct one.
+macro_rules! dispatch {
+    ($ptr:ident, $field:ident($($arg:expr),*)) => {
+        match $crate::backend::STATIC_FUNCTIONS {
+            Some(functions) => (functions.$field)($($arg),*),
+            None => $ptr.get()($($arg),*),
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    /// Returns one
+    fn one() -> u32 {
+        1
+    }
+
+    /// Returns two
+    fn two() -> u32 {
+        2
+    }
+
+    atomic_fn! {
+        /// Pointer used by the tests
+        static NUMBER: fn() -> u32 = one;
+    }
+
+    #[test]
+    fn atomic_fn_get_and_set() {
+        assert_eq!(NUMBER.get()(), 1);
+        NUMBER.set(two);
+        assert_eq!(NUMBER.get()(), 2);
+    }
+}
@@ -0,0 +1,101 @@
+//! Error type returned by the `try_*` parsing functions
+
+use std::error::Error;
+use std::fmt;
+
+/// The reason why a number couldn't be parsed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ParseErrorKind {
+    /// The input, or the field before the separator, has no digits
+    Empty,
+    /// A byte that isn't a digit was found where a digit was expected
+    InvalidDigit,
+    /// The number doesn't fit in the target type
+    Overflow,
+    /// The input ended before a separator or an end of line was found
+    MissingTerminator,
+}
+
+/// Error returned when a number can't be parsed from the input.
+///
+/// Along with the kind of error, it carries the offset of the offending byte
+/// inside the input. For `ParseErrorKind::MissingTerminator` the offset is the
+/// length of the input, i.e. where the terminator was expected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ParseError {
+    /// What went wrong
+    kind: ParseErrorKind,
+    /// Offset of the offending byte from the start of the input
+    offset: usize,
+}
+
+impl ParseError {
+    /// Creates a new error of the given kind at `offset`
+    #[inline]
+    pub(crate) fn new(kind: ParseErrorKind, offset: usize) -> Self {
+        ParseError { kind, offset }
+    }
+
+    /// Moves the offset forward by `n` bytes, for errors found in a sub slice
+    /// starting at `n`
+    #[inline]
+    pub(crate) fn shifted(self, n: usize) -> Self {
+        ParseError::new(self.kind, self.offset + n)
+    }
+
+    /// Returns the kind of the error
+    #[inline]
+    pub fn kind(&self) -> ParseErrorKind {
+        self.kind
+    }
+
+    /// Returns the offset of the offending byte from the start of the input
+    #[inline]
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let description = match self.kind {
+            ParseErrorKind::Empty => "no digits to parse",
+            ParseErrorKind::InvalidDigit => "invalid digit",
+            ParseErrorKind::Overflow => "number too large to fit in target type",
+            ParseErrorKind::MissingTerminator => "missing separator or end of line",
+        };
+        write!(f, "{} at byte {}", description, self.offset)
+    }
+}
+
+impl Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_invalid_digit() {
+        let e = ParseError::new(ParseErrorKind::InvalidDigit, 3);
+        assert_eq!(e.to_string(), "invalid digit at byte 3");
+    }
+
+    #[test]
+    fn display_missing_terminator() {
+        let e = ParseError::new(ParseErrorKind::MissingTerminator, 12);
+        assert_eq!(e.to_string(), "missing separator or end of line at byte 12");
+    }
+
+    #[test]
+    fn accessors() {
+        let e = ParseError::new(ParseErrorKind::Overflow, 9);
+        assert_eq!(e.kind(), ParseErrorKind::Overflow);
+        assert_eq!(e.offset(), 9);
+    }
+
+    #[test]
+    fn shifted_offset() {
+        let e = ParseError::new(ParseErrorKind::Empty, 2).shifted(5);
+        assert_eq!(e, ParseError::new(ParseErrorKind::Empty, 7));
+    }
+}
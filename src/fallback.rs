@@ -1,28 +1,40 @@
 //! Fallback implementations for parsing an u32 from a string
+//!
+//! Instead of going through the input one byte at a time, they work on 64 bits
+//! words holding 8 bytes (SWAR, SIMD within a register): bit tricks flag the
+//! bytes that are separators or non digits, while three multiply-shift steps
+//! combine 8 digits at once. The last bytes of the input are loaded into a
+//! zero padded word, so nothing past the end of the slice is ever read.
+
+use crate::error::{ParseError, ParseErrorKind};
+use crate::separators::SeparatorSet;
+use crate::Terminator;
 
 /// Parses an integer from the input string until a delimiter is encountered.
 ///
+/// If any char before the delimiter isn't a digit, then the result will be
+/// None, as for an empty field or an overflow.
+///
 /// To parse the digits, it exploits the fact that in ASCII encoding, digits are
 /// stored in the 4 least significant bits of the ASCII code. As example,
 /// consider '1': in binary is 0011-0001, and masking with 0x0F we get
 /// 0000-0001, which is 1.
 #[inline]
 pub fn parse_integer_separator(s: &str, separator: u8, eol: u8) -> Option<u32> {
-    // Extract the iter
-    let mut iter = s.bytes()
-        .take_while(|&byte| (byte != separator) && (byte != eol));
-
-    // Control if there is at least one element
-    let first = iter.next()?;
+    parse_integer_separator_bytes(s.as_bytes(), separator, eol)
+}
 
-    // Fold the result and check for overflow
-    iter.try_fold((first & 0x0F) as u32, |a, c| {
-        if let Some(m) = a.checked_mul(10u32) {
-            m.checked_add((c & 0x0F) as u32)
-        } else {
-            None
-        }
-    })
+/// Same as `parse_integer_separator`, but reads the input from a byte slice.
+#[inline]
+pub fn parse_integer_separator_bytes(s: &[u8], separator: u8, eol: u8) -> Option<u32> {
+    if let Some(n) = parse_first_word(s, |word| separators_mask(word, separator, eol)) {
+        return n;
+    }
+    let len = last_byte_without_separator_bytes(s, separator, eol) as usize;
+    if len == 0 || !check_all_chars_are_valid_bytes(&s[..len]) {
+        return None;
+    }
+    parse_digits_checked(&s[..len], u32::MAX as u64).map(|n| n as u32)
 }
 
 /// Parses an u32 from the input string up to the first occurence of `separator`
@@ -36,14 +48,23 @@ pub fn parse_integer_separator(s: &str, separator: u8, eol: u8) -> Option<u32> {
 /// string contains a number which doens't fit in a `u32`, a panic will be
 /// thrown.
 #[inline]
-pub unsafe fn parse_integer_separator_unchecked(
-    s: &str,
+pub unsafe fn parse_integer_separator_unchecked(s: &str, separator: u8, eol: u8) -> u32 {
+    parse_integer_separator_unchecked_bytes(s.as_bytes(), separator, eol)
+}
+
+/// Same as `parse_integer_separator_unchecked`, but reads the input from a byte slice.
+///
+/// # Safety
+///
+/// See `parse_integer_separator_unchecked`.
+#[inline]
+pub unsafe fn parse_integer_separator_unchecked_bytes(
+    s: &[u8],
     separator: u8,
     eol: u8
 ) -> u32 {
-    s.bytes()
-        .take_while(|&byte| (byte != separator) && (byte != eol))
-        .fold(0u32, |a, c| (a * 10) + (c & 0x0F) as u32)
+    let len = last_byte_without_separator_bytes(s, separator, eol) as usize;
+    parse_digits_unchecked(&s[..len])
 }
 
 /// Parses an integer from the input string
@@ -54,21 +75,20 @@ pub unsafe fn parse_integer_separator_unchecked(
 /// 0000-0001, which is 1.
 #[inline]
 pub fn parse_integer(s: &str) -> Option<u32> {
-    // Extract the iter
-    let mut iter = s.bytes()
-        .take_while(|&byte| (b'0'..=b'9').contains(&byte));
-
-    // Control if there is at least one element
-    let first = iter.next()?;
+    parse_integer_bytes(s.as_bytes())
+}
 
-    // Fold the result and check for overflow
-    iter.try_fold((first & 0x0F) as u32, |a, c| {
-        if let Some(m) = a.checked_mul(10u32) {
-            m.checked_add((c & 0x0F) as u32)
-        } else {
-            None
-        }
-    })
+/// Same as `parse_integer`, but reads the input from a byte slice.
+#[inline]
+pub fn parse_integer_bytes(s: &[u8]) -> Option<u32> {
+    if let Some(n) = parse_first_word(s, non_digit_mask) {
+        return n;
+    }
+    let len = last_digit_byte_bytes(s) as usize;
+    if len == 0 {
+        return None;
+    }
+    parse_digits_checked(&s[..len], u32::MAX as u64).map(|n| n as u32)
 }
 
 /// Parses an u32 from the given string
@@ -85,38 +105,528 @@ pub fn parse_integer(s: &str) -> Option<u32> {
 /// will parsed as valid digits, corrupting the result.
 #[inline]
 pub unsafe fn parse_integer_unchecked(s: &str) -> u32 {
-    s.bytes()
-        .fold(0u32, |a, c| (a * 10) + (c & 0x0F) as u32)
+    parse_integer_unchecked_bytes(s.as_bytes())
+}
+
+/// Same as `parse_integer_unchecked`, but reads the input from a byte slice.
+///
+/// # Safety
+///
+/// See `parse_integer_unchecked`.
+#[inline]
+pub unsafe fn parse_integer_unchecked_bytes(s: &[u8]) -> u32 {
+    parse_digits_unchecked(s)
+}
+
+/// Parses an u64 from the input string
+///
+/// It works as `parse_integer`, with the overflow checked against `u64::MAX`.
+#[inline]
+pub fn parse_u64(s: &str) -> Option<u64> {
+    parse_u64_bytes(s.as_bytes())
+}
+
+/// Same as `parse_u64`, but reads the input from a byte slice.
+#[inline]
+pub fn parse_u64_bytes(s: &[u8]) -> Option<u64> {
+    if let Some(n) = parse_first_word(s, non_digit_mask) {
+        return n.map(u64::from);
+    }
+    let len = last_digit_byte_bytes(s) as usize;
+    if len == 0 {
+        return None;
+    }
+    parse_digits_checked(&s[..len], u64::MAX)
+}
+
+/// Parses an u64 from the input string until a delimiter is encountered.
+///
+/// It works as `parse_integer_separator`, with the overflow checked against
+/// `u64::MAX`.
+#[inline]
+pub fn parse_u64_separator(s: &str, separator: u8, eol: u8) -> Option<u64> {
+    parse_u64_separator_bytes(s.as_bytes(), separator, eol)
+}
+
+/// Same as `parse_u64_separator`, but reads the input from a byte slice.
+#[inline]
+pub fn parse_u64_separator_bytes(s: &[u8], separator: u8, eol: u8) -> Option<u64> {
+    if let Some(n) = parse_first_word(s, |word| separators_mask(word, separator, eol)) {
+        return n.map(u64::from);
+    }
+    let len = last_byte_without_separator_bytes(s, separator, eol) as usize;
+    if len == 0 || !check_all_chars_are_valid_bytes(&s[..len]) {
+        return None;
+    }
+    parse_digits_checked(&s[..len], u64::MAX)
+}
+
+/// Parses an u32 from the input string as far as there are digits, reporting
+/// the reason of the failure.
+///
+/// An empty input gives `ParseErrorKind::Empty`, while an input not starting
+/// with a digit gives `ParseErrorKind::InvalidDigit` at offset 0.
+#[inline]
+pub fn try_parse_integer(s: &[u8]) -> Result<u32, ParseError> {
+    let digits = last_digit_byte_bytes(s) as usize;
+    if digits == 0 {
+        let kind = if s.is_empty() {
+            ParseErrorKind::Empty
+        } else {
+            ParseErrorKind::InvalidDigit
+        };
+        return Err(ParseError::new(kind, 0));
+    }
+    try_parse_digits(&s[..digits])
+}
+
+/// Parses an u32 from the input string up to the first occurrence of
+/// `separator` or `eol`, reporting the reason of the failure.
+///
+/// All the bytes before the separator must be digits. If the input ends
+/// before any separator, `ParseErrorKind::MissingTerminator` is returned.
+#[inline]
+pub fn try_parse_integer_separator(
+    s: &[u8],
+    separator: u8,
+    eol: u8
+) -> Result<u32, ParseError> {
+    let end = last_byte_without_separator_bytes(s, separator, eol) as usize;
+    try_parse_terminated(s, end)
+}
+
+/// Parses an u32 from the input string up to the first occurrence of any
+/// byte of `separators`, reporting the reason of the failure.
+///
+/// It works as `try_parse_integer_separator`, with a set of terminators.
+#[inline]
+pub fn try_parse_integer_separator_set(
+    s: &[u8],
+    separators: &SeparatorSet
+) -> Result<u32, ParseError> {
+    try_parse_terminated(s, separators.find(s))
+}
+
+/// Parses the field of the input string terminated at offset `end`, the length
+/// of the string if there is no terminator.
+#[inline]
+fn try_parse_terminated(s: &[u8], end: usize) -> Result<u32, ParseError> {
+    let field = &s[..end];
+    let invalid = last_digit_byte_bytes(field) as usize;
+    if invalid < end {
+        return Err(ParseError::new(ParseErrorKind::InvalidDigit, invalid));
+    }
+    if end == s.len() {
+        return Err(ParseError::new(ParseErrorKind::MissingTerminator, end));
+    }
+    if field.is_empty() {
+        return Err(ParseError::new(ParseErrorKind::Empty, 0));
+    }
+    try_parse_digits(field)
+}
+
+/// Parses an u32 from a non empty string made of all digits, reporting the
+/// offset of the digit causing the overflow.
+#[inline]
+pub(crate) fn try_parse_digits(digits: &[u8]) -> Result<u32, ParseError> {
+    if let Some(n) = parse_digits_checked(digits, u32::MAX as u64) {
+        return Ok(n as u32);
+    }
+    // look for the digit causing the overflow one at a time
+    digits.iter().enumerate().try_fold(0u32, |a, (i, &c)| {
+        a.checked_mul(10u32)
+            .and_then(|m| m.checked_add((c & 0x0F) as u32))
+            .ok_or_else(|| ParseError::new(ParseErrorKind::Overflow, i))
+    })
+}
+
+/// Parses all the fields of `buf`, terminated by either `separator` or `eol`,
+/// into `out`, returning the number of values written and of bytes consumed.
+#[inline]
+pub fn parse_delimited_into(
+    buf: &[u8],
+    separator: u8,
+    eol: u8,
+    out: &mut [u32]
+) -> Result<(usize, usize), ParseError> {
+    parse_fields(buf, 0, separator, eol, out, 0)
+}
+
+/// Parses the fields of `buf` from offset `start` on, writing them into `out`
+/// after the first `written` values; returns the total number of values written
+/// and the offset right after the terminator of the last field parsed.
+pub(crate) fn parse_fields(
+    buf: &[u8],
+    mut start: usize,
+    separator: u8,
+    eol: u8,
+    out: &mut [u32],
+    mut written: usize
+) -> Result<(usize, usize), ParseError> {
+    while written < out.len() && start < buf.len() {
+        let (value, end) = parse_field(buf, start, separator, eol)?;
+        out[written] = value;
+        written += 1;
+        start = end + 1;
+    }
+    // the last field of `buf` may have no terminator
+    Ok((written, start.min(buf.len())))
+}
+
+/// Parses the field of `buf` starting at offset `start`, returning its value
+/// along with the offset of its terminator, or the length of `buf` for the last
+/// field.
+pub(crate) fn parse_field(
+    buf: &[u8],
+    start: usize,
+    separator: u8,
+    eol: u8
+) -> Result<(u32, usize), ParseError> {
+    let field = &buf[start..];
+    let len = last_byte_without_separator_bytes(field, separator, eol) as usize;
+    let field = &field[..len];
+    let invalid = last_digit_byte_bytes(field) as usize;
+    if invalid < len {
+        return Err(ParseError::new(ParseErrorKind::InvalidDigit, start + invalid));
+    }
+    if field.is_empty() {
+        return Err(ParseError::new(ParseErrorKind::Empty, start));
+    }
+    let value = try_parse_digits(field).map_err(|e| e.shifted(start))?;
+    Ok((value, start + len))
+}
+
+/// Parses an u32 from the input string as far as there are digits, returning
+/// it along with the number of digits consumed.
+#[inline]
+pub fn parse_integer_prefix(s: &[u8]) -> Option<(u32, usize)> {
+    let len = last_digit_byte_bytes(s) as usize;
+    Some((parse_integer_bytes(&s[..len])?, len))
+}
+
+/// Parses an u32 from the input string up to the first occurrence of
+/// `separator` or `eol`, returning it along with the number of bytes consumed
+/// and the terminator found.
+#[inline]
+pub fn parse_integer_separator_prefix(
+    s: &[u8],
+    separator: u8,
+    eol: u8
+) -> Option<(u32, usize, Terminator)> {
+    let len = last_byte_without_separator_bytes(s, separator, eol) as usize;
+    let value = parse_integer_separator_bytes(&s[..len], separator, eol)?;
+    Some((value, len, Terminator::at(s, len, separator)))
 }
 
 /// Parses a limited amount of digits from the string
 #[inline]
 pub fn parse_byte_iterator_limited(s: &str, chars_to_parse: u32) -> u32 {
-    s.bytes()
-        .take(chars_to_parse as usize)
-        .fold(0, |a, c| a * 10 + (c & 0x0f) as u32)
+    parse_byte_iterator_limited_bytes(s.as_bytes(), chars_to_parse)
+}
+
+/// Same as `parse_byte_iterator_limited`, but reads the input from a byte slice.
+#[inline]
+pub fn parse_byte_iterator_limited_bytes(s: &[u8], chars_to_parse: u32) -> u32 {
+    let len = s.len().min(chars_to_parse as usize);
+    parse_digits_unchecked(&s[..len])
 }
 
 /// Checks if the string is composed of all numbers
 #[inline]
 pub fn check_all_chars_are_valid(s: &str) -> bool {
-    s.bytes().all(|b| (b'0'..=b'9').contains(&b))
+    check_all_chars_are_valid_bytes(s.as_bytes())
+}
+
+/// Same as `check_all_chars_are_valid`, but reads the input from a byte slice.
+#[inline]
+pub fn check_all_chars_are_valid_bytes(s: &[u8]) -> bool {
+    last_digit_byte_bytes(s) as usize == s.len()
 }
 
 /// Returns the index of the last digit not equals to separator or eol
 #[inline]
 pub fn last_byte_without_separator(s: &str, separator: u8, eol: u8) -> u32 {
-    s.bytes()
-        .take_while(|&byte| (byte != separator) && (byte != eol))
-        .count() as u32
+    last_byte_without_separator_bytes(s.as_bytes(), separator, eol)
+}
+
+/// Same as `last_byte_without_separator`, but reads the input from a byte slice.
+#[inline]
+pub fn last_byte_without_separator_bytes(s: &[u8], separator: u8, eol: u8) -> u32 {
+    count_unflagged(s, |word| separators_mask(word, separator, eol)) as u32
 }
 
 /// Returns the index of the last digit in the string
 #[inline]
 pub fn last_digit_byte(s: &str) -> u32 {
-    s.bytes()
-        .take_while(|&b| (b'0'..=b'9').contains(&b))
-        .count() as u32
+    last_digit_byte_bytes(s.as_bytes())
+}
+
+/// Same as `last_digit_byte`, but reads the input from a byte slice.
+#[inline]
+pub fn last_digit_byte_bytes(s: &[u8]) -> u32 {
+    count_unflagged(s, non_digit_mask) as u32
+}
+
+/// Returns the offset of the first char of the string which isn't a digit, or
+/// None if they are all digits
+#[inline]
+pub fn first_non_digit(s: &[u8]) -> Option<usize> {
+    first_flagged(s, non_digit_mask)
+}
+
+/// Returns the offset of the first `separator` or `eol` in the string, or None
+/// if there is none
+#[inline]
+pub fn find_separator(s: &[u8], separator: u8, eol: u8) -> Option<usize> {
+    first_flagged(s, |word| separators_mask(word, separator, eol))
+}
+
+// -----------------------------------------------------------------------------
+//                                SWAR helpers
+//
+// The words are loaded in little endian order, so the first byte of the input
+// is the lowest byte of the word, and the first flagged byte is found with
+// `trailing_zeros`.
+// -----------------------------------------------------------------------------
+
+/// Number of bytes in a word
+const WORD_SIZE: usize = 8;
+
+/// Word with all the bytes set to 1
+const ONES: u64 = 0x0101_0101_0101_0101;
+
+/// Word with the highest bit of each byte set
+const HIGH_BITS: u64 = ONES * 0x80;
+
+/// Powers of ten up to the number of digits in a word
+const POWERS_OF_TEN: [u64; WORD_SIZE + 1] =
+    [1, 10, 100, 1_000, 10_000, 100_000, 1_000_000, 10_000_000, 100_000_000];
+
+/// Loads the first 8 bytes of `s` into a word
+#[inline(always)]
+fn load_word(s: &[u8]) -> u64 {
+    let mut bytes = [0; WORD_SIZE];
+    bytes.copy_from_slice(&s[..WORD_SIZE]);
+    u64::from_le_bytes(bytes)
+}
+
+/// Loads the less than 8 bytes of `s` into a word, filling the missing bytes
+/// with zeros.
+///
+/// The bytes are read with two overlapping loads of fixed size, the first and
+/// the last ones of `s`, which are cheaper than copying a variable number of
+/// bytes.
+#[inline(always)]
+fn load_partial_word(s: &[u8]) -> u64 {
+    let len = s.len();
+    if len >= 4 {
+        let first = u32::from_le_bytes([s[0], s[1], s[2], s[3]]) as u64;
+        let last = u32::from_le_bytes([s[len - 4], s[len - 3], s[len - 2], s[len - 1]]) as u64;
+        first | last << (8 * (len - 4))
+    } else if len >= 2 {
+        let first = u16::from_le_bytes([s[0], s[1]]) as u64;
+        let last = u16::from_le_bytes([s[len - 2], s[len - 1]]) as u64;
+        first | last << (8 * (len - 2))
+    } else if len == 1 {
+        s[0] as u64
+    } else {
+        0
+    }
+}
+
+/// Sets the highest bit of the bytes of `word` which aren't ASCII digits, and
+/// clears all the other bits.
+///
+/// The high bits of the bytes are masked out before the sums, so no carry can
+/// cross a byte.
+#[inline(always)]
+fn non_digit_mask(word: u64) -> u64 {
+    let low = word & !HIGH_BITS;
+    // the highest bit of the sums is set if the byte is at least '0' and at
+    // least '9' + 1 respectively
+    let at_least_zero = low + ONES * (0x80 - b'0' as u64);
+    let above_nine = low + ONES * (0x80 - b'9' as u64 - 1);
+    (!at_least_zero | above_nine | word) & HIGH_BITS
+}
+
+/// Sets the highest bit of the bytes of `word` equal to `byte`, and clears all
+/// the other bits
+#[inline(always)]
+fn byte_mask(word: u64, byte: u8) -> u64 {
+    let diff = word ^ (ONES * byte as u64);
+    // the highest bit of the sum is set if any of the low 7 bits is set
+    let low_bits_set = (diff & !HIGH_BITS) + !HIGH_BITS;
+    !(low_bits_set | diff) & HIGH_BITS
+}
+
+/// Sets the highest bit of the bytes of `word` equal to `separator` or `eol`,
+/// and clears all the other bits
+#[inline(always)]
+fn separators_mask(word: u64, separator: u8, eol: u8) -> u64 {
+    byte_mask(word, separator) | byte_mask(word, eol)
+}
+
+/// Returns the first word of `s` and its flags computed by `mask`, with the
+/// bytes past the end of the input flagged as well
+#[inline(always)]
+fn first_word_flags(s: &[u8], mask: impl Fn(u64) -> u64) -> (u64, u64) {
+    if s.len() >= WORD_SIZE {
+        let word = load_word(s);
+        (word, mask(word))
+    } else {
+        let word = load_partial_word(s);
+        (word, mask(word) | (HIGH_BITS << (8 * s.len())))
+    }
+}
+
+/// Parses the number of `s` up to the first byte flagged by `mask`, when it is
+/// within the first word: the digits are combined straight from the word used
+/// to find its end.
+///
+/// Returns None if the number continues past the first word, otherwise the
+/// parsed number, which is None if there are no digits or if any of the bytes
+/// before the flagged one isn't a digit.
+#[inline(always)]
+fn parse_first_word(s: &[u8], mask: impl Fn(u64) -> u64) -> Option<Option<u32>> {
+    let (word, flags) = first_word_flags(s, mask);
+    if flags == 0 {
+        return None;
+    }
+    let len = (flags.trailing_zeros() / 8) as usize;
+    // shifting the flags of the non digits keeps only the ones before `len`
+    if len == 0 || non_digit_mask(word) << (8 * (WORD_SIZE - len)) != 0 {
+        return Some(None);
+    }
+    // move the digits to the highest bytes, dropping the ones after them
+    Some(Some(combine_8_digits(word << (8 * (WORD_SIZE - len)))))
+}
+
+/// Returns the number of bytes of `s` before the first one flagged by `mask`,
+/// or the length of `s` if there isn't any
+#[inline(always)]
+fn count_unflagged(s: &[u8], mask: impl Fn(u64) -> u64) -> usize {
+    let mut words = s.chunks_exact(WORD_SIZE);
+    let mut count = 0;
+    for word in &mut words {
+        let flags = mask(load_word(word));
+        if flags != 0 {
+            return count + (flags.trailing_zeros() / 8) as usize;
+        }
+        count += WORD_SIZE;
+    }
+    let tail = words.remainder();
+    // flag the bytes past the end of the input, so that the count stops there
+    let flags = mask(load_partial_word(tail)) | (HIGH_BITS << (8 * tail.len()));
+    count + (flags.trailing_zeros() / 8) as usize
+}
+
+/// Returns the offset of the first byte of `s` flagged by `mask`, or None if
+/// there isn't any
+#[inline(always)]
+fn first_flagged(s: &[u8], mask: impl Fn(u64) -> u64) -> Option<usize> {
+    let count = count_unflagged(s, mask);
+    if count < s.len() {
+        Some(count)
+    } else {
+        None
+    }
+}
+
+/// Combines the 8 digits of `word` into their value, the first digit being
+/// the most significant.
+///
+/// Only the 4 lowest bits of each byte are used. Each multiply-shift step
+/// merges every pair of adjacent lanes into a lane twice as wide, i.e.
+/// `lane * 10^k + next`: 8 lanes of 1 digit, 4 lanes of 2, 2 of 4 and then
+/// the whole number.
+#[inline(always)]
+fn combine_8_digits(word: u64) -> u32 {
+    let word = word & (ONES * 0x0F);
+    let word = (word.wrapping_mul(10 << 8 | 1) >> 8) & 0x00FF_00FF_00FF_00FF;
+    let word = (word.wrapping_mul(100 << 16 | 1) >> 16) & 0x0000_FFFF_0000_FFFF;
+    (word.wrapping_mul(10_000 << 32 | 1) >> 32) as u32
+}
+
+/// Parses the at most 8 digits of `s`
+#[inline(always)]
+fn parse_word_digits(s: &[u8]) -> u32 {
+    if s.is_empty() {
+        return 0;
+    }
+    let word = if s.len() == WORD_SIZE { load_word(s) } else { load_partial_word(s) };
+    // move the digits to the highest bytes, the zeros below act as leading
+    // zeros
+    combine_8_digits(word << (8 * (WORD_SIZE - s.len())))
+}
+
+/// Parses the digits of `s` 8 at a time, returning None if the number is
+/// greater than `max`.
+///
+/// Like the byte by byte parsing, only the 4 lowest bits of each byte are used.
+#[inline]
+fn parse_digits_checked(s: &[u8], max: u64) -> Option<u64> {
+    let mut words = s.chunks_exact(WORD_SIZE);
+    let mut value = 0u64;
+    for word in &mut words {
+        value = value
+            .checked_mul(POWERS_OF_TEN[WORD_SIZE])?
+            .checked_add(combine_8_digits(load_word(word)) as u64)?;
+        if value > max {
+            return None;
+        }
+    }
+    let tail = words.remainder();
+    let value = value
+        .checked_mul(POWERS_OF_TEN[tail.len()])?
+        .checked_add(parse_word_digits(tail) as u64)?;
+    if value > max {
+        return None;
+    }
+    Some(value)
+}
+
+/// Parses the digits of `s` 8 at a time, without checking for overflows:
+/// like the byte by byte parsing, it panics in debug builds.
+#[inline]
+fn parse_digits_unchecked(s: &[u8]) -> u32 {
+    let mut words = s.chunks_exact(WORD_SIZE);
+    let mut value = 0u32;
+    for word in &mut words {
+        value = value * POWERS_OF_TEN[WORD_SIZE] as u32 + combine_8_digits(load_word(word));
+    }
+    let tail = words.remainder();
+    value * POWERS_OF_TEN[tail.len()] as u32 + parse_word_digits(tail)
+}
+
+// -----------------------------------------------------------------------------
+//                             Benchmark only function
+// The byte by byte implementations replaced by the SWAR ones, kept to compare
+// their performance.
+// -----------------------------------------------------------------------------
+
+/// Parses an u32 from the input string one byte at a time
+#[cfg(feature = "benchmark")]
+#[inline]
+pub fn parse_integer_byte_by_byte(s: &[u8]) -> Option<u32> {
+    let mut iter = s.iter().copied()
+        .take_while(|&byte| byte.is_ascii_digit());
+    let first = iter.next()?;
+    iter.try_fold((first & 0x0F) as u32, |a, c| {
+        a.checked_mul(10u32)?.checked_add((c & 0x0F) as u32)
+    })
+}
+
+/// Parses an u32 from the input string up to the first occurrence of
+/// `separator` or `eol` one byte at a time
+#[cfg(feature = "benchmark")]
+#[inline]
+pub fn parse_integer_separator_byte_by_byte(s: &[u8], separator: u8, eol: u8) -> Option<u32> {
+    let mut iter = s.iter().copied()
+        .take_while(|&byte| (byte != separator) && (byte != eol));
+    let first = iter.next()?;
+    iter.try_fold((first & 0x0F) as u32, |a, c| {
+        a.checked_mul(10u32)?.checked_add((c & 0x0F) as u32)
+    })
 }
 
 #[cfg(test)]
@@ -256,4 +766,277 @@ mod tests {
         let s = format!("{}", u32::MAX);
         assert_eq!(parse_integer(&s), Some(u32::MAX));
     }
+
+    #[test]
+    fn parse_u64_empty() {
+        let s = "";
+        assert_eq!(parse_u64(s), None);
+    }
+
+    #[test]
+    fn parse_u64_max_u64() {
+        let s = format!("{}", u64::MAX);
+        assert_eq!(parse_u64(&s), Some(u64::MAX));
+    }
+
+    #[test]
+    fn parse_u64_overflow() {
+        let s = "18446744073709551616";
+        assert_eq!(parse_u64(s), None);
+    }
+
+    #[test]
+    fn parse_u64_separator_separator() {
+        let s = "12345678901,2345";
+        assert_eq!(parse_u64_separator(s, SEP, EOL), Some(12345678901));
+    }
+
+    #[test]
+    fn parse_u64_separator_only_separators() {
+        let s = "\n\n,,";
+        assert_eq!(parse_u64_separator(s, SEP, EOL), None);
+    }
+
+    #[test]
+    fn parse_u64_separator_non_digit() {
+        let s = "1234a678901,2345";
+        assert_eq!(parse_u64_separator(s, SEP, EOL), None);
+    }
+
+    #[test]
+    fn try_parse_integer_empty() {
+        let s = b"";
+        let e = try_parse_integer(s).unwrap_err();
+        assert_eq!((e.kind(), e.offset()), (ParseErrorKind::Empty, 0));
+    }
+
+    #[test]
+    fn try_parse_integer_no_digit() {
+        let s = b",123";
+        let e = try_parse_integer(s).unwrap_err();
+        assert_eq!((e.kind(), e.offset()), (ParseErrorKind::InvalidDigit, 0));
+    }
+
+    #[test]
+    fn try_parse_integer_overflow() {
+        let s = b"4294967296,";
+        let e = try_parse_integer(s).unwrap_err();
+        assert_eq!((e.kind(), e.offset()), (ParseErrorKind::Overflow, 9));
+    }
+
+    #[test]
+    fn try_parse_integer_max_u32() {
+        let s = b"4294967295";
+        assert_eq!(try_parse_integer(s), Ok(u32::MAX));
+    }
+
+    #[test]
+    fn try_parse_integer_separator_valid() {
+        let s = b"1234,5";
+        assert_eq!(try_parse_integer_separator(s, SEP, EOL), Ok(1234));
+    }
+
+    #[test]
+    fn try_parse_integer_separator_empty_field() {
+        let s = b",5";
+        let e = try_parse_integer_separator(s, SEP, EOL).unwrap_err();
+        assert_eq!((e.kind(), e.offset()), (ParseErrorKind::Empty, 0));
+    }
+
+    #[test]
+    fn try_parse_integer_separator_invalid_digit() {
+        let s = b"12a4\n";
+        let e = try_parse_integer_separator(s, SEP, EOL).unwrap_err();
+        assert_eq!((e.kind(), e.offset()), (ParseErrorKind::InvalidDigit, 2));
+    }
+
+    #[test]
+    fn try_parse_integer_separator_missing_terminator() {
+        let s = b"1234";
+        let e = try_parse_integer_separator(s, SEP, EOL).unwrap_err();
+        assert_eq!((e.kind(), e.offset()), (ParseErrorKind::MissingTerminator, 4));
+    }
+
+    #[test]
+    fn try_parse_integer_separator_set_any_separator() {
+        let set = SeparatorSet::new(b";| ").unwrap();
+        assert_eq!(try_parse_integer_separator_set(b"1234|5", &set), Ok(1234));
+        assert_eq!(try_parse_integer_separator_set(b"12 34;5", &set), Ok(12));
+        let e = try_parse_integer_separator_set(b"1234,5", &set).unwrap_err();
+        assert_eq!((e.kind(), e.offset()), (ParseErrorKind::InvalidDigit, 4));
+        let e = try_parse_integer_separator_set(b"1234", &set).unwrap_err();
+        assert_eq!((e.kind(), e.offset()), (ParseErrorKind::MissingTerminator, 4));
+    }
+
+    #[test]
+    fn parse_integer_prefix_more_digits() {
+        let s = b"1234,5";
+        assert_eq!(parse_integer_prefix(s), Some((1234, 4)));
+    }
+
+    #[test]
+    fn parse_integer_prefix_no_digit() {
+        let s = b",5";
+        assert_eq!(parse_integer_prefix(s), None);
+    }
+
+    #[test]
+    fn parse_integer_separator_prefix_eol() {
+        let s = b"1234\n5";
+        assert_eq!(
+            parse_integer_separator_prefix(s, SEP, EOL),
+            Some((1234, 4, Terminator::Eol))
+        );
+    }
+
+    #[test]
+    fn parse_integer_separator_prefix_end_of_input() {
+        let s = b"1234";
+        assert_eq!(
+            parse_integer_separator_prefix(s, SEP, EOL),
+            Some((1234, 4, Terminator::EndOfInput))
+        );
+    }
+
+    #[test]
+    fn parse_delimited_into_all_fields() {
+        let s = b"1,22\n333,4444\n";
+        let mut out = [0; 8];
+        assert_eq!(parse_delimited_into(s, SEP, EOL, &mut out), Ok((4, 14)));
+        assert_eq!(out[..4], [1, 22, 333, 4444]);
+    }
+
+    #[test]
+    fn parse_delimited_into_full_output() {
+        let s = b"1,22,333";
+        let mut out = [0; 2];
+        assert_eq!(parse_delimited_into(s, SEP, EOL, &mut out), Ok((2, 5)));
+        assert_eq!(out, [1, 22]);
+        assert_eq!(parse_delimited_into(&s[5..], SEP, EOL, &mut out), Ok((1, 3)));
+        assert_eq!(out[0], 333);
+    }
+
+    #[test]
+    fn parse_delimited_into_empty_field() {
+        let s = b"1,,3";
+        let mut out = [0; 4];
+        let e = parse_delimited_into(s, SEP, EOL, &mut out).unwrap_err();
+        assert_eq!(e, ParseError::new(ParseErrorKind::Empty, 2));
+    }
+
+    #[test]
+    fn parse_delimited_into_overflow() {
+        let s = b"1,4294967296";
+        let mut out = [0; 4];
+        let e = parse_delimited_into(s, SEP, EOL, &mut out).unwrap_err();
+        assert_eq!(e, ParseError::new(ParseErrorKind::Overflow, 11));
+    }
+
+    #[test]
+    fn non_digit_mask_every_byte() {
+        for byte in 0..=255u8 {
+            let expected = if byte.is_ascii_digit() { 0 } else { HIGH_BITS };
+            assert_eq!(non_digit_mask(ONES * byte as u64), expected, "byte {}", byte);
+        }
+    }
+
+    #[test]
+    fn non_digit_mask_flags_only_non_digits() {
+        let word = load_word(b"0/9:\x80\xb0\xff5");
+        assert_eq!(non_digit_mask(word), 0x0080_8080_8000_8000);
+    }
+
+    #[test]
+    fn byte_mask_every_byte() {
+        for byte in 0..=255u8 {
+            let expected = if byte == SEP { HIGH_BITS } else { 0 };
+            assert_eq!(byte_mask(ONES * byte as u64, SEP), expected, "byte {}", byte);
+        }
+    }
+
+    #[test]
+    fn byte_mask_flags_only_the_byte() {
+        let word = load_word(b",\xac-,+\n,,");
+        assert_eq!(byte_mask(word, SEP), 0x8080_0000_8000_0080);
+    }
+
+    #[test]
+    fn combine_8_digits_all_values() {
+        assert_eq!(combine_8_digits(load_word(b"12345678")), 12345678);
+        assert_eq!(combine_8_digits(load_word(b"00000000")), 0);
+        assert_eq!(combine_8_digits(load_word(b"99999999")), 99999999);
+        assert_eq!(combine_8_digits(load_word(b"90000001")), 90000001);
+    }
+
+    #[test]
+    fn parse_word_digits_every_length() {
+        let s = b"12345678";
+        for len in 0..=WORD_SIZE {
+            let expected = s[..len].iter().fold(0, |a, c| a * 10 + (c & 0x0F) as u32);
+            assert_eq!(parse_word_digits(&s[..len]), expected);
+        }
+    }
+
+    #[test]
+    fn load_partial_word_every_length() {
+        let s = b"\x01\x02\x03\x04\x05\x06\x07";
+        for len in 0..WORD_SIZE {
+            let expected = s[..len].iter().rev().fold(0, |a, &c| a << 8 | c as u64);
+            assert_eq!(load_partial_word(&s[..len]), expected);
+        }
+    }
+
+    #[test]
+    fn parse_first_word_stops_at_the_end_of_the_number() {
+        for len in 0..=2 * WORD_SIZE {
+            let s = format!("{},99", "9".repeat(len));
+            let expected = s[..len].parse::<u64>().ok().filter(|&n| n <= u32::MAX as u64);
+            let expected = expected.map(|n| n as u32);
+            assert_eq!(parse_integer_separator_bytes(s.as_bytes(), SEP, EOL), expected);
+            assert_eq!(parse_integer_bytes(s.as_bytes()), expected);
+        }
+    }
+
+    #[test]
+    fn last_byte_without_separator_every_tail_length() {
+        for len in 0..=3 * WORD_SIZE {
+            let field = "7".repeat(len);
+            let s = format!("{},1", field);
+            assert_eq!(last_byte_without_separator_bytes(s.as_bytes(), SEP, EOL), len as u32);
+            assert_eq!(last_byte_without_separator_bytes(field.as_bytes(), SEP, EOL), len as u32);
+            assert_eq!(last_digit_byte_bytes(s.as_bytes()), len as u32);
+            assert_eq!(last_digit_byte_bytes(field.as_bytes()), len as u32);
+        }
+    }
+
+    #[test]
+    fn separator_zero_is_not_confused_with_padding() {
+        let s = "123";
+        assert_eq!(last_byte_without_separator(s, 0, EOL), 3);
+        assert_eq!(parse_integer_separator(s, 0, EOL), Some(123));
+    }
+
+    #[test]
+    fn parse_integer_overflow_boundaries() {
+        assert_eq!(parse_integer_bytes(b"4294967295,"), Some(u32::MAX));
+        assert_eq!(parse_integer_bytes(b"4294967296,"), None);
+        assert_eq!(parse_integer_bytes(b"000000000000000004294967295"), Some(u32::MAX));
+        assert_eq!(parse_integer_bytes(b"99999999999999999999999999"), None);
+        assert_eq!(parse_u64_bytes(b"000000018446744073709551615"), Some(u64::MAX));
+        assert_eq!(parse_u64_bytes(b"18446744073709551616"), None);
+        assert_eq!(parse_u64_bytes(b"999999999999999999999999999"), None);
+    }
+
+    #[test]
+    fn parse_integer_separator_unchecked_long_field() {
+        let s = "00000000000000001234567,8";
+        assert_eq!(unsafe { parse_integer_separator_unchecked(s, SEP, EOL) }, 1234567);
+    }
+
+    #[test]
+    fn try_parse_integer_overflow_past_first_word() {
+        let s = b"000000000042949672950";
+        let e = try_parse_integer(s).unwrap_err();
+        assert_eq!(e, ParseError::new(ParseErrorKind::Overflow, 20));
+    }
 }
@@ -0,0 +1,344 @@
+//! Streaming parser over all the integers of a separated buffer
+
+use std::iter::FusedIterator;
+
+use crate::backend::{detected_backend, Backend};
+use crate::error::ParseError;
+#[cfg(any(
+    all(
+        target_arch = "x86_64",
+        any(
+            feature = "backend-sse41",
+            feature = "backend-sse42",
+            feature = "backend-avx2",
+            feature = "backend-sse2"
+        )
+    ),
+    feature = "backend-portable"
+))]
+use crate::error::ParseErrorKind;
+use crate::fallback;
+#[cfg(all(target_arch = "x86_64", feature = "backend-avx2"))]
+use crate::avx;
+#[cfg(all(
+    target_arch = "x86_64",
+    any(feature = "backend-sse41", feature = "backend-sse42")
+))]
+use crate::sse41;
+#[cfg(all(target_arch = "x86_64", feature = "backend-sse2"))]
+use crate::sse2;
+#[cfg(feature = "backend-portable")]
+use crate::portable;
+
+/// Iterator over all the integers of a buffer whose fields are terminated by
+/// either a separator or an end of line.
+///
+/// It yields one `Result<u32, ParseError>` per field, with the same rules of
+/// `parse_delimited_into`: a terminator at the end of the buffer is optional,
+/// and after the first error the iteration is over.
+///
+/// The separator mask of the last SIMD load is kept between the calls to
+/// `next`, so that short fields packed in the same chunk are located without
+/// loading and comparing the chunk again.
+///
+/// ```
+/// use simd_parsing::IntegerIter;
+///
+/// let values: Result<Vec<u32>, _> = IntegerIter::new(b"1,22\n333\n", b',', b'\n').collect();
+/// assert_eq!(values, Ok(vec![1, 22, 333]));
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(
+    not(any(
+        all(
+            target_arch = "x86_64",
+            any(
+                feature = "backend-sse41",
+                feature = "backend-sse42",
+                feature = "backend-avx2",
+                feature = "backend-sse2"
+            )
+        ),
+        feature = "backend-portable"
+    )),
+    allow(dead_code)
+)]
+pub struct IntegerIter<'a> {
+    /// Buffer to parse
+    buf: &'a [u8],
+    /// Field separator
+    separator: u8,
+    /// End of line, terminating the fields as well
+    eol: u8,
+    /// Offset of the next field to parse
+    start: usize,
+    /// Offset of the chunk described by `separators` and `non_digits`
+    chunk: usize,
+    /// Terminators of the chunk not consumed yet
+    separators: u32,
+    /// Non digits of the chunk
+    non_digits: u32,
+    /// Backend used to scan the buffer
+    backend: Backend,
+    /// Whether an error has been returned, ending the iteration
+    failed: bool,
+}
+
+impl<'a> IntegerIter<'a> {
+    /// Creates an iterator over the integers of `buf`, separated by either
+    /// `separator` or `eol`.
+    pub fn new(buf: &'a [u8], separator: u8, eol: u8) -> Self {
+        Self::with_backend(buf, separator, eol, detected_backend())
+    }
+
+    /// Creates an iterator over the integers of `buf` using `backend`, which
+    /// must be supported by the underlying CPU.
+    pub(crate) fn with_backend(buf: &'a [u8], separator: u8, eol: u8, backend: Backend) -> Self {
+        IntegerIter {
+            buf,
+            separator,
+            eol,
+            start: 0,
+            chunk: 0,
+            separators: 0,
+            non_digits: 0,
+            backend,
+            failed: false,
+        }
+    }
+
+    /// Yields the next field using only scalar code
+    fn next_fallback(&mut self) -> Result<u32, ParseError> {
+        let (value, end) = fallback::parse_field(self.buf, self.start, self.separator, self.eol)?;
+        self.start = end + 1;
+        Ok(value)
+    }
+
+    /// Moves to the next field, loading a new chunk with `load` only when the
+    /// previous one has no terminators left, and returns the offset and the
+    /// length of the field, already validated.
+    ///
+    /// Returns `None` when the field must be parsed by the fallback
+    /// implementation, either because the buffer is too short or because the
+    /// field is longer than a whole chunk.
+    #[cfg(any(
+        all(
+            target_arch = "x86_64",
+            any(
+                feature = "backend-sse41",
+                feature = "backend-sse42",
+                feature = "backend-avx2",
+                feature = "backend-sse2"
+            )
+        ),
+        feature = "backend-portable"
+    ))]
+    #[inline(always)]
+    fn next_field(
+        &mut self,
+        vector_size: usize,
+        load: impl FnOnce(&[u8], u8, u8) -> (u32, u32),
+    ) -> Option<Result<(usize, usize), ParseError>> {
+        if self.separators == 0 {
+            if self.start + vector_size > self.buf.len() {
+                return None;
+            }
+            let (separators, non_digits) = load(&self.buf[self.start..], self.separator, self.eol);
+            if separators == 0 {
+                return None;
+            }
+            self.chunk = self.start;
+            self.separators = separators;
+            self.non_digits = non_digits;
+        }
+
+        let field = self.start - self.chunk;
+        let end = self.separators.trailing_zeros() as usize;
+        let len = end - field;
+        // clear the terminator of this field
+        self.separators &= self.separators - 1;
+        self.start = self.chunk + end + 1;
+
+        // the terminator is a non digit itself, so `invalid` is at most `len`
+        let invalid = (self.non_digits >> field).trailing_zeros() as usize;
+        if invalid < len {
+            let offset = self.chunk + field + invalid;
+            return Some(Err(ParseError::new(ParseErrorKind::InvalidDigit, offset)));
+        }
+        if len == 0 {
+            return Some(Err(ParseError::new(ParseErrorKind::Empty, self.chunk + field)));
+        }
+        Some(Ok((self.chunk + field, len)))
+    }
+
+    /// Yields the next field using AVX2 intrinsics
+    #[cfg(all(target_arch = "x86_64", feature = "backend-avx2"))]
+    #[target_feature(enable = "avx2")]
+    unsafe fn next_avx2(&mut self) -> Result<u32, ParseError> {
+        let load = |s: &[u8], sep, eol| avx::separator_and_non_digit_masks(s, sep, eol);
+        match self.next_field(avx::VECTOR_SIZE, load) {
+            Some(Ok((start, len))) => parse_field_avx2(self.buf, start, len),
+            Some(Err(e)) => Err(e),
+            None => self.next_fallback(),
+        }
+    }
+
+    /// Yields the next field using SSE4.1 intrinsics
+    #[cfg(all(
+        target_arch = "x86_64",
+        any(feature = "backend-sse41", feature = "backend-sse42")
+    ))]
+    #[target_feature(enable = "sse4.1")]
+    unsafe fn next_sse41(&mut self) -> Result<u32, ParseError> {
+        let load = |s: &[u8], sep, eol| sse41::separator_and_non_digit_masks(s, sep, eol);
+        match self.next_field(sse41::VECTOR_SIZE, load) {
+            Some(Ok((start, len))) => parse_field_sse41(self.buf, start, len),
+            Some(Err(e)) => Err(e),
+            None => self.next_fallback(),
+        }
+    }
+
+    /// Yields the next field using SSE2 intrinsics
+    #[cfg(all(target_arch = "x86_64", feature = "backend-sse2"))]
+    #[target_feature(enable = "sse2")]
+    unsafe fn next_sse2(&mut self) -> Result<u32, ParseError> {
+        let load = |s: &[u8], sep, eol| sse2::separator_and_non_digit_masks(s, sep, eol);
+        match self.next_field(sse2::VECTOR_SIZE, load) {
+            Some(Ok((start, len))) => crate::parse_validated_field_sse2(self.buf, start, len),
+            Some(Err(e)) => Err(e),
+            None => self.next_fallback(),
+        }
+    }
+
+    /// Yields the next field using portable SIMD
+    #[cfg(feature = "backend-portable")]
+    fn next_portable(&mut self) -> Result<u32, ParseError> {
+        let load = |s: &[u8], sep, eol| portable::separator_and_non_digit_masks(s, sep, eol);
+        match self.next_field(portable::VECTOR_SIZE, load) {
+            Some(Ok((start, len))) => crate::parse_validated_field_portable(self.buf, start, len),
+            Some(Err(e)) => Err(e),
+            None => self.next_fallback(),
+        }
+    }
+}
+
+impl Iterator for IntegerIter<'_> {
+    type Item = Result<u32, ParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.start >= self.buf.len() {
+            return None;
+        }
+        let result = match self.backend {
+            #[cfg(all(target_arch = "x86_64", feature = "backend-avx2"))]
+            Backend::Avx2 => unsafe { self.next_avx2() },
+            #[cfg(all(
+                target_arch = "x86_64",
+                any(feature = "backend-sse41", feature = "backend-sse42")
+            ))]
+            Backend::Sse42 | Backend::Sse41 => unsafe { self.next_sse41() },
+            #[cfg(all(target_arch = "x86_64", feature = "backend-sse2"))]
+            Backend::Sse2 => unsafe { self.next_sse2() },
+            #[cfg(feature = "backend-portable")]
+            Backend::Portable => self.next_portable(),
+            _ => self.next_fallback(),
+        };
+        self.failed = result.is_err();
+        Some(result)
+    }
+}
+
+impl FusedIterator for IntegerIter<'_> {}
+
+/// Parses the `len` validated digits of `buf` starting at offset `start`, with
+/// the AVX2 kernel when 32 chars can be loaded
+#[inline]
+#[cfg(all(target_arch = "x86_64", feature = "backend-avx2"))]
+#[target_feature(enable = "avx2")]
+unsafe fn parse_field_avx2(buf: &[u8], start: usize, len: usize) -> Result<u32, ParseError> {
+    let s = &buf[start..];
+    if s.len() < avx::VECTOR_SIZE {
+        return crate::parse_validated_field(buf, start, len);
+    }
+    Ok(match len {
+        1..=9 => avx::parse_digits_simd(s, len) as u32,
+        // the number may overflow
+        _ => return crate::parse_validated_field(buf, start, len),
+    })
+}
+
+/// Parses the `len` validated digits of `buf` starting at offset `start`, with
+/// the SSE4.1 kernel when 16 chars can be loaded
+#[inline]
+#[cfg(all(
+    target_arch = "x86_64",
+    any(feature = "backend-sse41", feature = "backend-sse42")
+))]
+#[target_feature(enable = "sse4.1")]
+unsafe fn parse_field_sse41(buf: &[u8], start: usize, len: usize) -> Result<u32, ParseError> {
+    let s = &buf[start..];
+    if s.len() < sse41::VECTOR_SIZE {
+        return crate::parse_validated_field(buf, start, len);
+    }
+    Ok(match len {
+        1..=9 => sse41::parse_digits_simd(s, len) as u32,
+        // the number may overflow
+        _ => return crate::parse_validated_field(buf, start, len),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ParseErrorKind;
+    static SEP: u8 = b',';
+    static EOL: u8 = b'\n';
+
+    #[test]
+    fn integer_iter_short_buffer() {
+        let values: Vec<_> = IntegerIter::new(b"1,2\n3", SEP, EOL).collect();
+        assert_eq!(values, vec![Ok(1), Ok(2), Ok(3)]);
+    }
+
+    #[test]
+    fn integer_iter_empty_buffer() {
+        assert_eq!(IntegerIter::new(b"", SEP, EOL).next(), None);
+    }
+
+    #[test]
+    fn integer_iter_many_fields_per_chunk() {
+        let s = b"1,2,3,4,5,6,7,8,9,10,11,12,13,14,15,16,17,18,19,20,21,22,23,24,25\n";
+        let values: Vec<_> = IntegerIter::new(s, SEP, EOL).map(Result::unwrap).collect();
+        assert_eq!(values, (1..=25).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn integer_iter_long_field() {
+        let s = b"7,0000000000000000000000000000000000000123,8";
+        let values: Vec<_> = IntegerIter::new(s, SEP, EOL).collect();
+        assert_eq!(values, vec![Ok(7), Ok(123), Ok(8)]);
+    }
+
+    #[test]
+    fn integer_iter_stops_after_error() {
+        let s = b"12,34,x6,78,90,12,34,56,78,90,12,34\n";
+        let mut iter = IntegerIter::new(s, SEP, EOL);
+        assert_eq!(iter.next(), Some(Ok(12)));
+        assert_eq!(iter.next(), Some(Ok(34)));
+        assert_eq!(
+            iter.next(),
+            Some(Err(ParseError::new(ParseErrorKind::InvalidDigit, 6)))
+        );
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn integer_iter_overflow() {
+        let s = b"12,34,56,78,90,12,34,99999999999,1\n";
+        let values: Vec<_> = IntegerIter::new(s, SEP, EOL).collect();
+        assert_eq!(
+            values.last(),
+            Some(&Err(ParseError::new(ParseErrorKind::Overflow, 30)))
+        );
+    }
+}
@@ -1,16 +1,88 @@
 //! SIMD implementations for parsing an u32 from a string
 
 #![feature(stdsimd)]
+#![cfg_attr(feature = "backend-portable", feature(portable_simd))]
 #![deny(missing_docs)]
 #![deny(clippy::missing_docs_in_private_items)]
 #![deny(clippy::missing_safety_doc)]
 #![warn(rustdoc::missing_doc_code_examples)]
 #![warn(clippy::todo)]
 
+#[macro_use]
+mod dispatch;
+
+#[cfg(all(target_arch = "x86_64", feature = "backend-avx2"))]
 pub mod avx;
+mod backend;
+mod error;
 pub mod fallback;
+mod iter;
+mod padded;
+#[cfg(any(
+    all(
+        target_arch = "x86_64",
+        any(
+            feature = "backend-sse41",
+            feature = "backend-sse42",
+            feature = "backend-avx2",
+            feature = "backend-sse2"
+        )
+    ),
+    feature = "backend-portable"
+))]
+mod page;
+mod parser;
+#[cfg(any(
+    all(
+        target_arch = "x86_64",
+        any(
+            feature = "backend-sse41",
+            feature = "backend-sse42",
+            feature = "backend-avx2",
+            feature = "backend-sse2"
+        )
+    ),
+    feature = "backend-portable"
+))]
+mod scan;
+mod separators;
+#[cfg(feature = "backend-portable")]
+pub mod portable;
+#[cfg(all(target_arch = "x86_64", feature = "backend-sse2"))]
+pub mod sse2;
+// the SSE4.1 kernels are shared by the SSE4.1, SSE4.2 and AVX2 backends
+#[cfg(all(
+    target_arch = "x86_64",
+    any(feature = "backend-sse41", feature = "backend-sse42", feature = "backend-avx2")
+))]
 pub mod sse41;
+// the separator sets of the AVX2 backend are scanned with the SSE4.2 string
+// instructions
+#[cfg(all(target_arch = "x86_64", any(feature = "backend-sse42", feature = "backend-avx2")))]
 pub mod sse42;
+mod traits;
+
+pub use backend::{detected_backend, Backend, UnknownBackend, UnsupportedBackend};
+pub use error::{ParseError, ParseErrorKind};
+pub use iter::IntegerIter;
+pub use padded::{InsufficientPadding, PaddedInput, PADDING};
+pub use parser::{Avx2, Dynamic, Fallback, Parser, ParserBackend, Portable, Sse2, Sse41, Sse42};
+pub use separators::{SeparatorSet, TooManySeparators, MAX_SEPARATORS};
+pub use traits::{SimdParse, SimdParseExt};
+
+#[cfg(any(
+    all(
+        target_arch = "x86_64",
+        any(
+            feature = "backend-sse41",
+            feature = "backend-sse42",
+            feature = "backend-avx2",
+            feature = "backend-sse2"
+        )
+    ),
+    feature = "backend-portable"
+))]
+use std::convert::TryFrom;
 
 // -----------------------------------------------------------------------------
 //                         Dispatchers for the library API
@@ -19,238 +91,259 @@ pub mod sse42;
 // runtime the best implementation available based on the underlying CPU.
 // 
 // All the methods follow the same structure:
-//   1. Declare a function pointer, initialized with the dispatcher function;
+//   1. Declare a function pointer with `atomic_fn!`, initialized with the
+//      dispatcher function;
 //   2. Implement the dispatcher function;
 //   3. Declare the main API function, which will call the function pointer
 //      declared in step 1.
 //
 // When the API function is called for the first time, it will call the
 // dispatcher function: internally, it will reassign the function pointer to the
-// detected implementation of the current architecture.
+// implementation of `detected_backend()`, taken from the backend tables in
+// `backend.rs`. The pointers are atomic, so more threads can go through the
+// dispatcher at the same time: they all store the same implementation.
 // -----------------------------------------------------------------------------
 
-/// Holds the pointer to the function supported by the underlying CPU
-static mut LAST_BYTE_DIGIT_SEP: unsafe fn(&str, u8, u8) -> u32 = last_byte_digit_dispatcher;
+atomic_fn! {
+    /// Holds the pointer to the function supported by the underlying CPU
+    static LAST_BYTE_DIGIT_SEP: unsafe fn(&[u8], u8, u8) -> u32 = last_byte_digit_dispatcher;
+}
 
 /// Implements a single dispatch method to assign the appropriate function to the
 /// global variable LAST_BYTE_DIGIT
-fn last_byte_digit_dispatcher(s: &str, separator: u8, eol: u8) -> u32 {
-    #[cfg(target_arch = "x86_64")]
-    {
-        if is_x86_feature_detected!("avx2") {
-            // repelace the global variable with the pointer to the sse42
-            // function
-            unsafe {
-                LAST_BYTE_DIGIT_SEP = avx::last_byte_without_separator;
-                return avx::last_byte_without_separator(s, separator, eol);
-            }
-        }
-        if is_x86_feature_detected!("sse4.2") {
-            // repelace the global variable with the pointer to the sse42
-            // function
-            unsafe {
-                LAST_BYTE_DIGIT_SEP = sse42::last_byte_without_separator;
-                return sse42::last_byte_without_separator(s, separator, eol);
-            }
-        }
-        if is_x86_feature_detected!("sse4.1") {
-            // repelace the global variable with the pointer to the sse41
-            // function
-            unsafe {
-                LAST_BYTE_DIGIT_SEP = sse41::last_byte_without_separator;
-                return sse41::last_byte_without_separator(s, separator, eol);
-            }
-        }
-    }
-
-    unsafe {
-        LAST_BYTE_DIGIT_SEP = fallback::last_byte_without_separator;
-    }
-    fallback::last_byte_without_separator(s, separator, eol)
+fn last_byte_digit_dispatcher(s: &[u8], separator: u8, eol: u8) -> u32 {
+    let f = detected_backend().functions().last_byte_without_separator;
+    LAST_BYTE_DIGIT_SEP.set(f);
+    unsafe { f(s, separator, eol) }
 }
 
 /// Returns the index of the last char in the string different from `separator`
 /// and `eol`
+///
+/// Only the first vector of the string is scanned, so the result for a string
+/// without any separator in its first 16 chars depends on the backend:
+/// `find_separator` scans the whole string.
+#[inline]
 pub fn last_byte_without_separator(s: &str, separator: u8, eol: u8) -> u32 {
-    unsafe { LAST_BYTE_DIGIT_SEP(s, separator, eol) }
+    last_byte_without_separator_bytes(s.as_bytes(), separator, eol)
 }
 
-/// Pointer to `last_digit_byte` supported by the underlying cpu
-static mut LAST_DIGIT_BYTE: unsafe fn(&str) -> u32 = last_digit_byte_dispatcher;
+/// Same as `last_byte_without_separator`, but reads the input from a byte
+/// slice.
+#[inline]
+pub fn last_byte_without_separator_bytes(s: &[u8], separator: u8, eol: u8) -> u32 {
+    unsafe { dispatch!(LAST_BYTE_DIGIT_SEP, last_byte_without_separator(s, separator, eol)) }
+}
+
+atomic_fn! {
+    /// Pointer to `last_digit_byte` supported by the underlying cpu
+    static LAST_DIGIT_BYTE: unsafe fn(&[u8]) -> u32 = last_digit_byte_dispatcher;
+}
 
 /// Implements a single dispatch method to assign the appropiate function to the
 /// global variable LAST_DIGIT_BYTE
-fn last_digit_byte_dispatcher(s: &str) -> u32 {
-    #[cfg(target_arch = "x86_64")]
-    {
-        if is_x86_feature_detected!("avx2") {
-            // repelace the global variable with the pointer to the sse42 function
-            unsafe {
-                LAST_DIGIT_BYTE = avx::last_digit_byte;
-                return avx::last_digit_byte(s);
-            }
-        }
-        if is_x86_feature_detected!("sse4.2") {
-            // repelace the global variable with the pointer to the sse42 function
-            unsafe {
-                LAST_DIGIT_BYTE = sse42::last_digit_byte;
-                return sse42::last_digit_byte(s);
-            }
-        }
-        if is_x86_feature_detected!("sse4.1") {
-            // repelace the global variable with the pointer to the sse41
-            // function
-            unsafe {
-                LAST_DIGIT_BYTE = sse41::last_digit_byte;
-                return sse41::last_digit_byte(s);
-            }
-        }
-    }
-    // fallback implementation
-    unsafe {
-        LAST_DIGIT_BYTE = fallback::last_digit_byte;
-    }
-    fallback::last_digit_byte(s)
+fn last_digit_byte_dispatcher(s: &[u8]) -> u32 {
+    let f = detected_backend().functions().last_digit_byte;
+    LAST_DIGIT_BYTE.set(f);
+    unsafe { f(s) }
 }
 
 /// Returns the index of the last digit in the string
+///
+/// Only the first vector of the string is scanned, so the result for a string
+/// starting with 16 digits depends on the backend: `first_non_digit` scans the
+/// whole string.
+#[inline]
 pub fn last_digit_byte(s: &str) -> u32 {
-    unsafe { LAST_DIGIT_BYTE(s) }
+    last_digit_byte_bytes(s.as_bytes())
+}
+
+/// Same as `last_digit_byte`, but reads the input from a byte slice.
+#[inline]
+pub fn last_digit_byte_bytes(s: &[u8]) -> u32 {
+    unsafe { dispatch!(LAST_DIGIT_BYTE, last_digit_byte(s)) }
+}
+
+atomic_fn! {
+    /// Pointer to `first_non_digit` supported by the underlying cpu
+    static FIRST_NON_DIGIT: unsafe fn(&[u8]) -> Option<usize> = first_non_digit_dispatcher;
+}
+
+/// Implements a single dispatch method to assign the appropiate function to the
+/// global variable FIRST_NON_DIGIT
+fn first_non_digit_dispatcher(s: &[u8]) -> Option<usize> {
+    let f = detected_backend().functions().first_non_digit;
+    FIRST_NON_DIGIT.set(f);
+    unsafe { f(s) }
+}
+
+/// Returns the offset of the first char of the string which isn't an ASCII
+/// digit, or None if they are all digits.
+///
+/// Unlike `last_digit_byte`, the whole string is scanned, so the result is the
+/// same on every backend.
+///
+/// ```
+/// use simd_parsing::first_non_digit;
+///
+/// assert_eq!(first_non_digit("12345678901234567890x"), Some(20));
+/// assert_eq!(first_non_digit("12345678901234567890"), None);
+/// ```
+#[inline]
+pub fn first_non_digit(s: &str) -> Option<usize> {
+    first_non_digit_bytes(s.as_bytes())
+}
+
+/// Same as `first_non_digit`, but reads the input from a byte slice.
+#[inline]
+pub fn first_non_digit_bytes(s: &[u8]) -> Option<usize> {
+    unsafe { dispatch!(FIRST_NON_DIGIT, first_non_digit(s)) }
+}
+
+atomic_fn! {
+    /// Pointer to `find_separator` supported by the underlying cpu
+    static FIND_SEPARATOR: unsafe fn(&[u8], u8, u8) -> Option<usize> = find_separator_dispatcher;
+}
+
+/// Implements a single dispatch method to assign the appropiate function to the
+/// global variable FIND_SEPARATOR
+fn find_separator_dispatcher(s: &[u8], separator: u8, eol: u8) -> Option<usize> {
+    let f = detected_backend().functions().find_separator;
+    FIND_SEPARATOR.set(f);
+    unsafe { f(s, separator, eol) }
+}
+
+/// Returns the offset of the first occurrence of `separator` or `eol` in the
+/// string, or None if there is none.
+///
+/// Unlike `last_byte_without_separator`, the whole string is scanned, so the
+/// result is the same on every backend.
+///
+/// ```
+/// use simd_parsing::find_separator;
+///
+/// assert_eq!(find_separator("1,2", b',', b'\n'), Some(1));
+/// assert_eq!(find_separator("12345678901234567890\n", b',', b'\n'), Some(20));
+/// assert_eq!(find_separator("12", b',', b'\n'), None);
+/// ```
+#[inline]
+pub fn find_separator(s: &str, separator: u8, eol: u8) -> Option<usize> {
+    find_separator_bytes(s.as_bytes(), separator, eol)
+}
+
+/// Same as `find_separator`, but reads the input from a byte slice.
+#[inline]
+pub fn find_separator_bytes(s: &[u8], separator: u8, eol: u8) -> Option<usize> {
+    unsafe { dispatch!(FIND_SEPARATOR, find_separator(s, separator, eol)) }
 }
 
-/// Pointer to `check_all_chars_are_valid` function supported by the underlying
-/// cpu
-static mut CHECK_CHARS: unsafe fn(&str) -> bool = check_chars_dispatcher;
+atomic_fn! {
+    /// Pointer to `check_all_chars_are_valid` function supported by the underlying
+    /// cpu
+    static CHECK_CHARS: unsafe fn(&[u8]) -> bool = check_chars_dispatcher;
+}
 
 /// Implements a single dispatch method to assign the appropiate function to the
 /// global variable CHECK_CHARS
-fn check_chars_dispatcher(s: &str) -> bool {
-    #[cfg(target_arch = "x86_64")]
-    {
-        if is_x86_feature_detected!("avx2") {
-            unsafe {
-                CHECK_CHARS = avx::check_all_chars_are_valid;
-                return avx::check_all_chars_are_valid(s);
-            }
-        }
-        if is_x86_feature_detected!("sse4.2") {
-            unsafe {
-                CHECK_CHARS = sse42::check_all_chars_are_valid;
-                return sse42::check_all_chars_are_valid(s);
-            }
-        }
-        if is_x86_feature_detected!("sse4.1") {
-            unsafe {
-                CHECK_CHARS = sse41::check_all_chars_are_valid;
-                return sse41::check_all_chars_are_valid(s);
-            }
-        }
-    }
-    // fallback implementation
-    unsafe {
-        CHECK_CHARS = fallback::check_all_chars_are_valid;
-    }
-    fallback::check_all_chars_are_valid(s)
+fn check_chars_dispatcher(s: &[u8]) -> bool {
+    let f = detected_backend().functions().check_all_chars_are_valid;
+    CHECK_CHARS.set(f);
+    unsafe { f(s) }
 }
 
 /// Deteremines if the string in made of all numbers
+///
+/// Every char of the string is checked, on every backend; `first_non_digit`
+/// returns the offset of the first invalid one.
+///
+/// ```
+/// use simd_parsing::{check_all_chars_are_valid, first_non_digit};
+///
+/// let s = "1234567890123456789012345678901234x67890";
+/// assert!(!check_all_chars_are_valid(s));
+/// assert_eq!(first_non_digit(s), Some(34));
+/// ```
+#[inline]
 pub fn check_all_chars_are_valid(s: &str) -> bool {
-    unsafe { CHECK_CHARS(s) }
+    check_all_chars_are_valid_bytes(s.as_bytes())
+}
+
+/// Same as `check_all_chars_are_valid`, but reads the input from a byte slice.
+#[inline]
+pub fn check_all_chars_are_valid_bytes(s: &[u8]) -> bool {
+    unsafe { dispatch!(CHECK_CHARS, check_all_chars_are_valid(s)) }
 }
 
-/// Pointer to `parse_integer` supperted by the underlying CPU
-static mut PARSE_INTEGER: unsafe fn(&str) -> Option<u32> = parse_integer_checked_dispatcher;
+atomic_fn! {
+    /// Pointer to `parse_integer` supperted by the underlying CPU
+    static PARSE_INTEGER: unsafe fn(&[u8]) -> Option<u32> = parse_integer_checked_dispatcher;
+}
 
 /// Assigns the correct implementation to `PARSE_INTEGER` according to the
 /// underlying cpu
-fn parse_integer_checked_dispatcher(s: &str) -> Option<u32> {
-    #[cfg(target_arch = "x86_64")]
-    {
-        if is_x86_feature_detected!("avx2") {
-            unsafe {
-                PARSE_INTEGER = parse_integer_checked_avx2;
-                return parse_integer_checked_avx2(s);
-            }
-        }
-        if is_x86_feature_detected!("sse4.1") {
-            unsafe {
-                PARSE_INTEGER = parse_integer_checked_sse41;
-                return parse_integer_checked_sse41(s);
-            }
-        }
-    }
-    // fallback implementation
-    unsafe {
-        PARSE_INTEGER = fallback::parse_integer;
-    }
-    fallback::parse_integer(s)
+fn parse_integer_checked_dispatcher(s: &[u8]) -> Option<u32> {
+    let f = detected_backend().functions().parse_integer;
+    PARSE_INTEGER.set(f);
+    unsafe { f(s) }
 }
 
 /// Parses an `u32` from the input string.
 ///
 /// In case of empty string or arithmetic overflow, it will return None.
+#[inline]
 pub fn parse_integer(s: &str) -> Option<u32> {
-    unsafe { PARSE_INTEGER(s) }
+    parse_integer_bytes(s.as_bytes())
 }
 
-/// Pointer to `parse_integer` supported by the underlying CPU
-static mut PARSE_INTEGER_SEP: unsafe fn(&str, u8, u8) -> Option<u32> =
-    parse_integer_sep_checked_dispatcher;
+/// Same as `parse_integer`, but reads the input from a byte slice.
+#[inline]
+pub fn parse_integer_bytes(s: &[u8]) -> Option<u32> {
+    unsafe { dispatch!(PARSE_INTEGER, parse_integer(s)) }
+}
+
+atomic_fn! {
+    /// Pointer to `parse_integer` supported by the underlying CPU
+    static PARSE_INTEGER_SEP: unsafe fn(&[u8], u8, u8) -> Option<u32> =
+        parse_integer_sep_checked_dispatcher;
+}
 
 /// Assigns the correct implementation to `PARSE_INTEGER` according to the
 /// underlying cpu
-fn parse_integer_sep_checked_dispatcher(s: &str, sep: u8, eol: u8) -> Option<u32> {
-    #[cfg(target_arch = "x86_64")]
-    {
-        if is_x86_feature_detected!("avx2") {
-            unsafe {
-                PARSE_INTEGER_SEP = parse_integer_sep_checked_avx2;
-                return parse_integer_sep_checked_avx2(s, sep, eol);
-            }
-        }
-        if is_x86_feature_detected!("sse4.1") {
-            unsafe {
-                PARSE_INTEGER_SEP = parse_integer_sep_checked_sse41;
-                return parse_integer_sep_checked_sse41(s, sep, eol);
-            }
-        }
-    }
-    // fallback implementation
-    unsafe {
-        PARSE_INTEGER_SEP = fallback::parse_integer_separator;
-    }
-    fallback::parse_integer_separator(s, sep, eol)
+fn parse_integer_sep_checked_dispatcher(s: &[u8], sep: u8, eol: u8) -> Option<u32> {
+    let f = detected_backend().functions().parse_integer_separator;
+    PARSE_INTEGER_SEP.set(f);
+    unsafe { f(s, sep, eol) }
 }
 
 /// Parses an `u32` from the input string up to the first occurrence of
 /// `separator` or `eol`.
 ///
 /// In case of empty string, arithmetic overflow or absence of number to parse,
-/// it will return None.
+/// it will return None. The same happens if any char before the terminator, or
+/// before the end of the string when there is none, isn't a digit: every
+/// backend validates the whole field, no matter how long it is.
+#[inline]
 pub fn parse_integer_separator(s: &str, separator: u8, eol: u8) -> Option<u32> {
-    unsafe { PARSE_INTEGER_SEP(s, separator, eol) }
+    parse_integer_separator_bytes(s.as_bytes(), separator, eol)
+}
+
+/// Same as `parse_integer_separator`, but reads the input from a byte slice.
+#[inline]
+pub fn parse_integer_separator_bytes(s: &[u8], separator: u8, eol: u8) -> Option<u32> {
+    unsafe { dispatch!(PARSE_INTEGER_SEP, parse_integer_separator(s, separator, eol)) }
 }
 
-/// Pointer to `parse_integer_separator` supported by the underlying CPU
-static mut PARSE_INTEGER_SEP_UN: unsafe fn(&str, u8, u8) -> u32 = parse_integer_sep_dispatcher;
+atomic_fn! {
+    /// Pointer to `parse_integer_separator` supported by the underlying CPU
+    static PARSE_INTEGER_SEP_UN: unsafe fn(&[u8], u8, u8) -> u32 = parse_integer_sep_dispatcher;
+}
 
 /// Assigns the correct implementation to the global variable
 /// PARSE_INTEGER_SEP_UN
-unsafe fn parse_integer_sep_dispatcher(s: &str, separator: u8, eol: u8) -> u32 {
-    #[cfg(target_arch = "x86_64")]
-    {
-        if is_x86_feature_detected!("avx2") {
-            PARSE_INTEGER_SEP_UN = parse_integer_separator_avx2;
-            return parse_integer_separator_avx2(s, separator, eol);
-        }
-        if is_x86_feature_detected!("sse4.1") {
-            PARSE_INTEGER_SEP_UN = parse_integer_separator_sse41;
-            return parse_integer_separator_sse41(s, separator, eol);
-        }
-    }
-    // fallback implementation
-    PARSE_INTEGER_SEP_UN = fallback::parse_integer_separator_unchecked;
-    fallback::parse_integer_separator_unchecked(s, separator, eol)
+unsafe fn parse_integer_sep_dispatcher(s: &[u8], separator: u8, eol: u8) -> u32 {
+    let f = detected_backend().functions().parse_integer_separator_unchecked;
+    PARSE_INTEGER_SEP_UN.set(f);
+    f(s, separator, eol)
 }
 
 /// Parses an integer from the given string until a separator is found
@@ -267,28 +360,31 @@ unsafe fn parse_integer_sep_dispatcher(s: &str, separator: u8, eol: u8) -> u32 {
 /// be thrown.
 #[inline]
 pub unsafe fn parse_integer_separator_unchecked(s: &str, separator: u8, eol: u8) -> u32 {
-    PARSE_INTEGER_SEP_UN(s, separator, eol)
+    parse_integer_separator_unchecked_bytes(s.as_bytes(), separator, eol)
+}
+
+/// Same as `parse_integer_separator_unchecked`, but reads the input from a byte
+/// slice.
+///
+/// # Safety
+///
+/// See `parse_integer_separator_unchecked`.
+#[inline]
+pub unsafe fn parse_integer_separator_unchecked_bytes(s: &[u8], separator: u8, eol: u8) -> u32 {
+    dispatch!(PARSE_INTEGER_SEP_UN, parse_integer_separator_unchecked(s, separator, eol))
 }
 
-/// Pointer to `parse_integer_unchecked` function for the underlying CPU
-static mut PARSE_INTEGER_UN: unsafe fn(&str) -> u32 = parse_integer_dispatcher;
+atomic_fn! {
+    /// Pointer to `parse_integer_unchecked` function for the underlying CPU
+    static PARSE_INTEGER_UN: unsafe fn(&[u8]) -> u32 = parse_integer_dispatcher;
+}
 
 /// Assigns the correct implementation to PARSE_INTEGER_UN variable based on the
 /// the underlying CPU
-unsafe fn parse_integer_dispatcher(s: &str) -> u32 {
-    #[cfg(target_arch = "x86_64")]
-    {
-        if is_x86_feature_detected!("avx2") {
-            PARSE_INTEGER_UN = parse_integer_avx2;
-            return parse_integer_avx2(s);
-        }
-        if is_x86_feature_detected!("sse4.1") {
-            PARSE_INTEGER_UN = parse_integer_sse41;
-            return parse_integer_sse41(s);
-        }
-    }
-    PARSE_INTEGER_UN = fallback::parse_integer_unchecked;
-    fallback::parse_integer_unchecked(s)
+unsafe fn parse_integer_dispatcher(s: &[u8]) -> u32 {
+    let f = detected_backend().functions().parse_integer_unchecked;
+    PARSE_INTEGER_UN.set(f);
+    f(s)
 }
 
 /// Parses an integer from the given string
@@ -306,575 +402,2684 @@ unsafe fn parse_integer_dispatcher(s: &str) -> u32 {
 /// they will be parsed as regular digit, invalidating the final number.
 #[inline]
 pub unsafe fn parse_integer_unchecked(s: &str) -> u32 {
-    PARSE_INTEGER_UN(s)
+    parse_integer_unchecked_bytes(s.as_bytes())
 }
 
+/// Same as `parse_integer_unchecked`, but reads the input from a byte slice.
+///
+/// # Safety
+///
+/// See `parse_integer_unchecked`.
+#[inline]
+pub unsafe fn parse_integer_unchecked_bytes(s: &[u8]) -> u32 {
+    dispatch!(PARSE_INTEGER_UN, parse_integer_unchecked(s))
+}
 
-// -----------------------------------------------------------------------------
-//          Internal implementations of architecture specific functions.
-// -----------------------------------------------------------------------------
+atomic_fn! {
+    /// Pointer to `parse_u64` supported by the underlying CPU
+    static PARSE_U64: unsafe fn(&[u8]) -> Option<u64> = parse_u64_checked_dispatcher;
+}
 
-/// Parses and `u32` from the input string when possibile using AVX2 instrinics.
+/// Assigns the correct implementation to `PARSE_U64` according to the
+/// underlying cpu
+fn parse_u64_checked_dispatcher(s: &[u8]) -> Option<u64> {
+    let f = detected_backend().functions().parse_u64;
+    PARSE_U64.set(f);
+    unsafe { f(s) }
+}
+
+/// Parses an `u64` from the input string.
 ///
-/// If the input string is empty or the parsing function encounters an overflow,
-/// then `None` will be returned.
+/// In case of empty string or arithmetic overflow, it will return None.
 #[inline]
-unsafe fn parse_integer_checked_avx2(s: &str) -> Option<u32> {
-    // Go back to fallback implementation if the string doesn't have the correct
-    // size
-    if s.len() < avx::VECTOR_SIZE {
-        return fallback::parse_integer(s);
-    }
+pub fn parse_u64(s: &str) -> Option<u64> {
+    parse_u64_bytes(s.as_bytes())
+}
 
-    let index = avx::last_digit_byte(s);
-    match index {
-        8 => Some(avx::parse_8_chars_simd(s)),
-        9 => Some(avx::parse_9_chars_simd(s)),
-        7 => Some(avx::parse_7_chars_simd(s)),
-        6 => Some(avx::parse_6_chars_simd(s)),
-        5 => Some(avx::parse_5_chars_simd(s)),
-        4 => Some(avx::parse_4_chars_simd(s)),
-        1..=3 => Some(fallback::parse_byte_iterator_limited(s, index)),
-        // Use the default implementation since we cannot guarantee overflow
-        // check in the SIMD implementations. This also holds for padded
-        // strings.
-        _ => fallback::parse_integer(s),
-    }
+/// Same as `parse_u64`, but reads the input from a byte slice.
+#[inline]
+pub fn parse_u64_bytes(s: &[u8]) -> Option<u64> {
+    unsafe { dispatch!(PARSE_U64, parse_u64(s)) }
+}
+
+atomic_fn! {
+    /// Pointer to `parse_u64_separator` supported by the underlying CPU
+    static PARSE_U64_SEP: unsafe fn(&[u8], u8, u8) -> Option<u64> =
+        parse_u64_sep_checked_dispatcher;
 }
 
-/// Parses and `u32` from the input string when possible using AVX2 intrinsics.
+/// Assigns the correct implementation to `PARSE_U64_SEP` according to the
+/// underlying cpu
+fn parse_u64_sep_checked_dispatcher(s: &[u8], sep: u8, eol: u8) -> Option<u64> {
+    let f = detected_backend().functions().parse_u64_separator;
+    PARSE_U64_SEP.set(f);
+    unsafe { f(s, sep, eol) }
+}
+
+/// Parses an `u64` from the input string up to the first occurrence of
+/// `separator` or `eol`.
 ///
-/// If the input string is empty or the parsing function encounters an overflow,
-/// then `None` will be returned.
+/// In case of empty string, arithmetic overflow or absence of number to parse,
+/// it will return None. The same happens if any char before the terminator
+/// isn't a digit.
 #[inline]
-unsafe fn parse_integer_checked_sse41(s: &str) -> Option<u32> {
-    // Go back to fallback implementation if the string doesn't have the correct
-    // size
-    if s.len() < sse41::VECTOR_SIZE {
-        return fallback::parse_integer(s);
-    }
-    let index = sse41::last_digit_byte(s);
-    match index {
-        8 => Some(sse41::parse_8_chars_simd(s)),
-        9 => Some(sse41::parse_9_chars_simd(s)),
-        7 => Some(sse41::parse_7_chars_simd(s)),
-        6 => Some(sse41::parse_6_chars_simd(s)),
-        5 => Some(sse41::parse_5_chars_simd(s)),
-        4 => Some(sse41::parse_4_chars_simd(s)),
-        1..=3 => Some(fallback::parse_byte_iterator_limited(s, index)),
-        // Use the default implementation since we cannot guarantee overflow
-        // check in the SIMD implementations. This also holds for padded
-        // strings.
-        _ => fallback::parse_integer(s),
-    }
+pub fn parse_u64_separator(s: &str, separator: u8, eol: u8) -> Option<u64> {
+    parse_u64_separator_bytes(s.as_bytes(), separator, eol)
 }
 
-/// Parses and `u32` from the input string when possibile using AVX2 instrinics.
-///
-/// If the input string is empty or the parsing function encounters an overflow,
-/// then `None` will be returned.
+/// Same as `parse_u64_separator`, but reads the input from a byte slice.
 #[inline]
-unsafe fn parse_integer_sep_checked_avx2(s: &str, sep: u8, eol: u8) -> Option<u32> {
-    // Go back to fallback implementation if the string doesn't have the correct
-    // size
-    if s.len() < avx::VECTOR_SIZE {
-        return fallback::parse_integer_separator(s, sep, eol);
-    }
-    let index = avx::last_byte_without_separator(s, sep, eol);
-    match index {
-        8 => Some(avx::parse_8_chars_simd(s)),
-        9 => Some(avx::parse_9_chars_simd(s)),
-        7 => Some(avx::parse_7_chars_simd(s)),
-        6 => Some(avx::parse_6_chars_simd(s)),
-        5 => Some(avx::parse_5_chars_simd(s)),
-        4 => Some(avx::parse_4_chars_simd(s)),
-        1..=3 => Some(fallback::parse_byte_iterator_limited(s, index)),
-        // Use the default implementation since we cannot guarantee overflow
-        // check in the SIMD implementations
-        _ => fallback::parse_integer_separator(s, sep, eol),
-    }
+pub fn parse_u64_separator_bytes(s: &[u8], separator: u8, eol: u8) -> Option<u64> {
+    unsafe { dispatch!(PARSE_U64_SEP, parse_u64_separator(s, separator, eol)) }
+}
+
+
+atomic_fn! {
+    /// Pointer to `try_parse_integer` supported by the underlying CPU
+    static TRY_PARSE_INTEGER: unsafe fn(&[u8]) -> Result<u32, ParseError> =
+        try_parse_integer_dispatcher;
+}
+
+/// Assigns the correct implementation to `TRY_PARSE_INTEGER` according to the
+/// underlying cpu
+fn try_parse_integer_dispatcher(s: &[u8]) -> Result<u32, ParseError> {
+    let f = detected_backend().functions().try_parse_integer;
+    TRY_PARSE_INTEGER.set(f);
+    unsafe { f(s) }
 }
 
-/// Parses and `u32` from the input string when possible using AVX2 intrinsics.
+/// Parses an `u32` from the input string as far as there are digits.
 ///
-/// If the input string is empty or the parsing function encounters an overflow,
-/// then `None` will be returned.
+/// Unlike `parse_integer`, the error tells why the parsing failed and the
+/// offset of the offending byte: `ParseErrorKind::Empty` for an empty string,
+/// `ParseErrorKind::InvalidDigit` if it doesn't start with a digit and
+/// `ParseErrorKind::Overflow` if the number doesn't fit in an `u32`.
 #[inline]
-unsafe fn parse_integer_sep_checked_sse41(s: &str, sep: u8, eol: u8) -> Option<u32> {
-    // Go back to fallback implementation if the string doesn't have the correct
-    // size
-    if s.len() < sse41::VECTOR_SIZE {
-        return fallback::parse_integer_separator(s, sep, eol);
-    }
-    let index = sse41::last_byte_without_separator(s, sep, eol);
-    match index {
-        8 => Some(sse41::parse_8_chars_simd(s)),
-        9 => Some(sse41::parse_9_chars_simd(s)),
-        7 => Some(sse41::parse_7_chars_simd(s)),
-        6 => Some(sse41::parse_6_chars_simd(s)),
-        5 => Some(sse41::parse_5_chars_simd(s)),
-        4 => Some(sse41::parse_4_chars_simd(s)),
-        1..=3 => Some(fallback::parse_byte_iterator_limited(s, index)),
-        // Use the default implementation since we cannot guarantee overflow
-        // check in the SIMD implementations
-        _ => fallback::parse_integer_separator(s, sep, eol),
-    }
+pub fn try_parse_integer(s: &str) -> Result<u32, ParseError> {
+    try_parse_integer_bytes(s.as_bytes())
 }
 
-/// Parses an u32 from the input string using AVX2 intrinsics whenever is
-/// possible
-#[cfg(target_arch = "x86_64")]
-#[target_feature(enable = "avx2")]
-unsafe fn parse_integer_avx2(s: &str) -> u32 {
-    if s.len() < avx::VECTOR_SIZE {
-        return fallback::parse_integer_unchecked(s);
-    }
-    // find the first occurence of a separator
-    let index = avx::last_digit_byte(s);
-    match index {
-        8 => avx::parse_8_chars_simd(s),
-        10 => avx::parse_10_chars_simd(s),
-        9 => avx::parse_9_chars_simd(s),
-        7 => avx::parse_7_chars_simd(s),
-        6 => avx::parse_6_chars_simd(s),
-        5 => avx::parse_5_chars_simd(s),
-        4 => avx::parse_4_chars_simd(s),
-        1..=3 => fallback::parse_byte_iterator_limited(s, index),
-        // all the chars are numeric, and they should be padded with 0s to get a
-        // correct result. If not, the parsed number will not be correct due to
-        // internal processing techniques
-        32 => avx::parse_padded_integer_simd_all_numbers(s),
-        // there is no number to parse
-        _ => panic!("No u32 to parse from input string!"),
-    }
+/// Same as `try_parse_integer`, but reads the input from a byte slice.
+#[inline]
+pub fn try_parse_integer_bytes(s: &[u8]) -> Result<u32, ParseError> {
+    unsafe { dispatch!(TRY_PARSE_INTEGER, try_parse_integer(s)) }
 }
 
-/// Parses an u32 from the input string using AVX2 intrinsics whenever is
-/// possible up to the first occurence of `separator` or `eol`
-#[cfg(target_arch = "x86_64")]
-#[target_feature(enable = "avx2")]
-unsafe fn parse_integer_separator_avx2(s: &str, separator: u8, eol: u8) -> u32 {
-    if s.len() < avx::VECTOR_SIZE {
-        return fallback::parse_integer_separator_unchecked(s, separator, eol);
-    }
-    // find the first occurence of a separator
-    let index = avx::last_byte_without_separator(s, separator, eol);
-    match index {
-        8 => avx::parse_8_chars_simd(s),
-        10 => avx::parse_10_chars_simd(s),
-        9 => avx::parse_9_chars_simd(s),
-        7 => avx::parse_7_chars_simd(s),
-        6 => avx::parse_6_chars_simd(s),
-        5 => avx::parse_5_chars_simd(s),
-        4 => avx::parse_4_chars_simd(s),
-        1..=3 => fallback::parse_byte_iterator_limited(s, index),
-        // all the chars are numeric, and they should be padded with 0s to get a
-        // correct result. If not, the parsed number will not be correct due to
-        // internal processing techniques
-        32 => avx::parse_padded_integer_simd_all_numbers(s),
-        // there is no number to parse
-        _ => panic!("No u32 to parse from input string!"),
-    }
+atomic_fn! {
+    /// Pointer to `try_parse_integer_separator` supported by the underlying CPU
+    static TRY_PARSE_INTEGER_SEP: unsafe fn(&[u8], u8, u8) -> Result<u32, ParseError> =
+        try_parse_integer_sep_dispatcher;
 }
 
-/// Parses an u32 from the input string using SSE4.1 intrinsics whenever is
-/// possible
-#[cfg(target_arch = "x86_64")]
-#[target_feature(enable = "sse4.1")]
-unsafe fn parse_integer_sse41(s: &str) -> u32 {
-    if s.len() < sse41::VECTOR_SIZE {
-        return fallback::parse_integer_unchecked(s);
-    }
-    // find the first occurence of a separator
-    let index = sse41::last_digit_byte(s);
-    match index {
-        8 => sse41::parse_8_chars_simd(s),
-        10 => sse41::parse_10_chars_simd(s),
-        9 => sse41::parse_9_chars_simd(s),
-        7 => sse41::parse_7_chars_simd(s),
-        6 => sse41::parse_6_chars_simd(s),
-        5 => sse41::parse_5_chars_simd(s),
-        4 => sse41::parse_4_chars_simd(s),
-        1..=3 => fallback::parse_byte_iterator_limited(s, index),
-        // all the chars are numeric, maybe padded?
-        32 => sse41::parse_integer_simd_all_numbers(s),
-        // there is no u32 to parse
-        _ => panic!("No u32 to parse from input string!"),
-    }
+/// Assigns the correct implementation to `TRY_PARSE_INTEGER_SEP` according to
+/// the underlying cpu
+fn try_parse_integer_sep_dispatcher(s: &[u8], sep: u8, eol: u8) -> Result<u32, ParseError> {
+    let f = detected_backend().functions().try_parse_integer_separator;
+    TRY_PARSE_INTEGER_SEP.set(f);
+    unsafe { f(s, sep, eol) }
 }
 
-/// Parses an u32 from the input string using SSE4.1 intrinsics whenever is
-/// possible up to the first occurence of `separator` or `eol`
-#[cfg(target_arch = "x86_64")]
-#[target_feature(enable = "sse4.1")]
-unsafe fn parse_integer_separator_sse41(s: &str, separator: u8, eol: u8) -> u32 {
-    if s.len() < sse41::VECTOR_SIZE {
-        return fallback::parse_integer_separator_unchecked(s, separator, eol);
-    }
-    // find the first occurence of a separator
-    let index = sse41::last_byte_without_separator(s, separator, eol);
-    match index {
-        8 => sse41::parse_8_chars_simd(s),
-        10 => sse41::parse_10_chars_simd(s),
-        9 => sse41::parse_9_chars_simd(s),
-        7 => sse41::parse_7_chars_simd(s),
-        6 => sse41::parse_6_chars_simd(s),
-        5 => sse41::parse_5_chars_simd(s),
-        4 => sse41::parse_4_chars_simd(s),
-        1..=3 => fallback::parse_byte_iterator_limited(s, index),
-        // all the chars are numeric, maybe padded?
-        32 => sse41::parse_integer_simd_all_numbers(s),
-        // there is no u32 to parse
-        _ => panic!("No u32 to parse from input string!"),
+/// Parses an `u32` from the input string up to the first occurrence of
+/// `separator` or `eol`.
+///
+/// Unlike `parse_integer_separator`, every byte before the separator must be a
+/// digit and the field must be terminated. The error tells why the parsing
+/// failed and the offset of the offending byte:
+///
+/// - `ParseErrorKind::Empty` if the string starts with a separator;
+/// - `ParseErrorKind::InvalidDigit` for a non digit byte before the separator;
+/// - `ParseErrorKind::Overflow` if the number doesn't fit in an `u32`;
+/// - `ParseErrorKind::MissingTerminator` if neither `separator` nor `eol` are
+///   found, e.g. when the field continues in the next chunk of a stream.
+#[inline]
+pub fn try_parse_integer_separator(
+    s: &str,
+    separator: u8,
+    eol: u8,
+) -> Result<u32, ParseError> {
+    try_parse_integer_separator_bytes(s.as_bytes(), separator, eol)
+}
+
+/// Same as `try_parse_integer_separator`, but reads the input from a byte
+/// slice.
+#[inline]
+pub fn try_parse_integer_separator_bytes(
+    s: &[u8],
+    separator: u8,
+    eol: u8,
+) -> Result<u32, ParseError> {
+    unsafe { dispatch!(TRY_PARSE_INTEGER_SEP, try_parse_integer_separator(s, separator, eol)) }
+}
+
+/// Signature of the `try_parse_integer_separator_set` implementations
+pub(crate) type TryParseIntegerSepSet = unsafe fn(&[u8], &SeparatorSet) -> Result<u32, ParseError>;
+
+atomic_fn! {
+    /// Pointer to `try_parse_integer_separator_set` supported by the underlying
+    /// CPU
+    static TRY_PARSE_INTEGER_SEP_SET: TryParseIntegerSepSet = try_parse_integer_sep_set_dispatcher;
+}
+
+/// Assigns the correct implementation to `TRY_PARSE_INTEGER_SEP_SET` according
+/// to the underlying cpu
+fn try_parse_integer_sep_set_dispatcher(
+    s: &[u8],
+    separators: &SeparatorSet,
+) -> Result<u32, ParseError> {
+    let f = detected_backend().functions().try_parse_integer_separator_set;
+    TRY_PARSE_INTEGER_SEP_SET.set(f);
+    unsafe { f(s, separators) }
+}
+
+/// Parses an `u32` from the input string up to the first occurrence of any
+/// byte of `separators`.
+///
+/// It works as `try_parse_integer_separator`, with up to 16 terminators: on
+/// CPUs with SSE4.2, a single string instruction compares 16 chars against the
+/// whole set.
+///
+/// ```
+/// use simd_parsing::{try_parse_integer_separator_set, SeparatorSet};
+///
+/// let separators = SeparatorSet::new(b",; \t\n").unwrap();
+/// assert_eq!(try_parse_integer_separator_set("1234\t5678", &separators), Ok(1234));
+/// ```
+#[inline]
+pub fn try_parse_integer_separator_set(
+    s: &str,
+    separators: &SeparatorSet,
+) -> Result<u32, ParseError> {
+    try_parse_integer_separator_set_bytes(s.as_bytes(), separators)
+}
+
+/// Same as `try_parse_integer_separator_set`, but reads the input from a byte
+/// slice.
+#[inline]
+pub fn try_parse_integer_separator_set_bytes(
+    s: &[u8],
+    separators: &SeparatorSet,
+) -> Result<u32, ParseError> {
+    unsafe { dispatch!(TRY_PARSE_INTEGER_SEP_SET, try_parse_integer_separator_set(s, separators)) }
+}
+
+/// The byte which stopped the parsing of a field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Terminator {
+    /// The field ended with the separator
+    Separator,
+    /// The field ended with the end of line
+    Eol,
+    /// The input ended before any separator or end of line
+    EndOfInput,
+}
+
+impl Terminator {
+    /// Returns the terminator found at `index` of the input, where `index` is
+    /// the position of the first separator or end of line, if any
+    #[inline]
+    pub(crate) fn at(s: &[u8], index: usize, separator: u8) -> Self {
+        match s.get(index) {
+            Some(&byte) if byte == separator => Terminator::Separator,
+            Some(_) => Terminator::Eol,
+            None => Terminator::EndOfInput,
+        }
     }
 }
 
+atomic_fn! {
+    /// Pointer to `parse_integer_prefix` supported by the underlying CPU
+    static PARSE_INTEGER_PREFIX: unsafe fn(&[u8]) -> Option<(u32, usize)> =
+        parse_integer_prefix_dispatcher;
+}
+
+/// Assigns the correct implementation to `PARSE_INTEGER_PREFIX` according to
+/// the underlying cpu
+fn parse_integer_prefix_dispatcher(s: &[u8]) -> Option<(u32, usize)> {
+    let f = detected_backend().functions().parse_integer_prefix;
+    PARSE_INTEGER_PREFIX.set(f);
+    unsafe { f(s) }
+}
+
+/// Parses an `u32` from the input string as far as there are digits, returning
+/// it along with the number of digits consumed.
+///
+/// The caller can continue scanning the string from the returned index, which
+/// is the position of the first non digit byte. In case of empty string or
+/// arithmetic overflow, it will return None.
+#[inline]
+pub fn parse_integer_prefix(s: &str) -> Option<(u32, usize)> {
+    parse_integer_prefix_bytes(s.as_bytes())
+}
+
+/// Same as `parse_integer_prefix`, but reads the input from a byte slice.
+#[inline]
+pub fn parse_integer_prefix_bytes(s: &[u8]) -> Option<(u32, usize)> {
+    unsafe { dispatch!(PARSE_INTEGER_PREFIX, parse_integer_prefix(s)) }
+}
+
+/// Signature of the `parse_integer_separator_prefix` implementations
+pub(crate) type ParseIntegerSepPrefix = unsafe fn(&[u8], u8, u8) -> Option<(u32, usize, Terminator)>;
+
+atomic_fn! {
+    /// Pointer to `parse_integer_separator_prefix` supported by the underlying CPU
+    static PARSE_INTEGER_SEP_PREFIX: ParseIntegerSepPrefix = parse_integer_sep_prefix_dispatcher;
+}
+
+/// Assigns the correct implementation to `PARSE_INTEGER_SEP_PREFIX` according
+/// to the underlying cpu
+fn parse_integer_sep_prefix_dispatcher(
+    s: &[u8],
+    sep: u8,
+    eol: u8,
+) -> Option<(u32, usize, Terminator)> {
+    let f = detected_backend().functions().parse_integer_separator_prefix;
+    PARSE_INTEGER_SEP_PREFIX.set(f);
+    unsafe { f(s, sep, eol) }
+}
+
+/// Parses an `u32` from the input string up to the first occurrence of
+/// `separator` or `eol`, returning it along with the number of bytes consumed
+/// and the `Terminator` which stopped the parsing.
+///
+/// The number of bytes consumed doesn't include the terminator, so it's the
+/// index of the separator or of the end of line in the string, or its length
+/// if the string ended first. In case of empty string, arithmetic overflow,
+/// absence of number to parse or any non digit before the terminator, it will
+/// return None.
+#[inline]
+pub fn parse_integer_separator_prefix(
+    s: &str,
+    separator: u8,
+    eol: u8,
+) -> Option<(u32, usize, Terminator)> {
+    parse_integer_separator_prefix_bytes(s.as_bytes(), separator, eol)
+}
+
+/// Same as `parse_integer_separator_prefix`, but reads the input from a byte
+/// slice.
+#[inline]
+pub fn parse_integer_separator_prefix_bytes(
+    s: &[u8],
+    separator: u8,
+    eol: u8,
+) -> Option<(u32, usize, Terminator)> {
+    unsafe { dispatch!(PARSE_INTEGER_SEP_PREFIX, parse_integer_separator_prefix(s, separator, eol)) }
+}
+
+/// Signature of the `parse_delimited_into` implementations
+pub(crate) type ParseDelimitedInto =
+    unsafe fn(&[u8], u8, u8, &mut [u32]) -> Result<(usize, usize), ParseError>;
+
+atomic_fn! {
+    /// Pointer to `parse_delimited_into` supported by the underlying CPU
+    static PARSE_DELIMITED_INTO: ParseDelimitedInto = parse_delimited_into_dispatcher;
+}
+
+/// Assigns the correct implementation to `PARSE_DELIMITED_INTO` according to
+/// the underlying cpu
+fn parse_delimited_into_dispatcher(
+    buf: &[u8],
+    sep: u8,
+    eol: u8,
+    out: &mut [u32],
+) -> Result<(usize, usize), ParseError> {
+    let f = detected_backend().functions().parse_delimited_into;
+    PARSE_DELIMITED_INTO.set(f);
+    unsafe { f(buf, sep, eol, out) }
+}
+
+/// Parses all the fields of `buf` into `out`, returning the number of values
+/// written and the number of bytes consumed.
+///
+/// Fields are terminated by either `separator` or `eol`, so a whole table of
+/// integers can be read in one call, row after row. A terminator at the end of
+/// `buf` is optional. The parsing stops when either `buf` or `out` is over: in
+/// the latter case, the returned count is `out.len()`, and the bytes consumed
+/// end right after the terminator of the last value, so that the parsing can
+/// resume from there once `out` has been drained.
+///
+/// The implementation is selected once per call, and the whole buffer is
+/// scanned with SIMD instructions, locating more fields with a single load.
+/// If a field is empty, contains a non digit or overflows an `u32`, the error
+/// reports the offset of the offending byte from the start of `buf`; the values
+/// parsed up to that point are left in `out`.
+#[inline]
+pub fn parse_delimited_into(
+    buf: &str,
+    separator: u8,
+    eol: u8,
+    out: &mut [u32],
+) -> Result<(usize, usize), ParseError> {
+    parse_delimited_into_bytes(buf.as_bytes(), separator, eol, out)
+}
+
+/// Same as `parse_delimited_into`, but reads the input from a byte slice.
+#[inline]
+pub fn parse_delimited_into_bytes(
+    buf: &[u8],
+    separator: u8,
+    eol: u8,
+    out: &mut [u32],
+) -> Result<(usize, usize), ParseError> {
+    unsafe { dispatch!(PARSE_DELIMITED_INTO, parse_delimited_into(buf, separator, eol, out)) }
+}
 
 // -----------------------------------------------------------------------------
-//                             Benchmark only function
+//                           Signed integers parsing
 //
-// This functions are useful only to benchmark the performance of the different
-// algorithms. Make sure to pass `--features "benchmark"` during the compilation
-// step to enable the following definitions.
+// The signed API strips the optional sign and parses the magnitude with the
+// unsigned functions above, so the SIMD acceleration applies to the digits
+// following the sign byte. The sign is applied afterwards, checking the bounds
+// of the signed type.
 // -----------------------------------------------------------------------------
 
-/// SSE4.2 implementation for `parse_integer_separator` meant to be used only
-/// during benchamark
-#[cfg(feature = "benchmark")]
-#[cfg(target_arch = "x86_64")]
-#[target_feature(enable = "sse4.2")]
-unsafe fn parse_integer_separator_sse42(s: &str, separator: u8, eol: u8) -> u32 {
-    if s.len() < sse41::VECTOR_SIZE {
-        return fallback::parse_integer_separator_unchecked(s, separator, eol);
-    }
-    // find the first occurence of a separator
-    let index = sse42::last_byte_without_separator(s, separator, eol);
-    match index {
-        8 => return sse41::parse_8_chars_simd(s),
-        10 => return sse41::parse_10_chars_simd(s),
-        9 => return sse41::parse_9_chars_simd(s),
-        7 => return sse41::parse_7_chars_simd(s),
-        6 => return sse41::parse_6_chars_simd(s),
-        5 => return sse41::parse_5_chars_simd(s),
-        4 => return sse41::parse_4_chars_simd(s),
-        1..=3 => return fallback::parse_byte_iterator_limited(s, index),
-        // all the chars are numeric, maybe padded?
-        32 => return sse41::parse_integer_simd_all_numbers(s),
-        // there is no u32 to parse
-        _ => panic!("No u32 to parse from input string!"),
+/// Splits the optional leading `+` or `-` from the input string, returning
+/// true if the number is negative along with the remaining string.
+#[inline]
+pub(crate) fn split_sign(s: &[u8]) -> (bool, &[u8]) {
+    match s.first() {
+        Some(b'-') => (true, &s[1..]),
+        Some(b'+') => (false, &s[1..]),
+        _ => (false, s),
     }
 }
 
-/// SSE4.2 implementation for `parse_integer` meant to be used only during
-/// benchamark
-#[cfg(feature = "benchmark")]
-#[cfg(target_arch = "x86_64")]
-#[target_feature(enable = "sse4.2")]
-unsafe fn parse_integer_sse42(s: &str) -> u32 {
-    if s.len() < sse41::VECTOR_SIZE {
-        return fallback::parse_integer_unchecked(s);
-    }
-    // find the first occurence of a separator
-    let index = sse42::last_digit_byte(s);
-    match index {
-        8 => return sse41::parse_8_chars_simd(s),
-        10 => return sse41::parse_10_chars_simd(s),
-        9 => return sse41::parse_9_chars_simd(s),
-        7 => return sse41::parse_7_chars_simd(s),
-        6 => return sse41::parse_6_chars_simd(s),
-        5 => return sse41::parse_5_chars_simd(s),
-        4 => return sse41::parse_4_chars_simd(s),
-        1..=3 => return fallback::parse_byte_iterator_limited(s, index),
-        // all the chars are numeric, maybe padded?
-        32 => return sse41::parse_integer_simd_all_numbers(s),
-        // there is no u32 to parse
-        _ => panic!("No u32 to parse from input string!"),
+/// Applies the sign to the parsed magnitude, returning `None` if the result
+/// doesn't fit in an `i32`.
+///
+/// Since `i32::MIN` has no positive counterpart, a negative magnitude can be
+/// one unit bigger than `i32::MAX`.
+#[inline]
+pub(crate) fn apply_sign_i32(negative: bool, magnitude: u32) -> Option<i32> {
+    if negative {
+        if magnitude <= i32::MIN.unsigned_abs() {
+            Some((magnitude as i32).wrapping_neg())
+        } else {
+            None
+        }
+    } else if magnitude <= i32::MAX as u32 {
+        Some(magnitude as i32)
+    } else {
+        None
     }
 }
 
-/// Safe wrapper around `parse_integer_separator_sse41` to use only during
-/// benchmark
-#[cfg(feature = "benchmark")]
+/// Applies the sign to the parsed magnitude, returning `None` if the result
+/// doesn't fit in an `i64`.
+///
+/// Since `i64::MIN` has no positive counterpart, a negative magnitude can be
+/// one unit bigger than `i64::MAX`.
 #[inline]
-pub fn safe_parse_integer_separator_sse41(s: &str, separator: u8, eol: u8) -> u32 {
-    unsafe {
-        return parse_integer_separator_sse41(s, separator, eol);
+pub(crate) fn apply_sign_i64(negative: bool, magnitude: u64) -> Option<i64> {
+    if negative {
+        if magnitude <= i64::MIN.unsigned_abs() {
+            Some((magnitude as i64).wrapping_neg())
+        } else {
+            None
+        }
+    } else if magnitude <= i64::MAX as u64 {
+        Some(magnitude as i64)
+    } else {
+        None
     }
 }
 
-/// Safe wrapper around `parse_integer_separator_sse42` to use only during
-/// benchmark
-#[cfg(feature = "benchmark")]
+/// Parses an `i32` from the input string, with an optional leading `+` or `-`.
+///
+/// In case of empty string, missing digits after the sign or arithmetic
+/// overflow, it will return None.
 #[inline]
-pub fn safe_parse_integer_separator_sse42(s: &str, separator: u8, eol: u8) -> u32 {
-    unsafe {
-        return parse_integer_separator_sse42(s, separator, eol);
-    }
+pub fn parse_i32(s: &str) -> Option<i32> {
+    parse_i32_bytes(s.as_bytes())
 }
 
-/// Safe wrapper around `parse_integer_avx2` to use only during benchmark
-#[cfg(feature = "benchmark")]
+/// Same as `parse_i32`, but reads the input from a byte slice.
 #[inline]
-pub fn safe_parse_integer_separator_avx2(s: &str, separator: u8, eol: u8) -> u32 {
-    unsafe {
-        return parse_integer_separator_avx2(s, separator, eol);
+pub fn parse_i32_bytes(s: &[u8]) -> Option<i32> {
+    let (negative, digits) = split_sign(s);
+    apply_sign_i32(negative, parse_integer_bytes(digits)?)
+}
+
+/// Parses an `i32` from the input string, with an optional leading `+` or `-`,
+/// up to the first occurrence of `separator` or `eol`.
+///
+/// In case of empty string, arithmetic overflow or absence of number to parse,
+/// it will return None.
+#[inline]
+pub fn parse_i32_separator(s: &str, separator: u8, eol: u8) -> Option<i32> {
+    parse_i32_separator_bytes(s.as_bytes(), separator, eol)
+}
+
+/// Same as `parse_i32_separator`, but reads the input from a byte slice.
+#[inline]
+pub fn parse_i32_separator_bytes(s: &[u8], separator: u8, eol: u8) -> Option<i32> {
+    let (negative, digits) = split_sign(s);
+    apply_sign_i32(negative, parse_integer_separator_bytes(digits, separator, eol)?)
+}
+
+/// Parses an `i64` from the input string, with an optional leading `+` or `-`.
+///
+/// In case of empty string, missing digits after the sign or arithmetic
+/// overflow, it will return None.
+#[inline]
+pub fn parse_i64(s: &str) -> Option<i64> {
+    parse_i64_bytes(s.as_bytes())
+}
+
+/// Same as `parse_i64`, but reads the input from a byte slice.
+#[inline]
+pub fn parse_i64_bytes(s: &[u8]) -> Option<i64> {
+    let (negative, digits) = split_sign(s);
+    apply_sign_i64(negative, parse_u64_bytes(digits)?)
+}
+
+/// Parses an `i64` from the input string, with an optional leading `+` or `-`,
+/// up to the first occurrence of `separator` or `eol`.
+///
+/// In case of empty string, arithmetic overflow or absence of number to parse,
+/// it will return None.
+#[inline]
+pub fn parse_i64_separator(s: &str, separator: u8, eol: u8) -> Option<i64> {
+    parse_i64_separator_bytes(s.as_bytes(), separator, eol)
+}
+
+/// Same as `parse_i64_separator`, but reads the input from a byte slice.
+#[inline]
+pub fn parse_i64_separator_bytes(s: &[u8], separator: u8, eol: u8) -> Option<i64> {
+    let (negative, digits) = split_sign(s);
+    apply_sign_i64(negative, parse_u64_separator_bytes(digits, separator, eol)?)
+}
+
+// -----------------------------------------------------------------------------
+//          Internal implementations of architecture specific functions.
+// -----------------------------------------------------------------------------
+
+/// Parses and `u32` from the input string when possibile using AVX2 instrinics.
+///
+/// If the input string is empty or the parsing function encounters an overflow,
+/// then `None` will be returned.
+#[inline]
+#[cfg(all(target_arch = "x86_64", feature = "backend-avx2"))]
+#[target_feature(enable = "avx2")]
+unsafe fn parse_integer_checked_avx2(s: &[u8]) -> Option<u32> {
+    // every AVX2 CPU has SSE4.1, whose kernels need half the chars
+    if s.len() < avx::VECTOR_SIZE {
+        return parse_integer_checked_sse41(s);
+    }
+    // the digits are validated and combined from a single load
+    let (digits, value) = avx::parse_prefix_checked_simd(s);
+    match digits {
+        0 => None,
+        // up to 16 digits always fit in an `u64`
+        1..=16 => u32::try_from(value).ok(),
+        // Use the default implementation for the numbers with more than 16
+        // digits, which may still fit in an `u32` with leading zeros
+        _ => fallback::parse_integer_bytes(s),
     }
 }
 
-/// Safe wrapper around `parse_integer_sse41` to use only during benchmark
-#[cfg(feature = "benchmark")]
+/// Parses and `u32` from the input string when possible using SSE4.1
+/// intrinsics.
+///
+/// If the input string is empty or the parsing function encounters an overflow,
+/// then `None` will be returned.
 #[inline]
-pub fn safe_parse_integer_sse41(s: &str) -> u32 {
-    unsafe {
-        return parse_integer_sse41(s);
+#[cfg(all(
+    target_arch = "x86_64",
+    any(feature = "backend-sse41", feature = "backend-sse42", feature = "backend-avx2")
+))]
+#[target_feature(enable = "sse4.1")]
+unsafe fn parse_integer_checked_sse41(s: &[u8]) -> Option<u32> {
+    if s.len() < sse41::VECTOR_SIZE {
+        let (len, value) = sse41::parse_short_prefix(s);
+        // less than 16 digits always fit in an `u64`
+        return if len == 0 { None } else { u32::try_from(value).ok() };
+    }
+    // the digits are validated and combined from a single load
+    let (digits, value) = sse41::parse_prefix_checked_simd(s);
+    match digits {
+        0 => None,
+        1..=15 => u32::try_from(value).ok(),
+        // Use the default implementation when the number may continue past
+        // the loaded chars
+        _ => fallback::parse_integer_bytes(s),
     }
 }
 
-/// Safe wrapper around `parse_integer_sse42` to use only during benchmark
-#[cfg(feature = "benchmark")]
+/// Parses and `u32` from the input string when possible using SSE2
+/// intrinsics.
+///
+/// If the input string is empty or the parsing function encounters an overflow,
+/// then `None` will be returned.
 #[inline]
-pub fn safe_parse_integer_sse42(s: &str) -> u32 {
-    unsafe {
-        return parse_integer_sse42(s);
+#[cfg(all(target_arch = "x86_64", feature = "backend-sse2"))]
+#[target_feature(enable = "sse2")]
+unsafe fn parse_integer_checked_sse2(s: &[u8]) -> Option<u32> {
+    if s.len() < sse2::VECTOR_SIZE {
+        let (len, value) = sse2::parse_short_prefix(s);
+        // less than 16 digits always fit in an `u64`
+        return if len == 0 { None } else { u32::try_from(value).ok() };
+    }
+    // the digits are validated and combined from a single load
+    let (digits, value) = sse2::parse_prefix_checked_simd(s);
+    match digits {
+        0 => None,
+        1..=15 => u32::try_from(value).ok(),
+        // Use the default implementation when the number may continue past
+        // the loaded chars
+        _ => fallback::parse_integer_bytes(s),
     }
 }
 
-/// Safe wrapper around `parse_integer_avx2` to use only during benchmark
-#[cfg(feature = "benchmark")]
+/// Parses and `u32` from the input string when possible using portable
+/// SIMD.
+///
+/// If the input string is empty or the parsing function encounters an overflow,
+/// then `None` will be returned.
 #[inline]
-pub fn safe_parse_integer_avx2(s: &str) -> u32 {
-    unsafe {
-        return parse_integer_avx2(s);
+#[cfg(feature = "backend-portable")]
+unsafe fn parse_integer_checked_portable(s: &[u8]) -> Option<u32> {
+    if s.len() < portable::VECTOR_SIZE {
+        let (len, value) = portable::parse_short_prefix(s);
+        // less than 16 digits always fit in an `u64`
+        return if len == 0 { None } else { u32::try_from(value).ok() };
+    }
+    // the digits are validated and combined from a single load
+    let (digits, value) = portable::parse_prefix_checked_simd(s);
+    match digits {
+        0 => None,
+        1..=15 => u32::try_from(value).ok(),
+        // Use the default implementation when the number may continue past
+        // the loaded chars
+        _ => fallback::parse_integer_bytes(s),
     }
 }
 
-/// Safe wrapper around `sse41::check_all_chars_are_valid` to use only during
-/// benchmark
-#[cfg(feature = "benchmark")]
+/// Parses and `u32` from the input string when possibile using AVX2 instrinics.
+///
+/// If the input string is empty or the parsing function encounters an overflow,
+/// then `None` will be returned.
 #[inline]
-pub fn safe_check_all_chars_are_valid_sse41(s: &str) -> bool {
-    unsafe {
-        return sse41::check_all_chars_are_valid(s);
+#[cfg(all(target_arch = "x86_64", feature = "backend-avx2"))]
+#[target_feature(enable = "avx2")]
+unsafe fn parse_integer_sep_checked_avx2(s: &[u8], sep: u8, eol: u8) -> Option<u32> {
+    // every AVX2 CPU has SSE4.1, whose kernels need half the chars
+    if s.len() < avx::VECTOR_SIZE {
+        return parse_integer_sep_checked_sse41(s, sep, eol);
+    }
+    // the terminator, the digits and their value come from a single load
+    let (end, invalid, value) = avx::parse_field_checked_simd(s, sep, eol);
+    match end {
+        // either an empty field or a non digit before the terminator
+        _ if end == 0 || invalid < end => None,
+        1..=16 => u32::try_from(value).ok(),
+        // Use the default implementation for the fields with more than 16
+        // digits, which may still fit in an `u32` with leading zeros
+        _ => fallback::parse_integer_separator_bytes(s, sep, eol),
     }
 }
 
-/// Safe wrapper around `sse42::check_all_chars_are_valid` to use only during
-/// benchmark
-#[cfg(feature = "benchmark")]
+/// Parses and `u32` from the input string when possible using SSE4.1
+/// intrinsics.
+///
+/// If the input string is empty or the parsing function encounters an overflow,
+/// then `None` will be returned.
 #[inline]
-pub fn safe_check_all_chars_are_valid_sse42(s: &str) -> bool {
-    unsafe {
-        return sse42::check_all_chars_are_valid(s);
+#[cfg(all(
+    target_arch = "x86_64",
+    any(feature = "backend-sse41", feature = "backend-sse42", feature = "backend-avx2")
+))]
+#[target_feature(enable = "sse4.1")]
+unsafe fn parse_integer_sep_checked_sse41(s: &[u8], sep: u8, eol: u8) -> Option<u32> {
+    if s.len() < sse41::VECTOR_SIZE {
+        let (end, invalid, value) = sse41::parse_short_field(s, sep, eol);
+        // either an empty field or a non digit before the terminator
+        if end == 0 || invalid < end {
+            return None;
+        }
+        return u32::try_from(value).ok();
+    }
+    // the terminator, the digits and their value come from a single load
+    let (end, invalid, value) = sse41::parse_field_checked_simd(s, sep, eol);
+    match end {
+        // either an empty field or a non digit before the terminator
+        _ if end == 0 || invalid < end => None,
+        1..=15 => u32::try_from(value).ok(),
+        // Use the default implementation when the field may continue past the
+        // loaded chars
+        _ => fallback::parse_integer_separator_bytes(s, sep, eol),
     }
 }
 
-/// Safe wrapper around `avx::check_all_chars_are_valid` to use only during
-/// benchmark
-#[cfg(feature = "benchmark")]
+/// Parses and `u32` from the input string when possible using SSE2
+/// intrinsics.
+///
+/// If the input string is empty or the parsing function encounters an overflow,
+/// then `None` will be returned.
 #[inline]
-pub fn safe_check_all_chars_are_valid_avx(s: &str) -> bool {
-    unsafe {
-        return avx::check_all_chars_are_valid(s);
+#[cfg(all(target_arch = "x86_64", feature = "backend-sse2"))]
+#[target_feature(enable = "sse2")]
+unsafe fn parse_integer_sep_checked_sse2(s: &[u8], sep: u8, eol: u8) -> Option<u32> {
+    if s.len() < sse2::VECTOR_SIZE {
+        let (end, invalid, value) = sse2::parse_short_field(s, sep, eol);
+        // either an empty field or a non digit before the terminator
+        if end == 0 || invalid < end {
+            return None;
+        }
+        return u32::try_from(value).ok();
+    }
+    // the terminator, the digits and their value come from a single load
+    let (end, invalid, value) = sse2::parse_field_checked_simd(s, sep, eol);
+    match end {
+        // either an empty field or a non digit before the terminator
+        _ if end == 0 || invalid < end => None,
+        1..=15 => u32::try_from(value).ok(),
+        // Use the default implementation when the field may continue past the
+        // loaded chars
+        _ => fallback::parse_integer_separator_bytes(s, sep, eol),
     }
 }
 
-/// Safe wrapper around `sse41::last_byte_without_separator` to use only during
-/// benchmark
-#[cfg(feature = "benchmark")]
+/// Parses and `u32` from the input string when possible using portable
+/// SIMD.
+///
+/// If the input string is empty or the parsing function encounters an overflow,
+/// then `None` will be returned.
 #[inline]
-pub fn safe_last_byte_without_separator_sse41(s: &str, separator: u8, eol: u8) -> u32 {
-    unsafe {
-        return sse41::last_byte_without_separator(s, separator, eol);
+#[cfg(feature = "backend-portable")]
+unsafe fn parse_integer_sep_checked_portable(s: &[u8], sep: u8, eol: u8) -> Option<u32> {
+    if s.len() < portable::VECTOR_SIZE {
+        let (end, invalid, value) = portable::parse_short_field(s, sep, eol);
+        // either an empty field or a non digit before the terminator
+        if end == 0 || invalid < end {
+            return None;
+        }
+        return u32::try_from(value).ok();
+    }
+    // the terminator, the digits and their value come from a single load
+    let (end, invalid, value) = portable::parse_field_checked_simd(s, sep, eol);
+    match end {
+        // either an empty field or a non digit before the terminator
+        _ if end == 0 || invalid < end => None,
+        1..=15 => u32::try_from(value).ok(),
+        // Use the default implementation when the field may continue past the
+        // loaded chars
+        _ => fallback::parse_integer_separator_bytes(s, sep, eol),
     }
 }
 
-/// Safe wrapper around `sse42::last_byte_without_separator` to use only during
-/// benchmark
-#[cfg(feature = "benchmark")]
+/// Parses an u32 from the input string using AVX2 intrinsics whenever is
+/// possible
 #[inline]
-pub fn safe_last_byte_without_separator_sse42(s: &str, separator: u8, eol: u8) -> u32 {
-    unsafe {
-        return sse42::last_byte_without_separator(s, separator, eol);
+#[cfg(all(target_arch = "x86_64", feature = "backend-avx2"))]
+#[target_feature(enable = "avx2")]
+unsafe fn parse_integer_avx2(s: &[u8]) -> u32 {
+    // every AVX2 CPU has SSE4.1, whose kernels need half the chars
+    if s.len() < avx::VECTOR_SIZE {
+        return parse_integer_sse41(s);
+    }
+    // find the first occurence of a separator
+    let index = avx::last_digit_byte(s);
+    match index {
+        1..=10 => avx::parse_digits_simd(s, index as usize) as u32,
+        // all the chars are numeric, and they should be padded with 0s to get a
+        // correct result. If not, the parsed number will not be correct due to
+        // internal processing techniques
+        32 => avx::parse_padded_integer_simd_all_numbers(s),
+        // there is no number to parse
+        _ => panic!("No u32 to parse from input string!"),
     }
 }
 
-/// Safe wrapper around `avx::last_byte_without_separator` to use only during
-/// benchmark
-#[cfg(feature = "benchmark")]
+/// Parses an u32 from the input string using AVX2 intrinsics whenever is
+/// possible up to the first occurence of `separator` or `eol`
 #[inline]
-pub fn safe_last_byte_without_separator_avx(s: &str, separator: u8, eol: u8) -> u32 {
-    unsafe {
-        return avx::last_byte_without_separator(s, separator, eol);
+#[cfg(all(target_arch = "x86_64", feature = "backend-avx2"))]
+#[target_feature(enable = "avx2")]
+unsafe fn parse_integer_separator_avx2(s: &[u8], separator: u8, eol: u8) -> u32 {
+    // every AVX2 CPU has SSE4.1, whose kernels need half the chars
+    if s.len() < avx::VECTOR_SIZE {
+        return parse_integer_separator_sse41(s, separator, eol);
+    }
+    // find the first occurence of a separator
+    let index = avx::last_byte_without_separator(s, separator, eol);
+    match index {
+        1..=10 => avx::parse_digits_simd(s, index as usize) as u32,
+        // all the chars are numeric, and they should be padded with 0s to get a
+        // correct result. If not, the parsed number will not be correct due to
+        // internal processing techniques
+        32 => avx::parse_padded_integer_simd_all_numbers(s),
+        // there is no number to parse
+        _ => panic!("No u32 to parse from input string!"),
+    }
+}
+
+/// Parses an u32 from the input string using SSE4.1 intrinsics whenever is
+/// possible
+#[inline]
+#[cfg(all(
+    target_arch = "x86_64",
+    any(feature = "backend-sse41", feature = "backend-sse42", feature = "backend-avx2")
+))]
+#[target_feature(enable = "sse4.1")]
+unsafe fn parse_integer_sse41(s: &[u8]) -> u32 {
+    if s.len() < sse41::VECTOR_SIZE {
+        let (len, value) = sse41::parse_short_prefix(s);
+        if len == 0 {
+            // the scalar implementation gives 0 for an empty string
+            return fallback::parse_integer_unchecked_bytes(s);
+        }
+        return value as u32;
+    }
+    // find the first occurence of a separator
+    let index = sse41::last_digit_byte(s);
+    match index {
+        1..=10 => sse41::parse_digits_simd(s, index as usize) as u32,
+        // all the chars are numeric, maybe padded?
+        32 => sse41::parse_integer_simd_all_numbers(s),
+        // there is no u32 to parse
+        _ => panic!("No u32 to parse from input string!"),
+    }
+}
+
+/// Parses an u32 from the input string using SSE2 intrinsics whenever is
+/// possible
+#[inline]
+#[cfg(all(target_arch = "x86_64", feature = "backend-sse2"))]
+#[target_feature(enable = "sse2")]
+unsafe fn parse_integer_sse2(s: &[u8]) -> u32 {
+    if s.len() < sse2::VECTOR_SIZE {
+        let (len, value) = sse2::parse_short_prefix(s);
+        if len == 0 {
+            // the scalar implementation gives 0 for an empty string
+            return fallback::parse_integer_unchecked_bytes(s);
+        }
+        return value as u32;
+    }
+    // find the first occurence of a separator
+    let index = sse2::last_digit_byte(s);
+    match index {
+        1..=10 => sse2::parse_digits_simd(s, index as usize) as u32,
+        // all the chars are numeric, maybe padded?
+        32 => sse2::parse_digits_simd(s, sse2::VECTOR_SIZE) as u32,
+        // there is no u32 to parse
+        _ => panic!("No u32 to parse from input string!"),
+    }
+}
+
+/// Parses an u32 from the input string using portable SIMD whenever is
+/// possible
+#[inline]
+#[cfg(feature = "backend-portable")]
+unsafe fn parse_integer_portable(s: &[u8]) -> u32 {
+    if s.len() < portable::VECTOR_SIZE {
+        let (len, value) = portable::parse_short_prefix(s);
+        if len == 0 {
+            // the scalar implementation gives 0 for an empty string
+            return fallback::parse_integer_unchecked_bytes(s);
+        }
+        return value as u32;
+    }
+    // find the first occurence of a separator
+    let index = portable::last_digit_byte(s);
+    match index {
+        1..=10 => portable::parse_digits_simd(s, index as usize) as u32,
+        // all the chars are numeric, maybe padded?
+        32 => portable::parse_digits_simd(s, portable::VECTOR_SIZE) as u32,
+        // there is no u32 to parse
+        _ => panic!("No u32 to parse from input string!"),
+    }
+}
+
+/// Parses an u32 from the input string using SSE4.1 intrinsics whenever is
+/// possible up to the first occurence of `separator` or `eol`
+#[inline]
+#[cfg(all(
+    target_arch = "x86_64",
+    any(feature = "backend-sse41", feature = "backend-sse42", feature = "backend-avx2")
+))]
+#[target_feature(enable = "sse4.1")]
+unsafe fn parse_integer_separator_sse41(s: &[u8], separator: u8, eol: u8) -> u32 {
+    if s.len() < sse41::VECTOR_SIZE {
+        let (_, _, value) = sse41::parse_short_field(s, separator, eol);
+        return value as u32;
+    }
+    // find the first occurence of a separator
+    let index = sse41::last_byte_without_separator(s, separator, eol);
+    match index {
+        1..=10 => sse41::parse_digits_simd(s, index as usize) as u32,
+        // all the chars are numeric, maybe padded?
+        32 => sse41::parse_integer_simd_all_numbers(s),
+        // there is no u32 to parse
+        _ => panic!("No u32 to parse from input string!"),
+    }
+}
+
+/// Parses an u32 from the input string using SSE2 intrinsics whenever is
+/// possible up to the first occurence of `separator` or `eol`
+#[inline]
+#[cfg(all(target_arch = "x86_64", feature = "backend-sse2"))]
+#[target_feature(enable = "sse2")]
+unsafe fn parse_integer_separator_sse2(s: &[u8], separator: u8, eol: u8) -> u32 {
+    if s.len() < sse2::VECTOR_SIZE {
+        let (_, _, value) = sse2::parse_short_field(s, separator, eol);
+        return value as u32;
+    }
+    // find the first occurence of a separator
+    let index = sse2::last_byte_without_separator(s, separator, eol);
+    match index {
+        1..=10 => sse2::parse_digits_simd(s, index as usize) as u32,
+        // all the chars are numeric, maybe padded?
+        32 => sse2::parse_digits_simd(s, sse2::VECTOR_SIZE) as u32,
+        // there is no u32 to parse
+        _ => panic!("No u32 to parse from input string!"),
+    }
+}
+
+/// Parses an u32 from the input string using portable SIMD whenever is
+/// possible up to the first occurence of `separator` or `eol`
+#[inline]
+#[cfg(feature = "backend-portable")]
+unsafe fn parse_integer_separator_portable(s: &[u8], separator: u8, eol: u8) -> u32 {
+    if s.len() < portable::VECTOR_SIZE {
+        let (_, _, value) = portable::parse_short_field(s, separator, eol);
+        return value as u32;
+    }
+    // find the first occurence of a separator
+    let index = portable::last_byte_without_separator(s, separator, eol);
+    match index {
+        1..=10 => portable::parse_digits_simd(s, index as usize) as u32,
+        // all the chars are numeric, maybe padded?
+        32 => portable::parse_digits_simd(s, portable::VECTOR_SIZE) as u32,
+        // there is no u32 to parse
+        _ => panic!("No u32 to parse from input string!"),
+    }
+}
+
+/// Parses an `u64` from the input string when possible using AVX2 intrinsics.
+///
+/// If the input string is empty or the parsing function encounters an overflow,
+/// then `None` will be returned.
+#[inline]
+#[cfg(all(target_arch = "x86_64", feature = "backend-avx2"))]
+unsafe fn parse_u64_checked_avx2(s: &[u8]) -> Option<u64> {
+    // Go back to fallback implementation if the string doesn't have the correct
+    // size
+    if s.len() < avx::VECTOR_SIZE {
+        return fallback::parse_u64_bytes(s);
+    }
+
+    let index = avx::last_digit_byte(s);
+    match index {
+        1..=20 => avx::parse_u64_chars_simd(s, index as usize),
+        // Either there is no digit or there are more than 20 of them, which
+        // may still fit in an u64 when padded with zeros
+        _ => fallback::parse_u64_bytes(s),
+    }
+}
+
+/// Parses an `u64` from the input string when possible using SSE4.1
+/// intrinsics.
+///
+/// If the input string is empty or the parsing function encounters an overflow,
+/// then `None` will be returned.
+#[inline]
+#[cfg(all(target_arch = "x86_64", any(feature = "backend-sse41", feature = "backend-sse42")))]
+unsafe fn parse_u64_checked_sse41(s: &[u8]) -> Option<u64> {
+    // Go back to fallback implementation if the string doesn't have the correct
+    // size
+    if s.len() < sse41::VECTOR_SIZE {
+        return fallback::parse_u64_bytes(s);
+    }
+
+    let index = match sse41::last_digit_byte(s) {
+        // the first 16 chars are all digits, so the remaining ones are counted
+        // to find the end of the number
+        32 => {
+            sse41::VECTOR_SIZE as u32 + fallback::last_digit_byte_bytes(&s[sse41::VECTOR_SIZE..])
+        }
+        index => index,
+    };
+    match index {
+        1..=20 => sse41::parse_u64_chars_simd(s, index as usize),
+        // Either there is no digit or there are more than 20 of them, which
+        // may still fit in an u64 when padded with zeros
+        _ => fallback::parse_u64_bytes(s),
+    }
+}
+
+/// Parses an `u64` from the input string when possible using SSE2
+/// intrinsics.
+///
+/// If the input string is empty or the parsing function encounters an overflow,
+/// then `None` will be returned.
+#[inline]
+#[cfg(all(target_arch = "x86_64", feature = "backend-sse2"))]
+unsafe fn parse_u64_checked_sse2(s: &[u8]) -> Option<u64> {
+    // Go back to fallback implementation if the string doesn't have the correct
+    // size
+    if s.len() < sse2::VECTOR_SIZE {
+        return fallback::parse_u64_bytes(s);
+    }
+
+    let index = match sse2::last_digit_byte(s) {
+        // the first 16 chars are all digits, so the remaining ones are counted
+        // to find the end of the number
+        32 => {
+            sse2::VECTOR_SIZE as u32 + fallback::last_digit_byte_bytes(&s[sse2::VECTOR_SIZE..])
+        }
+        index => index,
+    };
+    match index {
+        1..=20 => sse2::parse_u64_chars_simd(s, index as usize),
+        // Either there is no digit or there are more than 20 of them, which
+        // may still fit in an u64 when padded with zeros
+        _ => fallback::parse_u64_bytes(s),
+    }
+}
+
+/// Parses an `u64` from the input string when possible using portable
+/// SIMD.
+///
+/// If the input string is empty or the parsing function encounters an overflow,
+/// then `None` will be returned.
+#[inline]
+#[cfg(feature = "backend-portable")]
+unsafe fn parse_u64_checked_portable(s: &[u8]) -> Option<u64> {
+    // Go back to fallback implementation if the string doesn't have the correct
+    // size
+    if s.len() < portable::VECTOR_SIZE {
+        return fallback::parse_u64_bytes(s);
+    }
+
+    let index = match portable::last_digit_byte(s) {
+        // the first 16 chars are all digits, so the remaining ones are counted
+        // to find the end of the number
+        32 => {
+            portable::VECTOR_SIZE as u32 + fallback::last_digit_byte_bytes(&s[portable::VECTOR_SIZE..])
+        }
+        index => index,
+    };
+    match index {
+        1..=20 => portable::parse_u64_chars_simd(s, index as usize),
+        // Either there is no digit or there are more than 20 of them, which
+        // may still fit in an u64 when padded with zeros
+        _ => fallback::parse_u64_bytes(s),
+    }
+}
+
+/// Parses an `u64` from the input string up to the first occurrence of
+/// `sep` or `eol` when possible using AVX2 intrinsics.
+///
+/// If the input string is empty or the parsing function encounters an overflow,
+/// then `None` will be returned.
+#[inline]
+#[cfg(all(target_arch = "x86_64", feature = "backend-avx2"))]
+unsafe fn parse_u64_sep_checked_avx2(s: &[u8], sep: u8, eol: u8) -> Option<u64> {
+    // Go back to fallback implementation if the string doesn't have the correct
+    // size
+    if s.len() < avx::VECTOR_SIZE {
+        return fallback::parse_u64_separator_bytes(s, sep, eol);
+    }
+
+    let index = avx::last_byte_without_separator(s, sep, eol);
+    match index {
+        // a non digit before the terminator
+        1..=20 if !avx::check_all_chars_are_valid(&s[..index as usize]) => None,
+        1..=20 => avx::parse_u64_chars_simd(s, index as usize),
+        // Use the default implementation since the number has too many digits
+        // to be handled by the SIMD implementations
+        _ => fallback::parse_u64_separator_bytes(s, sep, eol),
+    }
+}
+
+/// Parses an `u64` from the input string up to the first occurrence of
+/// `sep` or `eol` when possible using SSE4.1 intrinsics.
+///
+/// If the input string is empty or the parsing function encounters an overflow,
+/// then `None` will be returned.
+#[inline]
+#[cfg(all(target_arch = "x86_64", any(feature = "backend-sse41", feature = "backend-sse42")))]
+unsafe fn parse_u64_sep_checked_sse41(s: &[u8], sep: u8, eol: u8) -> Option<u64> {
+    // Go back to fallback implementation if the string doesn't have the correct
+    // size
+    if s.len() < sse41::VECTOR_SIZE {
+        return fallback::parse_u64_separator_bytes(s, sep, eol);
+    }
+
+    let index = match sse41::last_byte_without_separator(s, sep, eol) {
+        // no separator in the first 16 chars, so the remaining ones are
+        // scanned to find the end of the number
+        32 => {
+            sse41::VECTOR_SIZE as u32
+                + fallback::last_byte_without_separator_bytes(&s[sse41::VECTOR_SIZE..], sep, eol)
+        }
+        index => index,
+    };
+    match index {
+        // a non digit before the terminator
+        1..=20 if !sse41::check_all_chars_are_valid(&s[..index as usize]) => None,
+        1..=20 => sse41::parse_u64_chars_simd(s, index as usize),
+        // Use the default implementation since the number has too many digits
+        // to be handled by the SIMD implementations
+        _ => fallback::parse_u64_separator_bytes(s, sep, eol),
+    }
+}
+
+/// Parses an `u64` from the input string up to the first occurrence of
+/// `sep` or `eol` when possible using SSE2 intrinsics.
+///
+/// If the input string is empty or the parsing function encounters an overflow,
+/// then `None` will be returned.
+#[inline]
+#[cfg(all(target_arch = "x86_64", feature = "backend-sse2"))]
+unsafe fn parse_u64_sep_checked_sse2(s: &[u8], sep: u8, eol: u8) -> Option<u64> {
+    // Go back to fallback implementation if the string doesn't have the correct
+    // size
+    if s.len() < sse2::VECTOR_SIZE {
+        return fallback::parse_u64_separator_bytes(s, sep, eol);
+    }
+
+    let index = match sse2::last_byte_without_separator(s, sep, eol) {
+        // no separator in the first 16 chars, so the remaining ones are
+        // scanned to find the end of the number
+        32 => {
+            sse2::VECTOR_SIZE as u32
+                + fallback::last_byte_without_separator_bytes(&s[sse2::VECTOR_SIZE..], sep, eol)
+        }
+        index => index,
+    };
+    match index {
+        // a non digit before the terminator
+        1..=20 if !sse2::check_all_chars_are_valid(&s[..index as usize]) => None,
+        1..=20 => sse2::parse_u64_chars_simd(s, index as usize),
+        // Use the default implementation since the number has too many digits
+        // to be handled by the SIMD implementations
+        _ => fallback::parse_u64_separator_bytes(s, sep, eol),
+    }
+}
+
+/// Parses an `u64` from the input string up to the first occurrence of
+/// `sep` or `eol` when possible using portable SIMD.
+///
+/// If the input string is empty or the parsing function encounters an overflow,
+/// then `None` will be returned.
+#[inline]
+#[cfg(feature = "backend-portable")]
+unsafe fn parse_u64_sep_checked_portable(s: &[u8], sep: u8, eol: u8) -> Option<u64> {
+    // Go back to fallback implementation if the string doesn't have the correct
+    // size
+    if s.len() < portable::VECTOR_SIZE {
+        return fallback::parse_u64_separator_bytes(s, sep, eol);
+    }
+
+    let index = match portable::last_byte_without_separator(s, sep, eol) {
+        // no separator in the first 16 chars, so the remaining ones are
+        // scanned to find the end of the number
+        32 => {
+            portable::VECTOR_SIZE as u32
+                + fallback::last_byte_without_separator_bytes(&s[portable::VECTOR_SIZE..], sep, eol)
+        }
+        index => index,
+    };
+    match index {
+        // a non digit before the terminator
+        1..=20 if !portable::check_all_chars_are_valid(&s[..index as usize]) => None,
+        1..=20 => portable::parse_u64_chars_simd(s, index as usize),
+        // Use the default implementation since the number has too many digits
+        // to be handled by the SIMD implementations
+        _ => fallback::parse_u64_separator_bytes(s, sep, eol),
+    }
+}
+
+
+/// Parses an `u32` from the input string when possible using AVX2 intrinsics,
+/// reporting the reason of the failure.
+#[inline]
+#[cfg(all(target_arch = "x86_64", feature = "backend-avx2"))]
+unsafe fn try_parse_integer_avx2(s: &[u8]) -> Result<u32, ParseError> {
+    // Go back to fallback implementation if the string doesn't have the correct
+    // size
+    if s.len() < avx::VECTOR_SIZE {
+        return fallback::try_parse_integer(s);
+    }
+
+    let index = avx::last_digit_byte(s);
+    match index {
+        // the string isn't empty, so the first byte is not a digit
+        0 => Err(ParseError::new(ParseErrorKind::InvalidDigit, 0)),
+        1..=9 => Ok(avx::parse_digits_simd(s, index as usize) as u32),
+        // Use the default implementation since we cannot guarantee overflow
+        // check in the SIMD implementations
+        _ => fallback::try_parse_integer(s),
+    }
+}
+
+/// Parses an `u32` from the input string when possible using SSE4.1
+/// intrinsics, reporting the reason of the failure.
+#[inline]
+#[cfg(all(target_arch = "x86_64", any(feature = "backend-sse41", feature = "backend-sse42")))]
+unsafe fn try_parse_integer_sse41(s: &[u8]) -> Result<u32, ParseError> {
+    // Go back to fallback implementation if the string doesn't have the correct
+    // size
+    if s.len() < sse41::VECTOR_SIZE {
+        return fallback::try_parse_integer(s);
+    }
+
+    let index = sse41::last_digit_byte(s);
+    match index {
+        // the string isn't empty, so the first byte is not a digit
+        0 => Err(ParseError::new(ParseErrorKind::InvalidDigit, 0)),
+        1..=9 => Ok(sse41::parse_digits_simd(s, index as usize) as u32),
+        // Use the default implementation since we cannot guarantee overflow
+        // check in the SIMD implementations
+        _ => fallback::try_parse_integer(s),
+    }
+}
+
+/// Parses an `u32` from the input string when possible using SSE2 intrinsics,
+/// reporting the reason of the failure.
+#[inline]
+#[cfg(all(target_arch = "x86_64", feature = "backend-sse2"))]
+unsafe fn try_parse_integer_sse2(s: &[u8]) -> Result<u32, ParseError> {
+    // Go back to fallback implementation if the string doesn't have the correct
+    // size
+    if s.len() < sse2::VECTOR_SIZE {
+        return fallback::try_parse_integer(s);
+    }
+
+    let index = sse2::last_digit_byte(s);
+    match index {
+        // the string isn't empty, so the first byte is not a digit
+        0 => Err(ParseError::new(ParseErrorKind::InvalidDigit, 0)),
+        1..=9 => Ok(sse2::parse_digits_simd(s, index as usize) as u32),
+        // Use the default implementation since we cannot guarantee overflow
+        // check in the SIMD implementations
+        _ => fallback::try_parse_integer(s),
+    }
+}
+
+/// Parses an `u32` from the input string when possible using portable SIMD,
+/// reporting the reason of the failure.
+#[inline]
+#[cfg(feature = "backend-portable")]
+unsafe fn try_parse_integer_portable(s: &[u8]) -> Result<u32, ParseError> {
+    // Go back to fallback implementation if the string doesn't have the correct
+    // size
+    if s.len() < portable::VECTOR_SIZE {
+        return fallback::try_parse_integer(s);
+    }
+
+    let index = portable::last_digit_byte(s);
+    match index {
+        // the string isn't empty, so the first byte is not a digit
+        0 => Err(ParseError::new(ParseErrorKind::InvalidDigit, 0)),
+        1..=9 => Ok(portable::parse_digits_simd(s, index as usize) as u32),
+        // Use the default implementation since we cannot guarantee overflow
+        // check in the SIMD implementations
+        _ => fallback::try_parse_integer(s),
+    }
+}
+
+/// Shared body of the `try_parse_integer_sep_*` implementations: `masks`
+/// returns the terminators and the non digits among the first `vector_size`
+/// chars of the string, and `parse_digits` combines up to 9 of its digits.
+#[inline(always)]
+#[cfg(any(
+    all(
+        target_arch = "x86_64",
+        any(
+            feature = "backend-sse41",
+            feature = "backend-sse42",
+            feature = "backend-avx2",
+            feature = "backend-sse2"
+        )
+    ),
+    feature = "backend-portable"
+))]
+fn try_parse_integer_sep_simd(
+    s: &[u8],
+    sep: u8,
+    eol: u8,
+    vector_size: usize,
+    masks: impl FnOnce(&[u8], u8, u8) -> (u32, u32),
+    parse_digits: impl FnOnce(&[u8], usize) -> u64,
+) -> Result<u32, ParseError> {
+    // Go back to fallback implementation if the string doesn't have the correct
+    // size
+    if s.len() < vector_size {
+        return fallback::try_parse_integer_separator(s, sep, eol);
+    }
+
+    let (separators, non_digits) = masks(s, sep, eol);
+    let index = separators.trailing_zeros();
+    let invalid = non_digits.trailing_zeros();
+    // a non digit before the separator, which is a non digit itself
+    if invalid < index {
+        return Err(ParseError::new(ParseErrorKind::InvalidDigit, invalid as usize));
+    }
+    match index {
+        0 => Err(ParseError::new(ParseErrorKind::Empty, 0)),
+        1..=9 => Ok(parse_digits(s, index as usize) as u32),
+        // Either the number may overflow or the separator is past the loaded
+        // chars
+        _ => fallback::try_parse_integer_separator(s, sep, eol),
+    }
+}
+
+/// Parses an `u32` from the input string up to the first occurrence of `sep`
+/// or `eol` when possible using AVX2 intrinsics, reporting the reason of the
+/// failure.
+#[inline]
+#[cfg(all(target_arch = "x86_64", feature = "backend-avx2"))]
+#[target_feature(enable = "avx2")]
+unsafe fn try_parse_integer_sep_avx2(s: &[u8], sep: u8, eol: u8) -> Result<u32, ParseError> {
+    let masks = |s: &[u8], sep, eol| avx::separator_and_non_digit_masks(s, sep, eol);
+    let parse_digits = |s: &[u8], len| avx::parse_digits_simd(s, len);
+    try_parse_integer_sep_simd(s, sep, eol, avx::VECTOR_SIZE, masks, parse_digits)
+}
+
+/// Parses an `u32` from the input string up to the first occurrence of `sep`
+/// or `eol` when possible using SSE4.1 intrinsics, reporting the reason of the
+/// failure.
+#[inline]
+#[cfg(all(target_arch = "x86_64", any(feature = "backend-sse41", feature = "backend-sse42")))]
+#[target_feature(enable = "sse4.1")]
+unsafe fn try_parse_integer_sep_sse41(s: &[u8], sep: u8, eol: u8) -> Result<u32, ParseError> {
+    let masks = |s: &[u8], sep, eol| sse41::separator_and_non_digit_masks(s, sep, eol);
+    let parse_digits = |s: &[u8], len| sse41::parse_digits_simd(s, len);
+    try_parse_integer_sep_simd(s, sep, eol, sse41::VECTOR_SIZE, masks, parse_digits)
+}
+
+/// Parses an `u32` from the input string up to the first occurrence of any
+/// byte of `separators` when possible using SSE4.1 intrinsics, comparing the
+/// chars against one separator at a time.
+#[inline]
+#[cfg(all(target_arch = "x86_64", feature = "backend-sse41"))]
+unsafe fn try_parse_integer_sep_set_sse41(
+    s: &[u8],
+    separators: &SeparatorSet,
+) -> Result<u32, ParseError> {
+    // Go back to fallback implementation if the string doesn't have the correct
+    // size
+    if s.len() < sse41::VECTOR_SIZE {
+        return fallback::try_parse_integer_separator_set(s, separators);
+    }
+    let index = sse41::separator_set_mask(s, separators).trailing_zeros();
+    let invalid = sse41::last_digit_byte(s);
+    // a non digit before the separator, which is a non digit itself
+    if invalid < index {
+        return Err(ParseError::new(ParseErrorKind::InvalidDigit, invalid as usize));
+    }
+    match index {
+        0 => Err(ParseError::new(ParseErrorKind::Empty, 0)),
+        // Up to 9 digits there can't be any overflow
+        1..=9 => Ok(sse41::parse_digits_simd(s, index as usize) as u32),
+        // Either the number may overflow or the separator is past the first
+        // 16 chars
+        _ => fallback::try_parse_integer_separator_set(s, separators),
+    }
+}
+
+/// Parses an `u32` from the input string up to the first occurrence of any
+/// byte of `separators` when possible using SSE4.2 intrinsics, comparing the
+/// chars against the whole set with a single string instruction.
+///
+/// The small sets are compared one separator at a time like in the SSE4.1
+/// implementation, which is faster for them.
+#[inline]
+#[cfg(all(target_arch = "x86_64", any(feature = "backend-sse42", feature = "backend-avx2")))]
+unsafe fn try_parse_integer_sep_set_sse42(
+    s: &[u8],
+    separators: &SeparatorSet,
+) -> Result<u32, ParseError> {
+    // Go back to fallback implementation if the string doesn't have the correct
+    // size
+    if s.len() < sse42::VECTOR_SIZE {
+        return fallback::try_parse_integer_separator_set(s, separators);
+    }
+    let index = if separators.len() < sse42::MIN_STRING_SEPARATORS {
+        sse41::separator_set_mask(s, separators).trailing_zeros()
+    } else {
+        sse42::first_separator_in_set(s, separators)
+    };
+    // the digits are a range of two bytes, faster to check with the SSE4.1
+    // comparisons
+    let invalid = sse41::last_digit_byte(s);
+    // a non digit before the separator, which is a non digit itself
+    if invalid < index {
+        return Err(ParseError::new(ParseErrorKind::InvalidDigit, invalid as usize));
+    }
+    match index {
+        0 => Err(ParseError::new(ParseErrorKind::Empty, 0)),
+        // Up to 9 digits there can't be any overflow
+        1..=9 => Ok(sse41::parse_digits_simd(s, index as usize) as u32),
+        // Either the number may overflow or the separator is past the first
+        // 16 chars
+        _ => fallback::try_parse_integer_separator_set(s, separators),
+    }
+}
+
+/// Parses an `u32` from the input string up to the first occurrence of `sep`
+/// or `eol` when possible using SSE2 intrinsics, reporting the reason of the
+/// failure.
+#[inline]
+#[cfg(all(target_arch = "x86_64", feature = "backend-sse2"))]
+#[target_feature(enable = "sse2")]
+unsafe fn try_parse_integer_sep_sse2(s: &[u8], sep: u8, eol: u8) -> Result<u32, ParseError> {
+    let masks = |s: &[u8], sep, eol| sse2::separator_and_non_digit_masks(s, sep, eol);
+    let parse_digits = |s: &[u8], len| sse2::parse_digits_simd(s, len);
+    try_parse_integer_sep_simd(s, sep, eol, sse2::VECTOR_SIZE, masks, parse_digits)
+}
+
+/// Parses an `u32` from the input string up to the first occurrence of `sep`
+/// or `eol` when possible using portable SIMD, reporting the reason of the
+/// failure.
+#[inline]
+#[cfg(feature = "backend-portable")]
+unsafe fn try_parse_integer_sep_portable(s: &[u8], sep: u8, eol: u8) -> Result<u32, ParseError> {
+    let masks = portable::separator_and_non_digit_masks;
+    let parse_digits = portable::parse_digits_simd;
+    try_parse_integer_sep_simd(s, sep, eol, portable::VECTOR_SIZE, masks, parse_digits)
+}
+
+/// Shared body of the `parse_integer_prefix_*` implementations:
+/// `last_digit_byte` returns the number of leading digits among the first
+/// `vector_size` chars of the string, and `parse_digits` combines up to 9 of
+/// them.
+#[inline(always)]
+#[cfg(any(
+    all(
+        target_arch = "x86_64",
+        any(
+            feature = "backend-sse41",
+            feature = "backend-sse42",
+            feature = "backend-avx2",
+            feature = "backend-sse2"
+        )
+    ),
+    feature = "backend-portable"
+))]
+fn parse_integer_prefix_simd(
+    s: &[u8],
+    vector_size: usize,
+    last_digit_byte: impl FnOnce(&[u8]) -> u32,
+    parse_digits: impl FnOnce(&[u8], usize) -> u64,
+) -> Option<(u32, usize)> {
+    // Go back to fallback implementation if the string doesn't have the correct
+    // size
+    if s.len() < vector_size {
+        return fallback::parse_integer_prefix(s);
+    }
+
+    let index = last_digit_byte(s);
+    let value = match index {
+        1..=9 => parse_digits(s, index as usize) as u32,
+        // Use the default implementation since we cannot guarantee overflow
+        // check in the SIMD implementations
+        _ => return fallback::parse_integer_prefix(s),
+    };
+    Some((value, index as usize))
+}
+
+/// Parses an `u32` from the input string when possible using AVX2 intrinsics,
+/// returning it along with the number of digits consumed.
+#[inline]
+#[cfg(all(target_arch = "x86_64", feature = "backend-avx2"))]
+#[target_feature(enable = "avx2")]
+unsafe fn parse_integer_prefix_avx2(s: &[u8]) -> Option<(u32, usize)> {
+    let last_digit_byte = |s: &[u8]| avx::last_digit_byte(s);
+    let parse_digits = |s: &[u8], len| avx::parse_digits_simd(s, len);
+    parse_integer_prefix_simd(s, avx::VECTOR_SIZE, last_digit_byte, parse_digits)
+}
+
+/// Parses an `u32` from the input string when possible using SSE4.1
+/// intrinsics, returning it along with the number of digits consumed.
+#[inline]
+#[cfg(all(target_arch = "x86_64", any(feature = "backend-sse41", feature = "backend-sse42")))]
+#[target_feature(enable = "sse4.1")]
+unsafe fn parse_integer_prefix_sse41(s: &[u8]) -> Option<(u32, usize)> {
+    let last_digit_byte = |s: &[u8]| sse41::last_digit_byte(s);
+    let parse_digits = |s: &[u8], len| sse41::parse_digits_simd(s, len);
+    parse_integer_prefix_simd(s, sse41::VECTOR_SIZE, last_digit_byte, parse_digits)
+}
+
+/// Parses an `u32` from the input string when possible using SSE2 intrinsics,
+/// returning it along with the number of digits consumed.
+#[inline]
+#[cfg(all(target_arch = "x86_64", feature = "backend-sse2"))]
+#[target_feature(enable = "sse2")]
+unsafe fn parse_integer_prefix_sse2(s: &[u8]) -> Option<(u32, usize)> {
+    let last_digit_byte = |s: &[u8]| sse2::last_digit_byte(s);
+    let parse_digits = |s: &[u8], len| sse2::parse_digits_simd(s, len);
+    parse_integer_prefix_simd(s, sse2::VECTOR_SIZE, last_digit_byte, parse_digits)
+}
+
+/// Parses an `u32` from the input string when possible using portable SIMD,
+/// returning it along with the number of digits consumed.
+#[inline]
+#[cfg(feature = "backend-portable")]
+unsafe fn parse_integer_prefix_portable(s: &[u8]) -> Option<(u32, usize)> {
+    let last_digit_byte = portable::last_digit_byte;
+    let parse_digits = portable::parse_digits_simd;
+    parse_integer_prefix_simd(s, portable::VECTOR_SIZE, last_digit_byte, parse_digits)
+}
+
+/// Shared body of the `parse_integer_sep_prefix_*` implementations, with the
+/// same `masks` and `parse_digits` of `try_parse_integer_sep_simd`.
+#[inline(always)]
+#[cfg(any(
+    all(
+        target_arch = "x86_64",
+        any(
+            feature = "backend-sse41",
+            feature = "backend-sse42",
+            feature = "backend-avx2",
+            feature = "backend-sse2"
+        )
+    ),
+    feature = "backend-portable"
+))]
+fn parse_integer_sep_prefix_simd(
+    s: &[u8],
+    sep: u8,
+    eol: u8,
+    vector_size: usize,
+    masks: impl FnOnce(&[u8], u8, u8) -> (u32, u32),
+    parse_digits: impl FnOnce(&[u8], usize) -> u64,
+) -> Option<(u32, usize, Terminator)> {
+    // Go back to fallback implementation if the string doesn't have the correct
+    // size
+    if s.len() < vector_size {
+        return fallback::parse_integer_separator_prefix(s, sep, eol);
+    }
+
+    let (separators, non_digits) = masks(s, sep, eol);
+    let index = separators.trailing_zeros();
+    // a non digit before the separator, which is a non digit itself
+    if non_digits.trailing_zeros() < index {
+        return None;
+    }
+    let value = match index {
+        1..=9 => parse_digits(s, index as usize) as u32,
+        // Either there is no number, it may overflow or the separator is past
+        // the loaded chars
+        _ => return fallback::parse_integer_separator_prefix(s, sep, eol),
+    };
+    let index = index as usize;
+    Some((value, index, Terminator::at(s, index, sep)))
+}
+
+/// Parses an `u32` from the input string up to the first occurrence of `sep`
+/// or `eol` when possible using AVX2 intrinsics, returning it along with the
+/// number of bytes consumed and the terminator found.
+#[inline]
+#[cfg(all(target_arch = "x86_64", feature = "backend-avx2"))]
+#[target_feature(enable = "avx2")]
+unsafe fn parse_integer_sep_prefix_avx2(
+    s: &[u8],
+    sep: u8,
+    eol: u8,
+) -> Option<(u32, usize, Terminator)> {
+    let masks = |s: &[u8], sep, eol| avx::separator_and_non_digit_masks(s, sep, eol);
+    let parse_digits = |s: &[u8], len| avx::parse_digits_simd(s, len);
+    parse_integer_sep_prefix_simd(s, sep, eol, avx::VECTOR_SIZE, masks, parse_digits)
+}
+
+/// Parses an `u32` from the input string up to the first occurrence of `sep`
+/// or `eol` when possible using SSE4.1 intrinsics, returning it along with the
+/// number of bytes consumed and the terminator found.
+#[inline]
+#[cfg(all(target_arch = "x86_64", any(feature = "backend-sse41", feature = "backend-sse42")))]
+#[target_feature(enable = "sse4.1")]
+unsafe fn parse_integer_sep_prefix_sse41(
+    s: &[u8],
+    sep: u8,
+    eol: u8,
+) -> Option<(u32, usize, Terminator)> {
+    let masks = |s: &[u8], sep, eol| sse41::separator_and_non_digit_masks(s, sep, eol);
+    let parse_digits = |s: &[u8], len| sse41::parse_digits_simd(s, len);
+    parse_integer_sep_prefix_simd(s, sep, eol, sse41::VECTOR_SIZE, masks, parse_digits)
+}
+
+/// Parses an `u32` from the input string up to the first occurrence of `sep`
+/// or `eol` when possible using SSE2 intrinsics, returning it along with the
+/// number of bytes consumed and the terminator found.
+#[inline]
+#[cfg(all(target_arch = "x86_64", feature = "backend-sse2"))]
+#[target_feature(enable = "sse2")]
+unsafe fn parse_integer_sep_prefix_sse2(
+    s: &[u8],
+    sep: u8,
+    eol: u8,
+) -> Option<(u32, usize, Terminator)> {
+    let masks = |s: &[u8], sep, eol| sse2::separator_and_non_digit_masks(s, sep, eol);
+    let parse_digits = |s: &[u8], len| sse2::parse_digits_simd(s, len);
+    parse_integer_sep_prefix_simd(s, sep, eol, sse2::VECTOR_SIZE, masks, parse_digits)
+}
+
+/// Parses an `u32` from the input string up to the first occurrence of `sep`
+/// or `eol` when possible using portable SIMD, returning it along with the
+/// number of bytes consumed and the terminator found.
+#[inline]
+#[cfg(feature = "backend-portable")]
+unsafe fn parse_integer_sep_prefix_portable(
+    s: &[u8],
+    sep: u8,
+    eol: u8,
+) -> Option<(u32, usize, Terminator)> {
+    let masks = portable::separator_and_non_digit_masks;
+    let parse_digits = portable::parse_digits_simd;
+    parse_integer_sep_prefix_simd(s, sep, eol, portable::VECTOR_SIZE, masks, parse_digits)
+}
+
+/// Parses the field of `buf` made of `len` digits starting at offset `start`,
+/// where the digits have already been validated.
+#[inline]
+#[cfg(all(
+    target_arch = "x86_64",
+    any(feature = "backend-sse41", feature = "backend-sse42", feature = "backend-avx2")
+))]
+pub(crate) unsafe fn parse_validated_field(buf: &[u8], start: usize, len: usize) -> Result<u32, ParseError> {
+    // Up to 9 digits there can't be any overflow
+    if len <= 9 && start + sse41::VECTOR_SIZE <= buf.len() {
+        return Ok(sse41::parse_digits_simd(&buf[start..], len) as u32);
+    }
+    fallback::try_parse_digits(&buf[start..start + len]).map_err(|e| e.shifted(start))
+}
+
+/// Same as `parse_validated_field`, with the SSE2 kernels.
+#[inline]
+#[cfg(all(target_arch = "x86_64", feature = "backend-sse2"))]
+pub(crate) unsafe fn parse_validated_field_sse2(
+    buf: &[u8],
+    start: usize,
+    len: usize,
+) -> Result<u32, ParseError> {
+    // Up to 9 digits there can't be any overflow
+    if len <= 9 && start + sse2::VECTOR_SIZE <= buf.len() {
+        return Ok(sse2::parse_digits_simd(&buf[start..], len) as u32);
+    }
+    fallback::try_parse_digits(&buf[start..start + len]).map_err(|e| e.shifted(start))
+}
+
+/// Same as `parse_validated_field`, with the portable SIMD kernels.
+#[inline]
+#[cfg(feature = "backend-portable")]
+pub(crate) fn parse_validated_field_portable(
+    buf: &[u8],
+    start: usize,
+    len: usize,
+) -> Result<u32, ParseError> {
+    // Up to 9 digits there can't be any overflow
+    if len <= 9 && start + portable::VECTOR_SIZE <= buf.len() {
+        return Ok(portable::parse_digits_simd(&buf[start..], len) as u32);
+    }
+    fallback::try_parse_digits(&buf[start..start + len]).map_err(|e| e.shifted(start))
+}
+
+/// Shared body of the `parse_delimited_into_*` implementations: `masks`
+/// returns the terminators and the non digits among the first `vector_size`
+/// chars of the string, and `parse_field` parses the validated digits of a
+/// field, as `parse_validated_field` does.
+#[inline(always)]
+#[cfg(any(
+    all(
+        target_arch = "x86_64",
+        any(
+            feature = "backend-sse41",
+            feature = "backend-sse42",
+            feature = "backend-avx2",
+            feature = "backend-sse2"
+        )
+    ),
+    feature = "backend-portable"
+))]
+fn parse_delimited_into_simd(
+    buf: &[u8],
+    sep: u8,
+    eol: u8,
+    out: &mut [u32],
+    vector_size: usize,
+    masks: impl Fn(&[u8], u8, u8) -> (u32, u32),
+    parse_field: impl Fn(&[u8], usize, usize) -> Result<u32, ParseError>,
+) -> Result<(usize, usize), ParseError> {
+    let mut written = 0;
+    // offset of the field to parse, where the next chunk is loaded
+    let mut start = 0;
+    while written < out.len() && start + vector_size <= buf.len() {
+        let (mut separators, non_digits) = masks(&buf[start..], sep, eol);
+        // offset of the current field inside the chunk
+        let mut field = 0;
+        while separators != 0 && written < out.len() {
+            let end = separators.trailing_zeros() as usize;
+            let len = end - field;
+            // the separator is a non digit itself, so `invalid` is at most `len`
+            let invalid = (non_digits >> field).trailing_zeros() as usize;
+            if invalid < len {
+                return Err(ParseError::new(ParseErrorKind::InvalidDigit, start + field + invalid));
+            }
+            if len == 0 {
+                return Err(ParseError::new(ParseErrorKind::Empty, start + field));
+            }
+            out[written] = parse_field(buf, start + field, len)?;
+            written += 1;
+            // clear the lowest separator
+            separators &= separators - 1;
+            field = end + 1;
+        }
+        if field == 0 && written < out.len() {
+            // No terminator in the whole chunk: the field may still fit in an
+            // `u32` if it has leading zeros, so let the fallback handle it
+            let (value, end) = fallback::parse_field(buf, start, sep, eol)?;
+            out[written] = value;
+            written += 1;
+            field = end + 1 - start;
+        }
+        start += field;
+    }
+    fallback::parse_fields(buf, start, sep, eol, out, written)
+}
+
+/// Parses all the fields of `buf` into `out` using AVX2 intrinsics, locating
+/// every field ending in the same 32 chars with a single load.
+#[inline]
+#[cfg(all(target_arch = "x86_64", feature = "backend-avx2"))]
+#[target_feature(enable = "avx2")]
+unsafe fn parse_delimited_into_avx2(
+    buf: &[u8],
+    sep: u8,
+    eol: u8,
+    out: &mut [u32],
+) -> Result<(usize, usize), ParseError> {
+    let masks = |s: &[u8], sep, eol| avx::separator_and_non_digit_masks(s, sep, eol);
+    let parse_field = |buf: &[u8], start, len| parse_validated_field(buf, start, len);
+    parse_delimited_into_simd(buf, sep, eol, out, avx::VECTOR_SIZE, masks, parse_field)
+}
+
+/// Parses all the fields of `buf` into `out` using SSE4.1 intrinsics, locating
+/// every field ending in the same 16 chars with a single load.
+#[inline]
+#[cfg(all(target_arch = "x86_64", any(feature = "backend-sse41", feature = "backend-sse42")))]
+#[target_feature(enable = "sse4.1")]
+unsafe fn parse_delimited_into_sse41(
+    buf: &[u8],
+    sep: u8,
+    eol: u8,
+    out: &mut [u32],
+) -> Result<(usize, usize), ParseError> {
+    let masks = |s: &[u8], sep, eol| sse41::separator_and_non_digit_masks(s, sep, eol);
+    let parse_field = |buf: &[u8], start, len| parse_validated_field(buf, start, len);
+    parse_delimited_into_simd(buf, sep, eol, out, sse41::VECTOR_SIZE, masks, parse_field)
+}
+
+/// Parses all the fields of `buf` into `out` using SSE2 intrinsics, locating
+/// every field ending in the same 16 chars with a single load.
+#[inline]
+#[cfg(all(target_arch = "x86_64", feature = "backend-sse2"))]
+#[target_feature(enable = "sse2")]
+unsafe fn parse_delimited_into_sse2(
+    buf: &[u8],
+    sep: u8,
+    eol: u8,
+    out: &mut [u32],
+) -> Result<(usize, usize), ParseError> {
+    let masks = |s: &[u8], sep, eol| sse2::separator_and_non_digit_masks(s, sep, eol);
+    let parse_field = |buf: &[u8], start, len| parse_validated_field_sse2(buf, start, len);
+    parse_delimited_into_simd(buf, sep, eol, out, sse2::VECTOR_SIZE, masks, parse_field)
+}
+
+/// Parses all the fields of `buf` into `out` using portable SIMD, locating
+/// every field ending in the same 16 chars with a single load.
+#[inline]
+#[cfg(feature = "backend-portable")]
+unsafe fn parse_delimited_into_portable(
+    buf: &[u8],
+    sep: u8,
+    eol: u8,
+    out: &mut [u32],
+) -> Result<(usize, usize), ParseError> {
+    let masks = portable::separator_and_non_digit_masks;
+    let parse_field = parse_validated_field_portable;
+    parse_delimited_into_simd(buf, sep, eol, out, portable::VECTOR_SIZE, masks, parse_field)
+}
+
+// -----------------------------------------------------------------------------
+//                             Benchmark only function
+//
+// This functions are useful only to benchmark the performance of the different
+// algorithms. Make sure to pass `--features "benchmark"` during the compilation
+// step to enable the following definitions.
+// -----------------------------------------------------------------------------
+
+/// SSE4.2 implementation for `parse_integer_separator` meant to be used only
+/// during benchamark
+#[cfg(feature = "benchmark")]
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse4.2")]
+unsafe fn parse_integer_separator_sse42(s: &[u8], separator: u8, eol: u8) -> u32 {
+    if s.len() < sse41::VECTOR_SIZE {
+        return fallback::parse_integer_separator_unchecked_bytes(s, separator, eol);
+    }
+    // find the first occurence of a separator
+    let index = sse42::last_byte_without_separator(s, separator, eol);
+    match index {
+        1..=10 => return sse41::parse_digits_simd(s, index as usize) as u32,
+        // all the chars are numeric, maybe padded?
+        32 => return sse41::parse_integer_simd_all_numbers(s),
+        // there is no u32 to parse
+        _ => panic!("No u32 to parse from input string!"),
+    }
+}
+
+/// SSE4.2 implementation for `parse_integer` meant to be used only during
+/// benchamark
+#[cfg(feature = "benchmark")]
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse4.2")]
+unsafe fn parse_integer_sse42(s: &[u8]) -> u32 {
+    if s.len() < sse41::VECTOR_SIZE {
+        return fallback::parse_integer_unchecked_bytes(s);
+    }
+    // find the first occurence of a separator
+    let index = sse42::last_digit_byte(s);
+    match index {
+        1..=10 => return sse41::parse_digits_simd(s, index as usize) as u32,
+        // all the chars are numeric, maybe padded?
+        32 => return sse41::parse_integer_simd_all_numbers(s),
+        // there is no u32 to parse
+        _ => panic!("No u32 to parse from input string!"),
+    }
+}
+
+/// Safe wrapper around `parse_integer_separator_sse41` to use only during
+/// benchmark
+#[cfg(feature = "benchmark")]
+#[inline]
+pub fn safe_parse_integer_separator_sse41(s: &str, separator: u8, eol: u8) -> u32 {
+    unsafe {
+        return parse_integer_separator_sse41(s.as_bytes(), separator, eol);
+    }
+}
+
+/// Safe wrapper around `parse_integer_separator_sse42` to use only during
+/// benchmark
+#[cfg(feature = "benchmark")]
+#[inline]
+pub fn safe_parse_integer_separator_sse42(s: &str, separator: u8, eol: u8) -> u32 {
+    unsafe {
+        return parse_integer_separator_sse42(s.as_bytes(), separator, eol);
+    }
+}
+
+/// Safe wrapper around `parse_integer_avx2` to use only during benchmark
+#[cfg(feature = "benchmark")]
+#[inline]
+pub fn safe_parse_integer_separator_avx2(s: &str, separator: u8, eol: u8) -> u32 {
+    unsafe {
+        return parse_integer_separator_avx2(s.as_bytes(), separator, eol);
+    }
+}
+
+/// Safe wrapper around `parse_integer_sse41` to use only during benchmark
+#[cfg(feature = "benchmark")]
+#[inline]
+pub fn safe_parse_integer_sse41(s: &str) -> u32 {
+    unsafe {
+        return parse_integer_sse41(s.as_bytes());
+    }
+}
+
+/// Safe wrapper around `parse_integer_sse42` to use only during benchmark
+#[cfg(feature = "benchmark")]
+#[inline]
+pub fn safe_parse_integer_sse42(s: &str) -> u32 {
+    unsafe {
+        return parse_integer_sse42(s.as_bytes());
+    }
+}
+
+/// Safe wrapper around `parse_integer_avx2` to use only during benchmark
+#[cfg(feature = "benchmark")]
+#[inline]
+pub fn safe_parse_integer_avx2(s: &str) -> u32 {
+    unsafe {
+        return parse_integer_avx2(s.as_bytes());
+    }
+}
+
+/// Safe wrapper around `sse41::check_all_chars_are_valid` to use only during
+/// benchmark
+#[cfg(feature = "benchmark")]
+#[inline]
+pub fn safe_check_all_chars_are_valid_sse41(s: &str) -> bool {
+    unsafe {
+        return sse41::check_all_chars_are_valid(s.as_bytes());
+    }
+}
+
+/// Safe wrapper around `sse42::check_all_chars_are_valid` to use only during
+/// benchmark
+#[cfg(feature = "benchmark")]
+#[inline]
+pub fn safe_check_all_chars_are_valid_sse42(s: &str) -> bool {
+    unsafe {
+        return sse42::check_all_chars_are_valid(s.as_bytes());
+    }
+}
+
+/// Safe wrapper around `avx::check_all_chars_are_valid` to use only during
+/// benchmark
+#[cfg(feature = "benchmark")]
+#[inline]
+pub fn safe_check_all_chars_are_valid_avx(s: &str) -> bool {
+    unsafe {
+        return avx::check_all_chars_are_valid(s.as_bytes());
+    }
+}
+
+/// Safe wrapper around `sse41::last_byte_without_separator` to use only during
+/// benchmark
+#[cfg(feature = "benchmark")]
+#[inline]
+pub fn safe_last_byte_without_separator_sse41(s: &str, separator: u8, eol: u8) -> u32 {
+    unsafe {
+        return sse41::last_byte_without_separator(s.as_bytes(), separator, eol);
+    }
+}
+
+/// Safe wrapper around `sse42::last_byte_without_separator` to use only during
+/// benchmark
+#[cfg(feature = "benchmark")]
+#[inline]
+pub fn safe_last_byte_without_separator_sse42(s: &str, separator: u8, eol: u8) -> u32 {
+    unsafe {
+        return sse42::last_byte_without_separator(s.as_bytes(), separator, eol);
+    }
+}
+
+/// Safe wrapper around `avx::last_byte_without_separator` to use only during
+/// benchmark
+#[cfg(feature = "benchmark")]
+#[inline]
+pub fn safe_last_byte_without_separator_avx(s: &str, separator: u8, eol: u8) -> u32 {
+    unsafe {
+        return avx::last_byte_without_separator(s.as_bytes(), separator, eol);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    static SEP: u8 = b',';
+    static EOL: u8 = b'\n';
+
+    // ===== fallback tests =====
+
+    // ===== `parse_integer_separator` tests =====
+
+    #[test]
+    fn parse_integer_separator_empty() {
+        let s = "";
+        assert_eq!(parse_integer_separator(s, SEP, EOL), None);
+    }
+
+    #[test]
+    fn parse_integer_separator_no_digit() {
+        let s = ",,\n123";
+        assert_eq!(parse_integer_separator(s, SEP, EOL), None);
+    }
+
+    #[test]
+    fn parse_integer_separator_one_digit() {
+        let s = "1,123,23\n0";
+        assert_eq!(parse_integer_separator(s, SEP, EOL), Some(1));
+    }
+
+    #[test]
+    fn parse_integer_separator_more_digits() {
+        let s = "1123,23\n0";
+        assert_eq!(parse_integer_separator(s, SEP, EOL), Some(1123));
+    }
+
+    #[test]
+    fn parse_integer_separator_all_digits() {
+        let s = "112323";
+        assert_eq!(parse_integer_separator(s, SEP, EOL), Some(112323));
+    }
+
+    // ===== `parse_integer` tests =====
+
+    #[test]
+    fn parse_integer_empty() {
+        let s = "";
+        assert_eq!(parse_integer(s), None);
+    }
+
+    #[test]
+    fn parse_integer_no_digit() {
+        let s = ",,\n123";
+        assert_eq!(parse_integer(s), None);
+    }
+
+    #[test]
+    fn parse_integer_one_digit() {
+        let s = "1,123,23\n0";
+        assert_eq!(parse_integer(s), Some(1));
+    }
+
+    #[test]
+    fn parse_integer_more_digits() {
+        let s = "1123,23\n0";
+        assert_eq!(parse_integer(s), Some(1123));
+    }
+
+    #[test]
+    fn parse_integer_all_digits() {
+        let s = "112323";
+        assert_eq!(parse_integer(s), Some(112323));
+    }
+
+    // ===== AVX2 tests =====
+
+    // ===== `parse_integer_separator` tests =====
+
+    #[cfg(all(target_arch = "x86_64", target_feature = "avx2"))]
+    #[test]
+    fn check_all_chars_are_valid_valid_avx2() {
+        let s = "12345678901234567890123456789012";
+        assert!(check_all_chars_are_valid(s));
+    }
+
+    #[cfg(all(target_arch = "x86_64", any(target_feature = "avx2")))]
+    #[test]
+    fn parse_integer_separator_avx2_fallback() {
+        let s = "12345678";
+        assert_eq!(parse_integer_separator(s, SEP, EOL), Some(12345678));
+    }
+
+    #[cfg(all(target_arch = "x86_64", any(target_feature = "avx2")))]
+    #[test]
+    fn parse_integer_separator_avx2_no_digit() {
+        let s = ",,345678901234567890123456789012";
+        assert_eq!(parse_integer_separator(s, SEP, EOL), None);
+    }
+
+    #[cfg(all(target_arch = "x86_64", any(target_feature = "avx2")))]
+    #[test]
+    fn parse_integer_separator_avx2_one_digit() {
+        let s = "1,345678901234567890123456789012";
+        assert_eq!(parse_integer_separator(s, SEP, EOL), Some(1));
+    }
+
+    #[cfg(all(target_arch = "x86_64", any(target_feature = "avx2")))]
+    #[test]
+    fn parse_integer_separator_avx2_more_digits() {
+        let s = "12345678,01234567890123456789012";
+        assert_eq!(parse_integer_separator(s, SEP, EOL), Some(12345678));
+    }
+
+    #[cfg(all(target_arch = "x86_64", any(target_feature = "avx2")))]
+    #[test]
+    fn parse_integer_separator_avx2_all_digits_padded() {
+        let s = "00000000000000000000000012345678";
+        assert_eq!(parse_integer_separator(s, SEP, EOL), Some(12345678));
+    }
+
+    // ===== `parse_integer` tests =====
+
+    #[cfg(all(target_arch = "x86_64", any(target_feature = "avx2")))]
+    #[test]
+    fn parse_integer_avx2_fallback() {
+        let s = "12345678";
+        assert_eq!(parse_integer(s), Some(12345678));
+    }
+
+    #[cfg(all(target_arch = "x86_64", any(target_feature = "avx2")))]
+    #[test]
+    fn parse_integer_avx2_no_digit() {
+        let s = ",,345678901234567890123456789012";
+        assert_eq!(parse_integer(s), None);
+    }
+
+    #[cfg(all(target_arch = "x86_64", any(target_feature = "avx2")))]
+    #[test]
+    fn parse_integer_avx2_one_digit() {
+        let s = "1,345678901234567890123456789012";
+        assert_eq!(parse_integer(s), Some(1));
+    }
+
+    #[cfg(all(target_arch = "x86_64", any(target_feature = "avx2")))]
+    #[test]
+    fn parse_integer_avx2_more_digits() {
+        let s = "12345678,01234567890123456789012";
+        assert_eq!(parse_integer(s), Some(12345678));
+    }
+
+    #[cfg(all(target_arch = "x86_64", any(target_feature = "avx2")))]
+    #[test]
+    fn parse_integer_avx2_all_digits_padded() {
+        let s = "00000000000000000000000012345678";
+        assert_eq!(parse_integer(s), Some(12345678));
+    }
+
+    // ===== SSE4.1/2 tests =====
+
+    // ===== `parse_integer_separator` tests =====
+
+    #[cfg(all(
+        target_arch = "x86_64",
+        any(target_feature = "sse4.1", target_feature = "sse4.2")
+    ))]
+    #[test]
+    fn parse_integer_separator_sse4_fallback() {
+        let s = "1123,23";
+        assert_eq!(parse_integer_separator(s, SEP, EOL), Some(1123));
+    }
+
+    #[cfg(all(
+        target_arch = "x86_64",
+        any(target_feature = "sse4.1", target_feature = "sse4.2")
+    ))]
+    #[test]
+    fn parse_integer_separator_sse4_no_digit() {
+        let s = ",\n12345678912345";
+        assert_eq!(parse_integer_separator(s, SEP, EOL), None);
+    }
+
+    #[cfg(all(
+        target_arch = "x86_64",
+        any(target_feature = "sse4.1", target_feature = "sse4.2")
+    ))]
+    #[test]
+    fn parse_integer_separator_sse4_one_digit() {
+        let s = "1,12345678912345";
+        assert_eq!(parse_integer_separator(s, SEP, EOL), Some(1));
+    }
+
+    #[cfg(all(
+        target_arch = "x86_64",
+        any(target_feature = "sse4.1", target_feature = "sse4.2")
+    ))]
+    #[test]
+    fn parse_integer_separator_sse4_more_digits() {
+        let s = "12345607,8912345";
+        assert_eq!(parse_integer_separator(s, SEP, EOL), Some(12345607));
+    }
+
+    #[cfg(all(
+        target_arch = "x86_64",
+        any(target_feature = "sse4.1", target_feature = "sse4.2")
+    ))]
+    #[test]
+    fn parse_integer_separator_sse4_all_digits_padded() {
+        let s = "0000000012345678";
+        assert_eq!(parse_integer_separator(s, SEP, EOL), Some(12345678));
+    }
+
+    // ===== `parse_integer` tests =====
+
+    #[cfg(all(
+        target_arch = "x86_64",
+        any(target_feature = "sse4.1", target_feature = "sse4.2")
+    ))]
+    #[test]
+    fn parse_integer_sse4_fallback() {
+        let s = "1123,23";
+        assert_eq!(parse_integer(s), Some(1123));
+    }
+
+    #[cfg(all(
+        target_arch = "x86_64",
+        any(target_feature = "sse4.1", target_feature = "sse4.2")
+    ))]
+    #[test]
+    fn parse_integer_sse4_no_digit() {
+        let s = ",\n12345678912345";
+        assert_eq!(parse_integer(s), None);
+    }
+
+    #[cfg(all(
+        target_arch = "x86_64",
+        any(target_feature = "sse4.1", target_feature = "sse4.2")
+    ))]
+    #[test]
+    fn parse_integer_sse4_one_digit() {
+        let s = "1,12345678912345";
+        assert_eq!(parse_integer(s), Some(1));
+    }
+
+    #[cfg(all(
+        target_arch = "x86_64",
+        any(target_feature = "sse4.1", target_feature = "sse4.2")
+    ))]
+    #[test]
+    fn parse_integer_sse4_more_digits() {
+        let s = "12345607,8912345";
+        assert_eq!(parse_integer(s), Some(12345607));
+    }
+
+    #[cfg(all(
+        target_arch = "x86_64",
+        any(target_feature = "sse4.1", target_feature = "sse4.2")
+    ))]
+    #[test]
+    fn parse_integer_sse4_all_digits_padded() {
+        let s = "0000000012345678";
+        assert_eq!(parse_integer(s), Some(12345678));
+    }
+
+    #[cfg(all(
+        target_arch = "x86_64",
+        any(target_feature = "sse4.1", target_feature = "sse4.2")
+    ))]
+    #[test]
+    #[should_panic]
+    fn parse_integer_sse4_unchecked_zero_digit() {
+        let s = ",,00000012345678";
+        unsafe {
+            parse_integer_unchecked(s);
+        }
+    }
+
+    #[cfg(all(
+        target_arch = "x86_64",
+        any(target_feature = "sse4.1", target_feature = "sse4.2")
+    ))]
+    #[test]
+    #[should_panic]
+    fn parse_integer_sse4_unchecked_more_than_10_digits() {
+        let s = "123445123456,8456";
+        unsafe {
+            parse_integer_unchecked(s);
+        }
+    }
+
+    #[test]
+    fn parse_integer_ten_digits_every_backend() {
+        for backend in Backend::available() {
+            let parser = Parser::with_backend(backend).unwrap();
+            assert_eq!(parser.parse_integer(b"4294967295,67890123456789012345"), Some(u32::MAX));
+            assert_eq!(parser.parse_integer(b"4294967296,67890123456789012345"), None);
+            assert_eq!(parser.parse_integer(b"9999999999,67890123456789012345"), None);
+            assert_eq!(parser.parse_integer(b"1700000000\n67890123456789012345"), Some(1700000000));
+            assert_eq!(
+                parser.parse_integer_separator(b"4294967295,67890123456789012345", SEP, EOL),
+                Some(u32::MAX)
+            );
+            assert_eq!(
+                parser.parse_integer_separator(b"4294967296\n67890123456789012345", SEP, EOL),
+                None
+            );
+        }
+    }
+
+    #[test]
+    fn parse_integer_short_input_every_backend() {
+        for backend in Backend::available() {
+            let parser = Parser::with_backend(backend).unwrap();
+            assert_eq!(parser.parse_integer(b"42"), Some(42));
+            assert_eq!(parser.parse_integer(b"42,7"), Some(42));
+            assert_eq!(parser.parse_integer(b""), None);
+            assert_eq!(parser.parse_integer(b",7"), None);
+            assert_eq!(parser.parse_integer(b"4294967296"), None);
+            assert_eq!(parser.parse_integer(b"000004294967295"), Some(u32::MAX));
+            assert_eq!(parser.parse_integer_separator(b"42\n", SEP, EOL), Some(42));
+            assert_eq!(parser.parse_integer_separator(b"4,2", SEP, EOL), Some(4));
+            assert_eq!(parser.parse_integer_separator(b",42", SEP, EOL), None);
+            unsafe {
+                assert_eq!(parser.parse_integer_unchecked(b"123"), 123);
+                assert_eq!(parser.parse_integer_unchecked(b""), 0);
+                assert_eq!(parser.parse_integer_separator_unchecked(b"123,4", SEP, EOL), 123);
+            }
+        }
+    }
+
+    #[test]
+    fn parse_integer_separator_short_junk_every_backend() {
+        // every field shorter than a vector, with a junk byte at every offset,
+        // is parsed as the fallback implementation does
+        let digits = b"429496729542949";
+        let junk = [b'a', b'/', b':', b' ', b'\0', b'\xff', SEP, EOL];
+        for backend in Backend::available() {
+            let parser = Parser::with_backend(backend).unwrap();
+            for &separator in [SEP, b'\0', b'5'].iter() {
+                let check = |s: &[u8]| {
+                    assert_eq!(
+                        parser.parse_integer_separator(s, separator, EOL),
+                        fallback::parse_integer_separator_bytes(s, separator, EOL),
+                        "{} {:?}",
+                        backend,
+                        s
+                    );
+                };
+                for len in 0..=digits.len() {
+                    let mut s = digits[..len].to_vec();
+                    check(&s);
+                    for i in 0..len {
+                        for &byte in junk.iter() {
+                            s[i] = byte;
+                            check(&s);
+                        }
+                        s[i] = digits[i];
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn parse_integer_separator_non_digit_every_backend() {
+        // fields shorter than a vector, filling it and continuing past it,
+        // with a non digit at every offset
+        for backend in Backend::available() {
+            let parser = Parser::with_backend(backend).unwrap();
+            assert_eq!(parser.parse_integer_separator(b"743a,", SEP, EOL), None, "{}", backend);
+            for len in 1..=40 {
+                for i in 0..len {
+                    for junk in "a/: \0".chars() {
+                        let field = format!("{}{}{}", "0".repeat(i), junk, "1".repeat(len - i - 1));
+                        for s in [format!("{},5\n", field), field].iter() {
+                            let parsed = parser.parse_integer_separator(s.as_bytes(), SEP, EOL);
+                            assert_eq!(parsed, None, "{} {:?}", backend, s);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn check_all_chars_are_valid_every_backend() {
+        for backend in Backend::available() {
+            let parser = Parser::with_backend(backend).unwrap();
+            let mut s = [b'4'; 70];
+            assert!(parser.check_all_chars_are_valid(&s), "{}", backend);
+            for i in 0..s.len() {
+                s[i] = b'a';
+                for len in 0..=s.len() {
+                    assert_eq!(parser.check_all_chars_are_valid(&s[..len]), len <= i, "{}", backend);
+                }
+                s[i] = b'4';
+            }
+        }
+    }
+
+    #[test]
+    fn first_non_digit_every_backend() {
+        // every length up to three AVX2 vectors, with the non digit at every
+        // offset, in every vector and in the overlapping tail
+        let digits = [b'7'; 100];
+        for backend in Backend::available() {
+            let parser = Parser::with_backend(backend).unwrap();
+            for len in 0..digits.len() {
+                let mut s = digits[..len].to_vec();
+                assert_eq!(parser.first_non_digit(&s), None, "{} {}", backend, len);
+                assert_eq!(parser.find_separator(&s, SEP, EOL), None, "{} {}", backend, len);
+                for i in 0..len {
+                    s[i] = b',';
+                    assert_eq!(parser.first_non_digit(&s), Some(i), "{} {}", backend, len);
+                    assert_eq!(parser.find_separator(&s, SEP, EOL), Some(i), "{} {}", backend, len);
+                    s[i] = b'7';
+                }
+            }
+            let s = b"123x456,789\n";
+            assert_eq!(parser.first_non_digit(s), Some(3));
+            assert_eq!(parser.find_separator(s, SEP, EOL), Some(7));
+            assert_eq!(parser.find_separator(&s[8..], SEP, EOL), Some(3));
+        }
+    }
+
+    // ===== `parse_u64` tests =====
+
+    #[test]
+    fn parse_u64_empty() {
+        let s = "";
+        assert_eq!(parse_u64(s), None);
+    }
+
+    #[test]
+    fn parse_u64_more_digits() {
+        let s = "12345678901,1";
+        assert_eq!(parse_u64(s), Some(12345678901));
+    }
+
+    #[test]
+    fn parse_u64_max_u64() {
+        let s = format!("{}", u64::MAX);
+        assert_eq!(parse_u64(&s), Some(u64::MAX));
+    }
+
+    #[test]
+    fn parse_u64_separator_more_digits() {
+        let s = "12345678901,1";
+        assert_eq!(parse_u64_separator(s, SEP, EOL), Some(12345678901));
+    }
+
+    #[test]
+    fn parse_u64_separator_non_digit_every_backend() {
+        for backend in Backend::available() {
+            let parser = Parser::with_backend(backend).unwrap();
+            let parse = |s: &str| parser.parse_u64_separator(s.as_bytes(), SEP, EOL);
+            assert_eq!(parse("35379384940304294\0\n"), None, "{}", backend);
+            assert_eq!(parse("35379384940304294\n\0"), Some(35379384940304294), "{}", backend);
+            // fields shorter than a vector, filling it and continuing past it
+            for len in 1..=40 {
+                for i in 0..len {
+                    let field = format!("{}a{}", "0".repeat(i), "1".repeat(len - i - 1));
+                    for s in [format!("{},5\n", field), field].iter() {
+                        assert_eq!(parse(s), None, "{} {:?}", backend, s);
+                    }
+                }
+            }
+        }
+    }
+
+    #[cfg(all(target_arch = "x86_64", target_feature = "avx2"))]
+    #[test]
+    fn parse_u64_avx2_max_u64() {
+        let s = "18446744073709551615,11111111111";
+        assert_eq!(parse_u64(s), Some(u64::MAX));
+    }
+
+    #[cfg(all(target_arch = "x86_64", target_feature = "avx2"))]
+    #[test]
+    fn parse_u64_avx2_overflow() {
+        let s = "18446744073709551616,11111111111";
+        assert_eq!(parse_u64(s), None);
+    }
+
+    #[cfg(all(target_arch = "x86_64", target_feature = "avx2"))]
+    #[test]
+    fn parse_u64_avx2_small_number() {
+        let s = "123456,1111111111111111111111111";
+        assert_eq!(parse_u64(s), Some(123456));
+    }
+
+    #[cfg(all(target_arch = "x86_64", target_feature = "avx2"))]
+    #[test]
+    fn parse_u64_avx2_all_digits_padded() {
+        let s = "00000000000018446744073709551615";
+        assert_eq!(parse_u64(s), Some(u64::MAX));
+    }
+
+    #[cfg(all(target_arch = "x86_64", target_feature = "avx2"))]
+    #[test]
+    fn parse_u64_separator_avx2_more_digits() {
+        let s = "1234567890123456,111111111111111";
+        assert_eq!(parse_u64_separator(s, SEP, EOL), Some(1234567890123456));
+    }
+
+    #[cfg(all(
+        target_arch = "x86_64",
+        any(target_feature = "sse4.1", target_feature = "sse4.2")
+    ))]
+    #[test]
+    fn parse_u64_sse4_max_u64() {
+        let s = "18446744073709551615,";
+        assert_eq!(parse_u64(s), Some(u64::MAX));
+    }
+
+    #[cfg(all(
+        target_arch = "x86_64",
+        any(target_feature = "sse4.1", target_feature = "sse4.2")
+    ))]
+    #[test]
+    fn parse_u64_sse4_overflow() {
+        let s = "18446744073709551616,";
+        assert_eq!(parse_u64(s), None);
+    }
+
+    #[cfg(all(
+        target_arch = "x86_64",
+        any(target_feature = "sse4.1", target_feature = "sse4.2")
+    ))]
+    #[test]
+    fn parse_u64_sse4_more_digits() {
+        let s = "12345678901,2345";
+        assert_eq!(parse_u64(s), Some(12345678901));
+    }
+
+    #[cfg(all(
+        target_arch = "x86_64",
+        any(target_feature = "sse4.1", target_feature = "sse4.2")
+    ))]
+    #[test]
+    fn parse_u64_separator_sse4_max_u64() {
+        let s = "18446744073709551615\n";
+        assert_eq!(parse_u64_separator(s, SEP, EOL), Some(u64::MAX));
+    }
+
+    // ===== signed integers tests =====
+
+    #[test]
+    fn parse_i32_no_sign() {
+        let s = "1234,5";
+        assert_eq!(parse_i32(s), Some(1234));
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    static SEP: u8 = b',';
-    static EOL: u8 = b'\n';
+    #[test]
+    fn parse_i32_plus_sign() {
+        let s = "+1234";
+        assert_eq!(parse_i32(s), Some(1234));
+    }
 
-    // ===== fallback tests =====
+    #[test]
+    fn parse_i32_minus_sign() {
+        let s = "-1234";
+        assert_eq!(parse_i32(s), Some(-1234));
+    }
 
-    // ===== `parse_integer_separator` tests =====
+    #[test]
+    fn parse_i32_only_sign() {
+        assert_eq!(parse_i32("-"), None);
+        assert_eq!(parse_i32("+"), None);
+    }
 
     #[test]
-    fn parse_integer_separator_empty() {
-        let s = "";
-        assert_eq!(parse_integer_separator(s, SEP, EOL), None);
+    fn parse_i32_double_sign() {
+        let s = "--1234";
+        assert_eq!(parse_i32(s), None);
     }
 
     #[test]
-    fn parse_integer_separator_no_digit() {
-        let s = ",,\n123";
-        assert_eq!(parse_integer_separator(s, SEP, EOL), None);
+    fn parse_i32_bounds() {
+        assert_eq!(parse_i32(&i32::MAX.to_string()), Some(i32::MAX));
+        assert_eq!(parse_i32(&i32::MIN.to_string()), Some(i32::MIN));
     }
 
     #[test]
-    fn parse_integer_separator_one_digit() {
-        let s = "1,123,23\n0";
-        assert_eq!(parse_integer_separator(s, SEP, EOL), Some(1));
+    fn parse_i32_overflow() {
+        assert_eq!(parse_i32("2147483648"), None);
+        assert_eq!(parse_i32("-2147483649"), None);
     }
 
     #[test]
-    fn parse_integer_separator_more_digits() {
-        let s = "1123,23\n0";
-        assert_eq!(parse_integer_separator(s, SEP, EOL), Some(1123));
+    fn parse_i32_separator_minus_sign() {
+        let s = "-1234,5678";
+        assert_eq!(parse_i32_separator(s, SEP, EOL), Some(-1234));
     }
 
     #[test]
-    fn parse_integer_separator_all_digits() {
-        let s = "112323";
-        assert_eq!(parse_integer_separator(s, SEP, EOL), Some(112323));
+    fn parse_i32_separator_sign_then_separator() {
+        let s = "-,1234";
+        assert_eq!(parse_i32_separator(s, SEP, EOL), None);
     }
 
-    // ===== `parse_integer` tests =====
+    #[test]
+    fn parse_i64_bounds() {
+        assert_eq!(parse_i64(&i64::MAX.to_string()), Some(i64::MAX));
+        assert_eq!(parse_i64(&i64::MIN.to_string()), Some(i64::MIN));
+    }
 
     #[test]
-    fn parse_integer_empty() {
-        let s = "";
-        assert_eq!(parse_integer(s), None);
+    fn parse_i64_overflow() {
+        assert_eq!(parse_i64("9223372036854775808"), None);
+        assert_eq!(parse_i64("-9223372036854775809"), None);
     }
 
     #[test]
-    fn parse_integer_no_digit() {
-        let s = ",,\n123";
-        assert_eq!(parse_integer(s), None);
+    fn parse_i64_separator_minus_sign() {
+        let s = "-12345678901\n1";
+        assert_eq!(parse_i64_separator(s, SEP, EOL), Some(-12345678901));
     }
 
+    #[cfg(all(target_arch = "x86_64", target_feature = "avx2"))]
     #[test]
-    fn parse_integer_one_digit() {
-        let s = "1,123,23\n0";
-        assert_eq!(parse_integer(s), Some(1));
+    fn parse_i32_avx2_minus_sign() {
+        let s = "-12345678,01234567890123456789012";
+        assert_eq!(parse_i32(s), Some(-12345678));
     }
 
+    #[cfg(all(target_arch = "x86_64", target_feature = "avx2"))]
     #[test]
-    fn parse_integer_more_digits() {
-        let s = "1123,23\n0";
-        assert_eq!(parse_integer(s), Some(1123));
+    fn parse_i64_separator_avx2_min_i64() {
+        let s = "-9223372036854775808,11111111111111";
+        assert_eq!(parse_i64_separator(s, SEP, EOL), Some(i64::MIN));
     }
 
+    #[cfg(all(
+        target_arch = "x86_64",
+        any(target_feature = "sse4.1", target_feature = "sse4.2")
+    ))]
     #[test]
-    fn parse_integer_all_digits() {
-        let s = "112323";
-        assert_eq!(parse_integer(s), Some(112323));
+    fn parse_i32_separator_sse4_minus_sign() {
+        let s = "-12345607,8912345";
+        assert_eq!(parse_i32_separator(s, SEP, EOL), Some(-12345607));
     }
 
-    // ===== AVX2 tests =====
+    #[cfg(all(
+        target_arch = "x86_64",
+        any(target_feature = "sse4.1", target_feature = "sse4.2")
+    ))]
+    #[test]
+    fn parse_i64_sse4_max_i64() {
+        let s = "+9223372036854775807,";
+        assert_eq!(parse_i64(s), Some(i64::MAX));
+    }
 
-    // ===== `parse_integer_separator` tests =====
+    // ===== byte slices tests =====
 
-    #[cfg(all(target_arch = "x86_64", target_feature = "avx2"))]
     #[test]
-    fn check_all_chars_are_valid_valid_avx2() {
-        let s = "12345678901234567890123456789012";
-        assert!(check_all_chars_are_valid(s));
+    fn parse_integer_bytes_invalid_utf8_after_number() {
+        let s = b"1234,\xff\xfe";
+        assert_eq!(parse_integer_bytes(s), Some(1234));
     }
 
-    #[cfg(all(target_arch = "x86_64", any(target_feature = "avx2")))]
     #[test]
-    fn parse_integer_separator_avx2_fallback() {
-        let s = "12345678";
-        assert_eq!(parse_integer_separator(s, SEP, EOL), Some(12345678));
+    fn parse_integer_separator_bytes_more_digits() {
+        let s = b"1123,23\n0";
+        assert_eq!(parse_integer_separator_bytes(s, SEP, EOL), Some(1123));
     }
 
-    #[cfg(all(target_arch = "x86_64", any(target_feature = "avx2")))]
     #[test]
-    fn parse_integer_separator_avx2_no_digit() {
-        let s = ",,345678901234567890123456789012";
-        assert_eq!(parse_integer_separator(s, SEP, EOL), None);
+    fn last_digit_byte_bytes_some_digits() {
+        let s = b"0123,!49";
+        assert_eq!(last_digit_byte_bytes(s), 4);
     }
 
-    #[cfg(all(target_arch = "x86_64", any(target_feature = "avx2")))]
     #[test]
-    fn parse_integer_separator_avx2_one_digit() {
-        let s = "1,345678901234567890123456789012";
-        assert_eq!(parse_integer_separator(s, SEP, EOL), Some(1));
+    fn last_byte_without_separator_bytes_more_digits() {
+        let s = b"123,44321\n";
+        assert_eq!(last_byte_without_separator_bytes(s, SEP, EOL), 3);
     }
 
-    #[cfg(all(target_arch = "x86_64", any(target_feature = "avx2")))]
     #[test]
-    fn parse_integer_separator_avx2_more_digits() {
-        let s = "12345678,01234567890123456789012";
-        assert_eq!(parse_integer_separator(s, SEP, EOL), Some(12345678));
+    fn check_all_chars_are_valid_bytes_invalid() {
+        let s = b"1234\xff";
+        assert!(!check_all_chars_are_valid_bytes(s));
     }
 
-    #[cfg(all(target_arch = "x86_64", any(target_feature = "avx2")))]
     #[test]
-    fn parse_integer_separator_avx2_all_digits_padded() {
-        let s = "00000000000000000000000012345678";
-        assert_eq!(parse_integer_separator(s, SEP, EOL), Some(12345678));
+    fn parse_i64_bytes_minus_sign() {
+        let s = b"-12345678901,\xff";
+        assert_eq!(parse_i64_bytes(s), Some(-12345678901));
     }
 
-    // ===== `parse_integer` tests =====
+    #[cfg(all(target_arch = "x86_64", target_feature = "avx2"))]
+    #[test]
+    fn parse_integer_bytes_avx2_invalid_utf8() {
+        let s = b"12345678,\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff\xff";
+        assert_eq!(parse_integer_bytes(s), Some(12345678));
+    }
+
+    // ===== `try_parse_integer` tests =====
 
-    #[cfg(all(target_arch = "x86_64", any(target_feature = "avx2")))]
     #[test]
-    fn parse_integer_avx2_fallback() {
-        let s = "12345678";
-        assert_eq!(parse_integer(s), Some(12345678));
+    fn try_parse_integer_empty() {
+        let e = try_parse_integer("").unwrap_err();
+        assert_eq!((e.kind(), e.offset()), (ParseErrorKind::Empty, 0));
     }
 
-    #[cfg(all(target_arch = "x86_64", any(target_feature = "avx2")))]
     #[test]
-    fn parse_integer_avx2_no_digit() {
+    fn try_parse_integer_more_digits() {
+        let s = "1123,23\n0";
+        assert_eq!(try_parse_integer(s), Ok(1123));
+    }
+
+    #[test]
+    fn try_parse_integer_separator_missing_terminator() {
+        let s = "1123";
+        let e = try_parse_integer_separator(s, SEP, EOL).unwrap_err();
+        assert_eq!((e.kind(), e.offset()), (ParseErrorKind::MissingTerminator, 4));
+    }
+
+    #[cfg(all(target_arch = "x86_64", target_feature = "avx2"))]
+    #[test]
+    fn try_parse_integer_avx2_no_digit() {
         let s = ",,345678901234567890123456789012";
-        assert_eq!(parse_integer(s), None);
+        let e = try_parse_integer(s).unwrap_err();
+        assert_eq!((e.kind(), e.offset()), (ParseErrorKind::InvalidDigit, 0));
     }
 
-    #[cfg(all(target_arch = "x86_64", any(target_feature = "avx2")))]
+    #[cfg(all(target_arch = "x86_64", target_feature = "avx2"))]
     #[test]
-    fn parse_integer_avx2_one_digit() {
-        let s = "1,345678901234567890123456789012";
-        assert_eq!(parse_integer(s), Some(1));
+    fn try_parse_integer_avx2_overflow() {
+        let s = "4294967296,11111111111111111111";
+        let e = try_parse_integer(s).unwrap_err();
+        assert_eq!((e.kind(), e.offset()), (ParseErrorKind::Overflow, 9));
     }
 
-    #[cfg(all(target_arch = "x86_64", any(target_feature = "avx2")))]
+    #[cfg(all(target_arch = "x86_64", target_feature = "avx2"))]
     #[test]
-    fn parse_integer_avx2_more_digits() {
+    fn try_parse_integer_separator_avx2_more_digits() {
         let s = "12345678,01234567890123456789012";
-        assert_eq!(parse_integer(s), Some(12345678));
+        assert_eq!(try_parse_integer_separator(s, SEP, EOL), Ok(12345678));
     }
 
-    #[cfg(all(target_arch = "x86_64", any(target_feature = "avx2")))]
+    #[cfg(all(target_arch = "x86_64", target_feature = "avx2"))]
     #[test]
-    fn parse_integer_avx2_all_digits_padded() {
-        let s = "00000000000000000000000012345678";
-        assert_eq!(parse_integer(s), Some(12345678));
+    fn try_parse_integer_separator_avx2_invalid_digit() {
+        let s = "1234a678,01234567890123456789012";
+        let e = try_parse_integer_separator(s, SEP, EOL).unwrap_err();
+        assert_eq!((e.kind(), e.offset()), (ParseErrorKind::InvalidDigit, 4));
     }
 
-    // ===== SSE4.1/2 tests =====
+    #[cfg(all(target_arch = "x86_64", target_feature = "avx2"))]
+    #[test]
+    fn try_parse_integer_separator_avx2_empty_field() {
+        let s = "\n12345678901234567890123456789012";
+        let e = try_parse_integer_separator(s, SEP, EOL).unwrap_err();
+        assert_eq!((e.kind(), e.offset()), (ParseErrorKind::Empty, 0));
+    }
 
-    // ===== `parse_integer_separator` tests =====
+    #[cfg(all(target_arch = "x86_64", target_feature = "avx2"))]
+    #[test]
+    fn try_parse_integer_separator_avx2_invalid_digit_past_vector() {
+        let s = "000000000000000000000000000000001x,";
+        let e = try_parse_integer_separator(s, SEP, EOL).unwrap_err();
+        assert_eq!((e.kind(), e.offset()), (ParseErrorKind::InvalidDigit, 33));
+    }
 
     #[cfg(all(
         target_arch = "x86_64",
         any(target_feature = "sse4.1", target_feature = "sse4.2")
     ))]
     #[test]
-    fn parse_integer_separator_sse4_fallback() {
-        let s = "1123,23";
-        assert_eq!(parse_integer_separator(s, SEP, EOL), Some(1123));
+    fn try_parse_integer_separator_sse4_invalid_digit() {
+        let s = "123!5607,8912345";
+        let e = try_parse_integer_separator(s, SEP, EOL).unwrap_err();
+        assert_eq!((e.kind(), e.offset()), (ParseErrorKind::InvalidDigit, 3));
     }
 
     #[cfg(all(
@@ -882,71 +3087,152 @@ mod tests {
         any(target_feature = "sse4.1", target_feature = "sse4.2")
     ))]
     #[test]
-    fn parse_integer_separator_sse4_no_digit() {
-        let s = ",\n12345678912345";
-        assert_eq!(parse_integer_separator(s, SEP, EOL), None);
+    fn try_parse_integer_separator_sse4_more_digits() {
+        let s = "12345607,8912345";
+        assert_eq!(try_parse_integer_separator(s, SEP, EOL), Ok(12345607));
     }
 
-    #[cfg(all(
-        target_arch = "x86_64",
-        any(target_feature = "sse4.1", target_feature = "sse4.2")
-    ))]
+    // ===== separator set tests =====
+
     #[test]
-    fn parse_integer_separator_sse4_one_digit() {
-        let s = "1,12345678912345";
-        assert_eq!(parse_integer_separator(s, SEP, EOL), Some(1));
+    fn try_parse_integer_separator_set_every_backend() {
+        // a small set, compared one separator at a time, and a large one,
+        // compared with the string instructions
+        let small = SeparatorSet::new(b";\t").unwrap();
+        let large = SeparatorSet::new(b";\t|: ,\r\n#").unwrap();
+        let s = "12345607\t89123456;0";
+        for backend in Backend::available() {
+            let parser = Parser::with_backend(backend).unwrap();
+            for separators in [small, large].iter() {
+                let parse = |s: &[u8]| parser.try_parse_integer_separator_set(s, separators);
+                assert_eq!(parse(s.as_bytes()), Ok(12345607));
+                assert_eq!(parse(b"123;4567890123456"), Ok(123));
+                let e = parse(b"12.4;67890123456").unwrap_err();
+                assert_eq!((e.kind(), e.offset()), (ParseErrorKind::InvalidDigit, 2));
+                let e = parse(b";234567890123456").unwrap_err();
+                assert_eq!((e.kind(), e.offset()), (ParseErrorKind::Empty, 0));
+                let e = parse(b"4294967296\t67890").unwrap_err();
+                assert_eq!((e.kind(), e.offset()), (ParseErrorKind::Overflow, 9));
+                let e = parse(b"00000000000000000001").unwrap_err();
+                assert_eq!((e.kind(), e.offset()), (ParseErrorKind::MissingTerminator, 20));
+            }
+        }
     }
 
-    #[cfg(all(
-        target_arch = "x86_64",
-        any(target_feature = "sse4.1", target_feature = "sse4.2")
-    ))]
     #[test]
-    fn parse_integer_separator_sse4_more_digits() {
-        let s = "12345607,8912345";
-        assert_eq!(parse_integer_separator(s, SEP, EOL), Some(12345607));
+    fn try_parse_integer_separator_set_nul_separator() {
+        let separators = SeparatorSet::new(b"\0").unwrap();
+        assert_eq!(try_parse_integer_separator_set_bytes(b"1234\05678901234567", &separators), Ok(1234));
     }
 
-    #[cfg(all(
-        target_arch = "x86_64",
-        any(target_feature = "sse4.1", target_feature = "sse4.2")
-    ))]
+    // ===== prefix parsing tests =====
+
     #[test]
-    fn parse_integer_separator_sse4_all_digits_padded() {
-        let s = "0000000012345678";
-        assert_eq!(parse_integer_separator(s, SEP, EOL), Some(12345678));
+    fn parse_integer_prefix_more_digits() {
+        let s = "1123,23\n0";
+        assert_eq!(parse_integer_prefix(s), Some((1123, 4)));
     }
 
-    // ===== `parse_integer` tests =====
+    #[test]
+    fn parse_integer_prefix_empty() {
+        assert_eq!(parse_integer_prefix(""), None);
+    }
 
-    #[cfg(all(
-        target_arch = "x86_64",
-        any(target_feature = "sse4.1", target_feature = "sse4.2")
-    ))]
     #[test]
-    fn parse_integer_sse4_fallback() {
-        let s = "1123,23";
-        assert_eq!(parse_integer(s), Some(1123));
+    fn parse_integer_separator_prefix_separator() {
+        let s = "1123,23\n0";
+        assert_eq!(
+            parse_integer_separator_prefix(s, SEP, EOL),
+            Some((1123, 4, Terminator::Separator))
+        );
     }
 
-    #[cfg(all(
-        target_arch = "x86_64",
-        any(target_feature = "sse4.1", target_feature = "sse4.2")
-    ))]
     #[test]
-    fn parse_integer_sse4_no_digit() {
-        let s = ",\n12345678912345";
-        assert_eq!(parse_integer(s), None);
+    fn parse_integer_separator_prefix_tokenize() {
+        let s = "1,22\n333";
+        let mut fields = vec![];
+        let mut start = 0;
+        while let Some((n, len, terminator)) =
+            parse_integer_separator_prefix(&s[start..], SEP, EOL)
+        {
+            fields.push((n, terminator));
+            start += len + 1;
+            if terminator == Terminator::EndOfInput {
+                break;
+            }
+        }
+        assert_eq!(
+            fields,
+            vec![
+                (1, Terminator::Separator),
+                (22, Terminator::Eol),
+                (333, Terminator::EndOfInput)
+            ]
+        );
     }
 
-    #[cfg(all(
-        target_arch = "x86_64",
-        any(target_feature = "sse4.1", target_feature = "sse4.2")
-    ))]
     #[test]
-    fn parse_integer_sse4_one_digit() {
-        let s = "1,12345678912345";
-        assert_eq!(parse_integer(s), Some(1));
+    fn parse_integer_separator_prefix_non_digit() {
+        assert_eq!(parse_integer_separator_prefix("12a4,5", SEP, EOL), None);
+        let s = "/-5,0000000000000000000000000000000";
+        assert_eq!(parse_integer_separator_prefix(s, SEP, EOL), None);
+    }
+
+    #[test]
+    fn parse_integer_separator_prefix_non_digit_every_backend() {
+        // fields shorter than a vector, filling it and continuing past it,
+        // with a non digit at every offset
+        for backend in Backend::available() {
+            let parser = Parser::with_backend(backend).unwrap();
+            for len in 1..=40 {
+                for i in 0..len {
+                    for junk in "a/: \0".chars() {
+                        let field = format!("{}{}{}", "0".repeat(i), junk, "1".repeat(len - i - 1));
+                        for s in [format!("{},5\n", field), field].iter() {
+                            let parsed = parser.parse_integer_separator_prefix(s.as_bytes(), SEP, EOL);
+                            assert_eq!(parsed, None, "{} {:?}", backend, s);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn parse_integer_separator_prefix_agrees_on_every_backend() {
+        let fields = ["1", "4294967295", "4294967296", "000000000000000000042", "12a4", "/-5", ""];
+        for backend in Backend::available() {
+            let parser = Parser::with_backend(backend).unwrap();
+            for field in fields.iter() {
+                for tail in [",5\n", "\n", "", ",00000000000000000000000000000000"].iter() {
+                    let s = format!("{}{}", field, tail);
+                    assert_eq!(
+                        parser.parse_integer_separator_prefix(s.as_bytes(), SEP, EOL),
+                        fallback::parse_integer_separator_prefix(s.as_bytes(), SEP, EOL),
+                        "{} {:?}",
+                        backend,
+                        s
+                    );
+                }
+            }
+        }
+    }
+
+    #[cfg(all(target_arch = "x86_64", target_feature = "avx2"))]
+    #[test]
+    fn parse_integer_prefix_avx2_more_digits() {
+        let s = "12345678,01234567890123456789012";
+        assert_eq!(parse_integer_prefix(s), Some((12345678, 8)));
+    }
+
+    #[cfg(all(target_arch = "x86_64", target_feature = "avx2"))]
+    #[test]
+    fn parse_integer_separator_prefix_avx2_eol() {
+        let s = "123456\n901234567890123456789012";
+        assert_eq!(
+            parse_integer_separator_prefix(s, SEP, EOL),
+            Some((123456, 6, Terminator::Eol))
+        );
     }
 
     #[cfg(all(
@@ -954,44 +3240,156 @@ mod tests {
         any(target_feature = "sse4.1", target_feature = "sse4.2")
     ))]
     #[test]
-    fn parse_integer_sse4_more_digits() {
+    fn parse_integer_separator_prefix_sse4_separator() {
         let s = "12345607,8912345";
-        assert_eq!(parse_integer(s), Some(12345607));
+        assert_eq!(
+            parse_integer_separator_prefix(s, SEP, EOL),
+            Some((12345607, 8, Terminator::Separator))
+        );
     }
 
-    #[cfg(all(
-        target_arch = "x86_64",
-        any(target_feature = "sse4.1", target_feature = "sse4.2")
-    ))]
+    // ===== `parse_delimited_into` tests =====
+
     #[test]
-    fn parse_integer_sse4_all_digits_padded() {
-        let s = "0000000012345678";
-        assert_eq!(parse_integer(s), Some(12345678));
+    fn parse_delimited_into_table() {
+        let s = "1,22,333\n4444,55555,666666\n7777777,88888888,999999999\n";
+        let mut out = [0; 16];
+        assert_eq!(parse_delimited_into(s, SEP, EOL, &mut out), Ok((9, s.len())));
+        assert_eq!(
+            out[..9],
+            [1, 22, 333, 4444, 55555, 666666, 7777777, 88888888, 999999999]
+        );
     }
 
-    #[cfg(all(
-        target_arch = "x86_64",
-        any(target_feature = "sse4.1", target_feature = "sse4.2")
-    ))]
     #[test]
-    #[should_panic]
-    fn parse_integer_sse4_unchecked_zero_digit() {
-        let s = ",,00000012345678";
-        unsafe {
-            parse_integer_unchecked(s);
+    fn parse_delimited_into_stops_when_out_is_full() {
+        let s = "1,2,3,4,5,6,7,8,9,10,11,12,13,14,15,16,17,18,19,20";
+        let mut out = [0; 15];
+        assert_eq!(parse_delimited_into(s, SEP, EOL, &mut out), Ok((15, 36)));
+        assert_eq!(out[14], 15);
+    }
+
+    #[test]
+    fn parse_delimited_into_resumes_after_full_output() {
+        let s = "1,22,333\n4444,55555,666666\n7777777,88888888,999999999\n0,4294967295";
+        let mut out = [0; 4];
+        let mut values = Vec::new();
+        let mut consumed = 0;
+        while consumed < s.len() {
+            let (n, len) = parse_delimited_into(&s[consumed..], SEP, EOL, &mut out).unwrap();
+            values.extend_from_slice(&out[..n]);
+            consumed += len;
         }
+        assert_eq!(consumed, s.len());
+        assert_eq!(
+            values,
+            [1, 22, 333, 4444, 55555, 666666, 7777777, 88888888, 999999999, 0, 4294967295]
+        );
     }
 
-    #[cfg(all(
-        target_arch = "x86_64",
-        any(target_feature = "sse4.1", target_feature = "sse4.2")
-    ))]
     #[test]
-    #[should_panic]
-    fn parse_integer_sse4_unchecked_more_than_10_digits() {
-        let s = "123445123456,8456";
-        unsafe {
-            parse_integer_unchecked(s);
+    fn parse_delimited_into_long_field_with_leading_zeros() {
+        let s = "1,000000000000000000000000000000000000042,3";
+        let mut out = [0; 4];
+        assert_eq!(parse_delimited_into(s, SEP, EOL, &mut out), Ok((3, s.len())));
+        assert_eq!(out[..3], [1, 42, 3]);
+    }
+
+    #[test]
+    fn parse_delimited_into_invalid_digit() {
+        let s = "12,34,56,78,90,12,34,5x,78,90,12,34\n";
+        let mut out = [0; 16];
+        let e = parse_delimited_into(s, SEP, EOL, &mut out).unwrap_err();
+        assert_eq!(e, ParseError::new(ParseErrorKind::InvalidDigit, 22));
+    }
+
+    #[test]
+    fn parse_delimited_into_empty_field() {
+        let s = "12,34,56,78,90,12,34,56,78,90,,12\n";
+        let mut out = [0; 16];
+        let e = parse_delimited_into(s, SEP, EOL, &mut out).unwrap_err();
+        assert_eq!(e, ParseError::new(ParseErrorKind::Empty, 30));
+    }
+
+    #[test]
+    fn parse_delimited_into_overflow() {
+        let s = "12,34,56,78,90,12,34,4294967296,1\n";
+        let mut out = [0; 16];
+        let e = parse_delimited_into(s, SEP, EOL, &mut out).unwrap_err();
+        assert_eq!(e, ParseError::new(ParseErrorKind::Overflow, 30));
+    }
+
+    // ===== `IntegerIter` tests =====
+
+    #[test]
+    fn integer_iter_matches_parse_delimited_into() {
+        let s = "1,22,333\n4444,55555,666666\n7777777,88888888,999999999\n0,4294967295";
+        let mut out = [0; 16];
+        let (n, _) = parse_delimited_into(s, SEP, EOL, &mut out).unwrap();
+        let values: Vec<_> = IntegerIter::new(s.as_bytes(), SEP, EOL).collect();
+        assert_eq!(values, out[..n].iter().map(|&v| Ok(v)).collect::<Vec<_>>());
+    }
+
+    // ===== thread-safe dispatch tests =====
+
+    #[test]
+    fn concurrent_first_use_of_the_dispatchers() {
+        use std::sync::{Arc, Barrier};
+        use std::thread;
+
+        const THREADS: usize = 8;
+        let s = "1234,5678,0000000000000000000000\n";
+        for _ in 0..50 {
+            // go back to the state before the first call
+            LAST_BYTE_DIGIT_SEP.set(last_byte_digit_dispatcher);
+            LAST_DIGIT_BYTE.set(last_digit_byte_dispatcher);
+            CHECK_CHARS.set(check_chars_dispatcher);
+            PARSE_INTEGER.set(parse_integer_checked_dispatcher);
+            PARSE_INTEGER_SEP.set(parse_integer_sep_checked_dispatcher);
+            PARSE_INTEGER_SEP_UN.set(parse_integer_sep_dispatcher);
+            PARSE_INTEGER_UN.set(parse_integer_dispatcher);
+            PARSE_U64.set(parse_u64_checked_dispatcher);
+            PARSE_U64_SEP.set(parse_u64_sep_checked_dispatcher);
+            TRY_PARSE_INTEGER.set(try_parse_integer_dispatcher);
+            TRY_PARSE_INTEGER_SEP.set(try_parse_integer_sep_dispatcher);
+            TRY_PARSE_INTEGER_SEP_SET.set(try_parse_integer_sep_set_dispatcher);
+            PARSE_INTEGER_PREFIX.set(parse_integer_prefix_dispatcher);
+            PARSE_INTEGER_SEP_PREFIX.set(parse_integer_sep_prefix_dispatcher);
+            PARSE_DELIMITED_INTO.set(parse_delimited_into_dispatcher);
+
+            let barrier = Arc::new(Barrier::new(THREADS));
+            let handles: Vec<_> = (0..THREADS)
+                .map(|_| {
+                    let barrier = Arc::clone(&barrier);
+                    thread::spawn(move || {
+                        barrier.wait();
+                        assert_eq!(last_byte_without_separator(s, SEP, EOL), 4);
+                        assert_eq!(last_digit_byte(s), 4);
+                        assert!(!check_all_chars_are_valid(s));
+                        assert_eq!(parse_integer(s), Some(1234));
+                        assert_eq!(parse_integer_separator(s, SEP, EOL), Some(1234));
+                        assert_eq!(unsafe { parse_integer_separator_unchecked(s, SEP, EOL) }, 1234);
+                        assert_eq!(unsafe { parse_integer_unchecked(&s[..4]) }, 1234);
+                        assert_eq!(parse_u64(s), Some(1234));
+                        assert_eq!(parse_u64_separator(s, SEP, EOL), Some(1234));
+                        assert_eq!(try_parse_integer(s), Ok(1234));
+                        assert_eq!(try_parse_integer_separator(s, SEP, EOL), Ok(1234));
+                        let separators = SeparatorSet::new(b",\n").unwrap();
+                        assert_eq!(try_parse_integer_separator_set(s, &separators), Ok(1234));
+                        assert_eq!(parse_integer_prefix(s), Some((1234, 4)));
+                        assert_eq!(
+                            parse_integer_separator_prefix(s, SEP, EOL),
+                            Some((1234, 4, Terminator::Separator))
+                        );
+                        let mut out = [0; 2];
+                        assert_eq!(parse_delimited_into(s, SEP, EOL, &mut out), Ok((2, 10)));
+                        assert_eq!(out, [1234, 5678]);
+                    })
+                })
+                .collect();
+            for handle in handles {
+                handle.join().unwrap();
+            }
         }
     }
 }
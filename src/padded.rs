@@ -0,0 +1,324 @@
+//! Inputs followed by enough readable bytes for 16 byte vector loads
+
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+
+use crate::backend::{detected_backend, Backend};
+use crate::fallback;
+#[cfg(feature = "backend-portable")]
+use crate::portable;
+#[cfg(all(target_arch = "x86_64", feature = "backend-sse2"))]
+use crate::sse2;
+#[cfg(all(
+    target_arch = "x86_64",
+    any(
+        feature = "backend-sse41",
+        feature = "backend-sse42",
+        feature = "backend-avx2"
+    )
+))]
+use crate::sse41;
+
+/// Number of readable bytes a `PaddedInput` requires after its data.
+///
+/// It's the size of the single load parsing a padded field, which is 16 bytes
+/// on every backend: an `u32` has at most 10 digits, so the AVX2 backend parses
+/// the padded fields with the SSE4.1 kernels too, and never loads 32 bytes.
+/// Buffers with more slack, e.g. 32 bytes, satisfy it as well.
+pub const PADDING: usize = 16;
+
+/// Number of chars of the load parsing a padded field
+const LOADED_CHARS: u32 = PADDING as u32;
+
+/// Input whose data is followed by at least 16 readable bytes, e.g. a buffer
+/// refilled from a file with some slack at its end.
+///
+/// The slack is checked once, when the input is created, so that every field
+/// is parsed from a single 16 byte load, no matter how short it is and how
+/// close it is to the end of the data. The bytes of the slack are never
+/// parsed, so their content doesn't matter.
+///
+/// ```
+/// use simd_parsing::{PaddedInput, PADDING};
+///
+/// let mut buf = b"42,7\n".to_vec();
+/// let len = buf.len();
+/// buf.resize(len + PADDING, 0);
+///
+/// let input = PaddedInput::new(&buf, len).unwrap();
+/// assert_eq!(input.parse_integer_separator(0, b',', b'\n'), Some(42));
+/// assert_eq!(input.parse_integer(3), Some(7));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct PaddedInput<'a> {
+    /// The data followed by the slack
+    buf: &'a [u8],
+    /// Length of the data
+    len: usize,
+    /// Backend used to parse the fields
+    backend: Backend,
+}
+
+impl<'a> PaddedInput<'a> {
+    /// Creates an input whose data are the first `len` bytes of `buf`.
+    ///
+    /// Fails if `buf` doesn't have at least `PADDING` bytes after the data.
+    pub fn new(buf: &'a [u8], len: usize) -> Result<Self, InsufficientPadding> {
+        Self::with_backend(buf, len, detected_backend())
+    }
+
+    /// Creates an input parsed with `backend`, which must be supported by the
+    /// underlying CPU.
+    pub(crate) fn with_backend(
+        buf: &'a [u8],
+        len: usize,
+        backend: Backend,
+    ) -> Result<Self, InsufficientPadding> {
+        if buf.len() < len.saturating_add(PADDING) {
+            return Err(InsufficientPadding { len, buf_len: buf.len() });
+        }
+        Ok(PaddedInput { buf, len, backend })
+    }
+
+    /// Returns the data, without the slack
+    #[inline]
+    pub fn data(&self) -> &'a [u8] {
+        &self.buf[..self.len]
+    }
+
+    /// Returns the length of the data
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether there is no data
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Parses an `u32` from the data starting at offset `start`, as far as
+    /// there are digits, like `parse_integer`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `start` is past the end of the data.
+    #[inline]
+    pub fn parse_integer(&self, start: usize) -> Option<u32> {
+        let (s, len) = self.field(start);
+        match self.prefix(s, len) {
+            Some((0, _)) => None,
+            Some((digits, value)) if digits < LOADED_CHARS => u32::try_from(value).ok(),
+            // either the number may continue past the loaded chars or there
+            // is no SIMD backend
+            _ => fallback::parse_integer_bytes(&s[..len]),
+        }
+    }
+
+    /// Parses an `u32` from the data starting at offset `start`, up to the
+    /// first occurrence of `separator` or `eol`, like
+    /// `parse_integer_separator`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `start` is past the end of the data.
+    #[inline]
+    pub fn parse_integer_separator(&self, start: usize, separator: u8, eol: u8) -> Option<u32> {
+        let (s, len) = self.field(start);
+        match self.field_bounds(s, len, separator, eol) {
+            // either an empty field or a non digit before the terminator
+            Some((end, invalid, _)) if end == 0 || invalid < end => None,
+            Some((end, _, value)) if end < LOADED_CHARS => u32::try_from(value).ok(),
+            // either the field may continue past the loaded chars or there is
+            // no SIMD backend
+            _ => fallback::parse_integer_separator_bytes(&s[..len], separator, eol),
+        }
+    }
+
+    /// Returns the number of leading digits among the first `len` chars of
+    /// `s` and their value, computed by the SIMD kernels of the backend, or
+    /// None for the fallback backend
+    #[inline]
+    #[cfg_attr(
+        not(any(
+            all(
+                target_arch = "x86_64",
+                any(
+                    feature = "backend-sse41",
+                    feature = "backend-sse42",
+                    feature = "backend-avx2",
+                    feature = "backend-sse2"
+                )
+            ),
+            feature = "backend-portable"
+        )),
+        allow(unused_variables)
+    )]
+    fn prefix(&self, s: &[u8], len: usize) -> Option<(u32, u64)> {
+        match self.backend {
+            #[cfg(all(
+                target_arch = "x86_64",
+                any(
+                    feature = "backend-sse41",
+                    feature = "backend-sse42",
+                    feature = "backend-avx2"
+                )
+            ))]
+            Backend::Avx2 | Backend::Sse42 | Backend::Sse41 => unsafe {
+                Some(sse41::parse_padded_prefix(s, len))
+            },
+            #[cfg(all(target_arch = "x86_64", feature = "backend-sse2"))]
+            Backend::Sse2 => unsafe { Some(sse2::parse_padded_prefix(s, len)) },
+            #[cfg(feature = "backend-portable")]
+            Backend::Portable => Some(portable::parse_padded_prefix(s, len)),
+            _ => None,
+        }
+    }
+
+    /// Returns the offsets of the terminator and of the first non digit among
+    /// the first `len` chars of `s`, and the value of the chars before the
+    /// terminator, computed by the SIMD kernels of the backend, or None for
+    /// the fallback backend
+    #[inline]
+    #[cfg_attr(
+        not(any(
+            all(
+                target_arch = "x86_64",
+                any(
+                    feature = "backend-sse41",
+                    feature = "backend-sse42",
+                    feature = "backend-avx2",
+                    feature = "backend-sse2"
+                )
+            ),
+            feature = "backend-portable"
+        )),
+        allow(unused_variables)
+    )]
+    fn field_bounds(
+        &self,
+        s: &[u8],
+        len: usize,
+        separator: u8,
+        eol: u8,
+    ) -> Option<(u32, u32, u64)> {
+        match self.backend {
+            #[cfg(all(
+                target_arch = "x86_64",
+                any(
+                    feature = "backend-sse41",
+                    feature = "backend-sse42",
+                    feature = "backend-avx2"
+                )
+            ))]
+            Backend::Avx2 | Backend::Sse42 | Backend::Sse41 => unsafe {
+                Some(sse41::parse_padded_field(s, len, separator, eol))
+            },
+            #[cfg(all(target_arch = "x86_64", feature = "backend-sse2"))]
+            Backend::Sse2 => unsafe { Some(sse2::parse_padded_field(s, len, separator, eol)) },
+            #[cfg(feature = "backend-portable")]
+            Backend::Portable => Some(portable::parse_padded_field(s, len, separator, eol)),
+            _ => None,
+        }
+    }
+
+    /// Returns the bytes from offset `start` up to the end of the slack, along
+    /// with the number of them which are data
+    #[inline]
+    fn field(&self, start: usize) -> (&'a [u8], usize) {
+        assert!(
+            start <= self.len,
+            "field start {} is past the end of the data of length {}",
+            start,
+            self.len
+        );
+        (&self.buf[start..], self.len - start)
+    }
+}
+
+/// Error returned when creating a `PaddedInput` from a buffer without
+/// `PADDING` bytes after the data.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct InsufficientPadding {
+    /// Length of the data
+    len: usize,
+    /// Length of the whole buffer
+    buf_len: usize,
+}
+
+impl fmt::Display for InsufficientPadding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "a padded input needs {} bytes after its {} bytes of data, but the buffer has {} bytes",
+            PADDING, self.len, self.buf_len
+        )
+    }
+}
+
+impl Error for InsufficientPadding {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    static SEP: u8 = b',';
+    static EOL: u8 = b'\n';
+
+    /// Returns `data` followed by `PADDING` bytes of digits, which must never
+    /// be parsed
+    fn padded(data: &[u8]) -> Vec<u8> {
+        let mut buf = data.to_vec();
+        buf.resize(data.len() + PADDING, b'9');
+        buf
+    }
+
+    #[test]
+    fn padded_input_insufficient_padding() {
+        let e = PaddedInput::new(&[b'1'; 24], 9).unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "a padded input needs 16 bytes after its 9 bytes of data, but the buffer has 24 bytes"
+        );
+        assert!(PaddedInput::new(&[b'1'; 24], 8).is_ok());
+        assert!(PaddedInput::new(&[b'1'; 40], 8).is_ok());
+        assert!(PaddedInput::new(&[], usize::MAX).is_err());
+    }
+
+    #[test]
+    fn padded_input_every_backend() {
+        let buf = padded(b"42,7\n,4294967295,4294967296,12345678901234567,x");
+        for backend in Backend::available() {
+            let input = PaddedInput::with_backend(&buf, buf.len() - PADDING, backend).unwrap();
+            assert_eq!(input.parse_integer(0), Some(42));
+            assert_eq!(input.parse_integer_separator(3, SEP, EOL), Some(7));
+            assert_eq!(input.parse_integer_separator(5, SEP, EOL), None);
+            assert_eq!(input.parse_integer(6), Some(u32::MAX));
+            assert_eq!(input.parse_integer_separator(17, SEP, EOL), None);
+            assert_eq!(input.parse_integer(28), None);
+            assert_eq!(input.parse_integer_separator(28, SEP, EOL), None);
+            // the last field ends at the end of the data, before the slack
+            assert_eq!(input.parse_integer(input.len() - 1), None);
+            assert_eq!(input.parse_integer(input.len()), None);
+            assert_eq!(input.parse_integer(input.len() - 3), Some(7));
+        }
+    }
+
+    #[test]
+    fn padded_input_field_at_the_end_of_the_data() {
+        let buf = padded(b"1,23");
+        for backend in Backend::available() {
+            let input = PaddedInput::with_backend(&buf, 4, backend).unwrap();
+            assert_eq!(input.parse_integer(2), Some(23));
+            assert_eq!(input.parse_integer_separator(2, SEP, EOL), Some(23));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn padded_input_start_past_the_end() {
+        let buf = padded(b"1");
+        PaddedInput::new(&buf, 1).unwrap().parse_integer(2);
+    }
+}
@@ -0,0 +1,99 @@
+//! Vector loads of inputs shorter than a vector.
+//!
+//! Memory is mapped one 4 KiB page at a time, so a load which doesn't cross a
+//! page boundary can't fault, even when it reads past the end of the input.
+//! Near the end of a page, the input is copied into a zero padded buffer
+//! instead.
+// The portable backend loads the short inputs with the masked loads of
+// `std::simd`, so it only needs `past_end_mask`.
+#![cfg_attr(
+    not(all(
+        target_arch = "x86_64",
+        any(
+            feature = "backend-sse41",
+            feature = "backend-sse42",
+            feature = "backend-avx2",
+            feature = "backend-sse2"
+        )
+    )),
+    allow(dead_code)
+)]
+
+#[cfg(target_arch = "x86_64")]
+use std::arch::asm;
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+
+/// Size of the smallest memory page
+pub(crate) const PAGE_SIZE: usize = 4096;
+
+/// Returns whether the `n` bytes starting at `ptr` are all in the same page
+#[inline]
+pub(crate) fn fits_in_page(ptr: *const u8, n: usize) -> bool {
+    (ptr as usize & (PAGE_SIZE - 1)) <= PAGE_SIZE - n
+}
+
+/// Loads 16 bytes whose first `s.len()` are the chars of `s`, while the others
+/// are unspecified.
+///
+/// # Safety
+///
+/// `s` must not be empty and must have less than 16 chars.
+#[inline]
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+pub(crate) unsafe fn load_short_128(s: &[u8]) -> __m128i {
+    let ptr = s.as_ptr();
+    if fits_in_page(ptr, 16) {
+        let value: __m128i;
+        // the load goes through inline assembly since, for the compiler, the
+        // bytes past the end of the slice don't exist: the CPU only requires
+        // them to be mapped, which the page check guarantees
+        asm!(
+            "movdqu {value}, [{ptr}]",
+            value = out(xmm_reg) value,
+            ptr = in(reg) ptr,
+            options(pure, readonly, nostack, preserves_flags)
+        );
+        return value;
+    }
+    let mut padded = [0u8; 16];
+    padded[..s.len()].copy_from_slice(s);
+    _mm_loadu_si128(padded.as_ptr() as *const _)
+}
+
+/// Returns the mask of the positions past the end of a string of `len` chars,
+/// with the first char in the lowest bit, where `len` is less than 32
+#[inline]
+pub(crate) fn past_end_mask(len: usize) -> u32 {
+    u32::MAX << len
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fits_in_page_bounds() {
+        assert!(fits_in_page(0x1000 as *const u8, 16));
+        assert!(fits_in_page(0x1ff0 as *const u8, 16));
+        assert!(!fits_in_page(0x1ff1 as *const u8, 16));
+        assert!(fits_in_page(0x1fff as *const u8, 1));
+    }
+
+    #[test]
+    #[cfg(target_arch = "x86_64")]
+    fn load_short_128_near_page_end() {
+        // a buffer spanning two pages, with the input at the end of the first
+        let buf = vec![b'7'; 3 * PAGE_SIZE];
+        let page_end = PAGE_SIZE - (buf.as_ptr() as usize & (PAGE_SIZE - 1));
+        let s = &buf[page_end + PAGE_SIZE - 3..page_end + PAGE_SIZE];
+        let mut bytes = [0u8; 16];
+        unsafe {
+            let value = load_short_128(s);
+            _mm_storeu_si128(bytes.as_mut_ptr() as *mut _, value);
+        }
+        // the chars past the page end are not loaded
+        assert_eq!(&bytes[..4], b"777\0");
+    }
+}
@@ -0,0 +1,858 @@
+//! Handles to the library API implemented by a specific backend.
+//!
+//! A `Parser<Dynamic>`, the default, picks the backend at runtime and calls its
+//! implementations through function pointers. The parsers of the zero sized
+//! backends `Avx2`, `Sse42`, `Sse41`, `Sse2`, `Portable` and `Fallback` instead
+//! call them directly, so that generic code over `ParserBackend` is
+//! monomorphized for each backend and the kernels can be inlined in its loops.
+//! The implementations of each backend are listed once in `backend.rs`, both
+//! as its table of function pointers and as its `Kernels` methods.
+
+use std::fmt;
+
+use crate::backend::{detected_backend, Backend, Functions, UnsupportedBackend};
+use crate::error::ParseError;
+use crate::fallback;
+use crate::iter::IntegerIter;
+use crate::separators::SeparatorSet;
+use crate::Terminator;
+
+/// Backend of a `Parser`.
+///
+/// It's implemented by `Dynamic`, for backends chosen at runtime, and by the
+/// zero sized `Avx2`, `Sse42`, `Sse41`, `Sse2`, `Portable` and `Fallback`,
+/// known at compile time.
+pub trait ParserBackend: private::Sealed {
+    /// Returns the backend
+    fn backend(&self) -> Backend;
+}
+
+/// Implementation details of `ParserBackend`, hidden from the users
+pub(crate) mod private {
+    use super::*;
+
+    /// Prevents the implementation of `ParserBackend` outside the library
+    pub trait Sealed: Kernels + Copy + Sized {
+        /// Calls `f` with `parser`, in a function compiled with the target
+        /// features of the backend
+        fn run<R, F: FnOnce(Parser<Self>) -> R>(parser: Parser<Self>, f: F) -> R;
+    }
+
+    /// Implementations of the library API of a backend.
+    ///
+    /// The zero sized backends compiled in override every method with a direct
+    /// call to their implementation, see `backend_functions!`, while the ones
+    /// left out keep the scalar implementations, although their parsers can't
+    /// be created. The methods are unsafe because the backend must be
+    /// supported by the CPU.
+    pub trait Kernels {
+        /// See `last_byte_without_separator`
+        #[inline(always)]
+        unsafe fn last_byte_without_separator(&self, s: &[u8], separator: u8, eol: u8) -> u32 {
+            fallback::last_byte_without_separator_bytes(s, separator, eol)
+        }
+
+        /// See `last_digit_byte`
+        #[inline(always)]
+        unsafe fn last_digit_byte(&self, s: &[u8]) -> u32 {
+            fallback::last_digit_byte_bytes(s)
+        }
+
+        /// See `first_non_digit`
+        #[inline(always)]
+        unsafe fn first_non_digit(&self, s: &[u8]) -> Option<usize> {
+            fallback::first_non_digit(s)
+        }
+
+        /// See `find_separator`
+        #[inline(always)]
+        unsafe fn find_separator(&self, s: &[u8], separator: u8, eol: u8) -> Option<usize> {
+            fallback::find_separator(s, separator, eol)
+        }
+
+        /// See `check_all_chars_are_valid`
+        #[inline(always)]
+        unsafe fn check_all_chars_are_valid(&self, s: &[u8]) -> bool {
+            fallback::check_all_chars_are_valid_bytes(s)
+        }
+
+        /// See `parse_integer`
+        #[inline(always)]
+        unsafe fn parse_integer(&self, s: &[u8]) -> Option<u32> {
+            fallback::parse_integer_bytes(s)
+        }
+
+        /// See `parse_integer_separator`
+        #[inline(always)]
+        unsafe fn parse_integer_separator(&self, s: &[u8], separator: u8, eol: u8) -> Option<u32> {
+            fallback::parse_integer_separator_bytes(s, separator, eol)
+        }
+
+        /// See `parse_integer_unchecked`
+        #[inline(always)]
+        unsafe fn parse_integer_unchecked(&self, s: &[u8]) -> u32 {
+            fallback::parse_integer_unchecked_bytes(s)
+        }
+
+        /// See `parse_integer_separator_unchecked`
+        #[inline(always)]
+        unsafe fn parse_integer_separator_unchecked(
+            &self,
+            s: &[u8],
+            separator: u8,
+            eol: u8,
+        ) -> u32 {
+            fallback::parse_integer_separator_unchecked_bytes(s, separator, eol)
+        }
+
+        /// See `parse_u64`
+        #[inline(always)]
+        unsafe fn parse_u64(&self, s: &[u8]) -> Option<u64> {
+            fallback::parse_u64_bytes(s)
+        }
+
+        /// See `parse_u64_separator`
+        #[inline(always)]
+        unsafe fn parse_u64_separator(&self, s: &[u8], separator: u8, eol: u8) -> Option<u64> {
+            fallback::parse_u64_separator_bytes(s, separator, eol)
+        }
+
+        /// See `try_parse_integer`
+        #[inline(always)]
+        unsafe fn try_parse_integer(&self, s: &[u8]) -> Result<u32, ParseError> {
+            fallback::try_parse_integer(s)
+        }
+
+        /// See `try_parse_integer_separator`
+        #[inline(always)]
+        unsafe fn try_parse_integer_separator(
+            &self,
+            s: &[u8],
+            separator: u8,
+            eol: u8,
+        ) -> Result<u32, ParseError> {
+            fallback::try_parse_integer_separator(s, separator, eol)
+        }
+
+        /// See `try_parse_integer_separator_set`
+        #[inline(always)]
+        unsafe fn try_parse_integer_separator_set(
+            &self,
+            s: &[u8],
+            separators: &SeparatorSet,
+        ) -> Result<u32, ParseError> {
+            fallback::try_parse_integer_separator_set(s, separators)
+        }
+
+        /// See `parse_integer_prefix`
+        #[inline(always)]
+        unsafe fn parse_integer_prefix(&self, s: &[u8]) -> Option<(u32, usize)> {
+            fallback::parse_integer_prefix(s)
+        }
+
+        /// See `parse_integer_separator_prefix`
+        #[inline(always)]
+        unsafe fn parse_integer_separator_prefix(
+            &self,
+            s: &[u8],
+            separator: u8,
+            eol: u8,
+        ) -> Option<(u32, usize, Terminator)> {
+            fallback::parse_integer_separator_prefix(s, separator, eol)
+        }
+
+        /// See `parse_delimited_into`
+        #[inline(always)]
+        unsafe fn parse_delimited_into(
+            &self,
+            buf: &[u8],
+            separator: u8,
+            eol: u8,
+            out: &mut [u32],
+        ) -> Result<(usize, usize), ParseError> {
+            fallback::parse_delimited_into(buf, separator, eol, out)
+        }
+    }
+}
+
+/// Backend of a `Parser` chosen at runtime, whose implementations are called
+/// through function pointers
+#[derive(Clone, Copy)]
+pub struct Dynamic {
+    /// The chosen backend
+    backend: Backend,
+    /// Implementations of `backend`
+    functions: &'static Functions,
+}
+
+impl fmt::Debug for Dynamic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Dynamic").field(&self.backend).finish()
+    }
+}
+
+impl private::Sealed for Dynamic {
+    #[inline]
+    fn run<R, F: FnOnce(Parser<Self>) -> R>(parser: Parser<Self>, f: F) -> R {
+        f(parser)
+    }
+}
+
+impl private::Kernels for Dynamic {
+    #[inline]
+    unsafe fn last_byte_without_separator(&self, s: &[u8], separator: u8, eol: u8) -> u32 {
+        (self.functions.last_byte_without_separator)(s, separator, eol)
+    }
+
+    #[inline]
+    unsafe fn last_digit_byte(&self, s: &[u8]) -> u32 {
+        (self.functions.last_digit_byte)(s)
+    }
+
+    #[inline]
+    unsafe fn first_non_digit(&self, s: &[u8]) -> Option<usize> {
+        (self.functions.first_non_digit)(s)
+    }
+
+    #[inline]
+    unsafe fn find_separator(&self, s: &[u8], separator: u8, eol: u8) -> Option<usize> {
+        (self.functions.find_separator)(s, separator, eol)
+    }
+
+    #[inline]
+    unsafe fn check_all_chars_are_valid(&self, s: &[u8]) -> bool {
+        (self.functions.check_all_chars_are_valid)(s)
+    }
+
+    #[inline]
+    unsafe fn parse_integer(&self, s: &[u8]) -> Option<u32> {
+        (self.functions.parse_integer)(s)
+    }
+
+    #[inline]
+    unsafe fn parse_integer_separator(&self, s: &[u8], separator: u8, eol: u8) -> Option<u32> {
+        (self.functions.parse_integer_separator)(s, separator, eol)
+    }
+
+    #[inline]
+    unsafe fn parse_integer_unchecked(&self, s: &[u8]) -> u32 {
+        (self.functions.parse_integer_unchecked)(s)
+    }
+
+    #[inline]
+    unsafe fn parse_integer_separator_unchecked(&self, s: &[u8], separator: u8, eol: u8) -> u32 {
+        (self.functions.parse_integer_separator_unchecked)(s, separator, eol)
+    }
+
+    #[inline]
+    unsafe fn parse_u64(&self, s: &[u8]) -> Option<u64> {
+        (self.functions.parse_u64)(s)
+    }
+
+    #[inline]
+    unsafe fn parse_u64_separator(&self, s: &[u8], separator: u8, eol: u8) -> Option<u64> {
+        (self.functions.parse_u64_separator)(s, separator, eol)
+    }
+
+    #[inline]
+    unsafe fn try_parse_integer(&self, s: &[u8]) -> Result<u32, ParseError> {
+        (self.functions.try_parse_integer)(s)
+    }
+
+    #[inline]
+    unsafe fn try_parse_integer_separator(
+        &self,
+        s: &[u8],
+        separator: u8,
+        eol: u8,
+    ) -> Result<u32, ParseError> {
+        (self.functions.try_parse_integer_separator)(s, separator, eol)
+    }
+
+    #[inline]
+    unsafe fn try_parse_integer_separator_set(
+        &self,
+        s: &[u8],
+        separators: &SeparatorSet,
+    ) -> Result<u32, ParseError> {
+        (self.functions.try_parse_integer_separator_set)(s, separators)
+    }
+
+    #[inline]
+    unsafe fn parse_integer_prefix(&self, s: &[u8]) -> Option<(u32, usize)> {
+        (self.functions.parse_integer_prefix)(s)
+    }
+
+    #[inline]
+    unsafe fn parse_integer_separator_prefix(
+        &self,
+        s: &[u8],
+        separator: u8,
+        eol: u8,
+    ) -> Option<(u32, usize, Terminator)> {
+        (self.functions.parse_integer_separator_prefix)(s, separator, eol)
+    }
+
+    #[inline]
+    unsafe fn parse_delimited_into(
+        &self,
+        buf: &[u8],
+        separator: u8,
+        eol: u8,
+        out: &mut [u32],
+    ) -> Result<(usize, usize), ParseError> {
+        (self.functions.parse_delimited_into)(buf, separator, eol, out)
+    }
+}
+
+impl ParserBackend for Dynamic {
+    #[inline]
+    fn backend(&self) -> Backend {
+        self.backend
+    }
+}
+
+/// Declares the zero sized backends known at compile time, with the function
+/// running the closures of `Parser::run` for each of them
+macro_rules! static_backends {
+    ($($(#[$attr:meta])* $name:ident => $run:ident;)*) => {
+        $(
+            $(#[$attr])*
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+            pub struct $name(());
+
+            impl private::Sealed for $name {
+                #[inline]
+                fn run<R, F: FnOnce(Parser<Self>) -> R>(parser: Parser<Self>, f: F) -> R {
+                    // SAFETY: the parser exists only if the backend is
+                    // available, see `Parser::new`
+                    unsafe { $run(parser, f) }
+                }
+            }
+
+            impl ParserBackend for $name {
+                #[inline]
+                fn backend(&self) -> Backend {
+                    Backend::$name
+                }
+            }
+
+            impl Parser<$name> {
+                /// Creates a parser using the backend, if compiled in and
+                /// supported by the underlying CPU.
+                #[inline]
+                pub fn new() -> Option<Self> {
+                    if Backend::$name.is_available() {
+                        Some(Parser { backend: $name(()) })
+                    } else {
+                        None
+                    }
+                }
+            }
+        )*
+    };
+}
+
+static_backends! {
+    /// AVX2 backend, known at compile time
+    Avx2 => run_avx2;
+    /// SSE4.2 backend, known at compile time
+    Sse42 => run_sse42;
+    /// SSE4.1 backend, known at compile time
+    Sse41 => run_sse41;
+    /// SSE2 backend, known at compile time
+    Sse2 => run_sse2;
+    /// Portable SIMD backend, known at compile time
+    Portable => run_portable;
+    /// Scalar backend, known at compile time and available on every CPU
+    Fallback => run_fallback;
+}
+
+/// Calls `f` with `parser`, with the AVX2 instructions enabled
+///
+/// # Safety
+///
+/// The CPU must support AVX2.
+#[cfg(all(target_arch = "x86_64", feature = "backend-avx2"))]
+#[target_feature(enable = "avx2")]
+unsafe fn run_avx2<B, R, F: FnOnce(Parser<B>) -> R>(parser: Parser<B>, f: F) -> R {
+    f(parser)
+}
+
+/// Calls `f` with `parser`, with the SSE4.1 and SSE4.2 instructions enabled
+///
+/// # Safety
+///
+/// The CPU must support SSE4.1 and SSE4.2.
+#[cfg(all(target_arch = "x86_64", feature = "backend-sse42"))]
+#[target_feature(enable = "sse4.1,sse4.2")]
+unsafe fn run_sse42<B, R, F: FnOnce(Parser<B>) -> R>(parser: Parser<B>, f: F) -> R {
+    f(parser)
+}
+
+/// Calls `f` with `parser`, with the SSE4.1 instructions enabled
+///
+/// # Safety
+///
+/// The CPU must support SSE4.1.
+#[cfg(all(target_arch = "x86_64", feature = "backend-sse41"))]
+#[target_feature(enable = "sse4.1")]
+unsafe fn run_sse41<B, R, F: FnOnce(Parser<B>) -> R>(parser: Parser<B>, f: F) -> R {
+    f(parser)
+}
+
+/// Calls `f` with `parser`, with the SSE2 instructions enabled
+///
+/// # Safety
+///
+/// The CPU must support SSE2, as every x86_64 CPU does.
+#[cfg(all(target_arch = "x86_64", feature = "backend-sse2"))]
+#[target_feature(enable = "sse2")]
+unsafe fn run_sse2<B, R, F: FnOnce(Parser<B>) -> R>(parser: Parser<B>, f: F) -> R {
+    f(parser)
+}
+
+/// Calls `f` with `parser`
+///
+/// # Safety
+///
+/// Always safe, it's unsafe only to share the signature of the others.
+unsafe fn run_fallback<B, R, F: FnOnce(Parser<B>) -> R>(parser: Parser<B>, f: F) -> R {
+    f(parser)
+}
+
+// the parsers of the backends not compiled in can't be created
+#[cfg(not(all(target_arch = "x86_64", feature = "backend-avx2")))]
+use self::run_fallback as run_avx2;
+#[cfg(not(all(target_arch = "x86_64", feature = "backend-sse42")))]
+use self::run_fallback as run_sse42;
+#[cfg(not(all(target_arch = "x86_64", feature = "backend-sse41")))]
+use self::run_fallback as run_sse41;
+#[cfg(not(all(target_arch = "x86_64", feature = "backend-sse2")))]
+use self::run_fallback as run_sse2;
+// the portable backend doesn't depend on any target feature
+use self::run_fallback as run_portable;
+
+// the backends not compiled in keep the scalar implementations
+#[cfg(not(all(target_arch = "x86_64", feature = "backend-avx2")))]
+impl private::Kernels for Avx2 {}
+#[cfg(not(all(target_arch = "x86_64", feature = "backend-sse42")))]
+impl private::Kernels for Sse42 {}
+#[cfg(not(all(target_arch = "x86_64", feature = "backend-sse41")))]
+impl private::Kernels for Sse41 {}
+#[cfg(not(all(target_arch = "x86_64", feature = "backend-sse2")))]
+impl private::Kernels for Sse2 {}
+#[cfg(not(feature = "backend-portable"))]
+impl private::Kernels for Portable {}
+impl private::Kernels for Fallback {}
+
+/// Handle to the library API implemented by a specific backend.
+///
+/// While the functions of the library always use the detected backend, a
+/// `Parser` sticks to the one it was created with, e.g. to compare the
+/// performance of the backends on the same machine. Its methods take the input
+/// as a byte slice, like the `_bytes` functions of the library.
+///
+/// ```
+/// use simd_parsing::{Backend, Parser};
+///
+/// let parser = Parser::with_backend(Backend::Fallback).unwrap();
+/// assert_eq!(parser.parse_integer(b"1234,5"), Some(1234));
+/// ```
+///
+/// The parsers of a backend known at compile time, e.g. `Parser<Avx2>`, are
+/// zero sized and call its implementations directly. Code generic over the
+/// backend can pick the best one once, with `with_best_parser!`, and then run
+/// without any dispatch:
+///
+/// ```
+/// use simd_parsing::{with_best_parser, Parser, ParserBackend};
+///
+/// fn sum<B: ParserBackend>(parser: Parser<B>, fields: &[&[u8]]) -> u32 {
+///     fields.iter().filter_map(|s| parser.parse_integer(s)).sum()
+/// }
+///
+/// let fields: [&[u8]; 3] = [b"12", b"25", b"5"];
+/// assert_eq!(with_best_parser!(|parser| sum(parser, &fields)), 42);
+/// ```
+#[derive(Clone, Copy)]
+pub struct Parser<B = Dynamic> {
+    /// Backend of the parser
+    backend: B,
+}
+
+impl Parser {
+    /// Creates a parser using `backend`, if compiled in and supported by the
+    /// underlying CPU.
+    pub fn with_backend(backend: Backend) -> Result<Self, UnsupportedBackend> {
+        if !backend.is_available() {
+            return Err(UnsupportedBackend::new(backend));
+        }
+        Ok(Parser { backend: Dynamic { backend, functions: backend.functions() } })
+    }
+
+    /// Creates a parser using the backend of `detected_backend()`.
+    pub fn detected() -> Self {
+        let backend = detected_backend();
+        Parser { backend: Dynamic { backend, functions: backend.functions() } }
+    }
+}
+
+impl<B: ParserBackend> Parser<B> {
+    /// Returns the backend of the parser
+    #[inline]
+    pub fn backend(&self) -> Backend {
+        self.backend.backend()
+    }
+
+    /// Calls `f` with the parser, in a function compiled with the target
+    /// features of its backend, so that the implementations called by `f` can
+    /// be inlined in it.
+    #[inline]
+    pub fn run<R, F: FnOnce(Self) -> R>(self, f: F) -> R {
+        B::run(self, f)
+    }
+
+    /// Same as `last_byte_without_separator`, using the backend of the parser.
+    #[inline]
+    pub fn last_byte_without_separator(&self, s: &[u8], separator: u8, eol: u8) -> u32 {
+        // SAFETY: the backend is supported by the CPU, see the constructors
+        unsafe { self.backend.last_byte_without_separator(s, separator, eol) }
+    }
+
+    /// Same as `last_digit_byte`, using the backend of the parser.
+    #[inline]
+    pub fn last_digit_byte(&self, s: &[u8]) -> u32 {
+        unsafe { self.backend.last_digit_byte(s) }
+    }
+
+    /// Same as `first_non_digit`, using the backend of the parser.
+    #[inline]
+    pub fn first_non_digit(&self, s: &[u8]) -> Option<usize> {
+        unsafe { self.backend.first_non_digit(s) }
+    }
+
+    /// Same as `find_separator`, using the backend of the parser.
+    #[inline]
+    pub fn find_separator(&self, s: &[u8], separator: u8, eol: u8) -> Option<usize> {
+        unsafe { self.backend.find_separator(s, separator, eol) }
+    }
+
+    /// Same as `check_all_chars_are_valid`, using the backend of the parser.
+    #[inline]
+    pub fn check_all_chars_are_valid(&self, s: &[u8]) -> bool {
+        unsafe { self.backend.check_all_chars_are_valid(s) }
+    }
+
+    /// Same as `parse_integer`, using the backend of the parser.
+    #[inline]
+    pub fn parse_integer(&self, s: &[u8]) -> Option<u32> {
+        unsafe { self.backend.parse_integer(s) }
+    }
+
+    /// Same as `parse_integer_separator`, using the backend of the parser.
+    #[inline]
+    pub fn parse_integer_separator(&self, s: &[u8], separator: u8, eol: u8) -> Option<u32> {
+        unsafe { self.backend.parse_integer_separator(s, separator, eol) }
+    }
+
+    /// Same as `parse_integer_unchecked`, using the backend of the parser.
+    ///
+    /// # Safety
+    ///
+    /// See `parse_integer_unchecked`.
+    #[inline]
+    pub unsafe fn parse_integer_unchecked(&self, s: &[u8]) -> u32 {
+        self.backend.parse_integer_unchecked(s)
+    }
+
+    /// Same as `parse_integer_separator_unchecked`, using the backend of the
+    /// parser.
+    ///
+    /// # Safety
+    ///
+    /// See `parse_integer_separator_unchecked`.
+    #[inline]
+    pub unsafe fn parse_integer_separator_unchecked(&self, s: &[u8], separator: u8, eol: u8) -> u32 {
+        self.backend.parse_integer_separator_unchecked(s, separator, eol)
+    }
+
+    /// Same as `parse_u64`, using the backend of the parser.
+    #[inline]
+    pub fn parse_u64(&self, s: &[u8]) -> Option<u64> {
+        unsafe { self.backend.parse_u64(s) }
+    }
+
+    /// Same as `parse_u64_separator`, using the backend of the parser.
+    #[inline]
+    pub fn parse_u64_separator(&self, s: &[u8], separator: u8, eol: u8) -> Option<u64> {
+        unsafe { self.backend.parse_u64_separator(s, separator, eol) }
+    }
+
+    /// Same as `parse_i32`, using the backend of the parser.
+    #[inline]
+    pub fn parse_i32(&self, s: &[u8]) -> Option<i32> {
+        let (negative, digits) = crate::split_sign(s);
+        crate::apply_sign_i32(negative, self.parse_integer(digits)?)
+    }
+
+    /// Same as `parse_i32_separator`, using the backend of the parser.
+    #[inline]
+    pub fn parse_i32_separator(&self, s: &[u8], separator: u8, eol: u8) -> Option<i32> {
+        let (negative, digits) = crate::split_sign(s);
+        crate::apply_sign_i32(negative, self.parse_integer_separator(digits, separator, eol)?)
+    }
+
+    /// Same as `parse_i64`, using the backend of the parser.
+    #[inline]
+    pub fn parse_i64(&self, s: &[u8]) -> Option<i64> {
+        let (negative, digits) = crate::split_sign(s);
+        crate::apply_sign_i64(negative, self.parse_u64(digits)?)
+    }
+
+    /// Same as `parse_i64_separator`, using the backend of the parser.
+    #[inline]
+    pub fn parse_i64_separator(&self, s: &[u8], separator: u8, eol: u8) -> Option<i64> {
+        let (negative, digits) = crate::split_sign(s);
+        crate::apply_sign_i64(negative, self.parse_u64_separator(digits, separator, eol)?)
+    }
+
+    /// Same as `try_parse_integer`, using the backend of the parser.
+    #[inline]
+    pub fn try_parse_integer(&self, s: &[u8]) -> Result<u32, ParseError> {
+        unsafe { self.backend.try_parse_integer(s) }
+    }
+
+    /// Same as `try_parse_integer_separator`, using the backend of the parser.
+    #[inline]
+    pub fn try_parse_integer_separator(
+        &self,
+        s: &[u8],
+        separator: u8,
+        eol: u8,
+    ) -> Result<u32, ParseError> {
+        unsafe { self.backend.try_parse_integer_separator(s, separator, eol) }
+    }
+
+    /// Same as `try_parse_integer_separator_set`, using the backend of the
+    /// parser.
+    #[inline]
+    pub fn try_parse_integer_separator_set(
+        &self,
+        s: &[u8],
+        separators: &SeparatorSet,
+    ) -> Result<u32, ParseError> {
+        unsafe { self.backend.try_parse_integer_separator_set(s, separators) }
+    }
+
+    /// Same as `parse_integer_prefix`, using the backend of the parser.
+    #[inline]
+    pub fn parse_integer_prefix(&self, s: &[u8]) -> Option<(u32, usize)> {
+        unsafe { self.backend.parse_integer_prefix(s) }
+    }
+
+    /// Same as `parse_integer_separator_prefix`, using the backend of the
+    /// parser.
+    #[inline]
+    pub fn parse_integer_separator_prefix(
+        &self,
+        s: &[u8],
+        separator: u8,
+        eol: u8,
+    ) -> Option<(u32, usize, Terminator)> {
+        unsafe { self.backend.parse_integer_separator_prefix(s, separator, eol) }
+    }
+
+    /// Same as `parse_delimited_into`, using the backend of the parser.
+    #[inline]
+    pub fn parse_delimited_into(
+        &self,
+        buf: &[u8],
+        separator: u8,
+        eol: u8,
+        out: &mut [u32],
+    ) -> Result<(usize, usize), ParseError> {
+        unsafe { self.backend.parse_delimited_into(buf, separator, eol, out) }
+    }
+
+    /// Same as `IntegerIter::new`, using the backend of the parser.
+    #[inline]
+    pub fn integer_iter<'a>(&self, buf: &'a [u8], separator: u8, eol: u8) -> IntegerIter<'a> {
+        IntegerIter::with_backend(buf, separator, eol, self.backend())
+    }
+}
+
+impl<B: ParserBackend> fmt::Debug for Parser<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Parser").field("backend", &self.backend()).finish()
+    }
+}
+
+/// Evaluates a closure taking a `Parser` of the backend of
+/// `detected_backend()`, known at compile time.
+///
+/// The closure is monomorphized for every backend and runs in a function
+/// compiled with the target features of the detected one, see `Parser::run`:
+/// the backend is picked once, and the calls of the parser inside the closure
+/// don't go through any function pointer.
+///
+/// It's a macro rather than a function because each static backend is its own
+/// type: a function can't return the parser of whichever backend is the best,
+/// so the macro expands the closure once for every backend instead.
+///
+/// ```
+/// use simd_parsing::with_best_parser;
+///
+/// let mut values = Vec::new();
+/// with_best_parser!(|parser| {
+///     for line in ["12,3\n", "45,6\n"].iter() {
+///         values.extend(parser.parse_integer_separator(line.as_bytes(), b',', b'\n'));
+///     }
+/// });
+/// assert_eq!(values, [12, 45]);
+/// ```
+#[macro_export]
+macro_rules! with_best_parser {
+    (|$parser:ident| $body:expr) => {
+        match $crate::detected_backend() {
+            $crate::Backend::Avx2 => $crate::Parser::<$crate::Avx2>::new()
+                .expect("the detected backend is available")
+                .run(|$parser| $body),
+            $crate::Backend::Sse42 => $crate::Parser::<$crate::Sse42>::new()
+                .expect("the detected backend is available")
+                .run(|$parser| $body),
+            $crate::Backend::Sse41 => $crate::Parser::<$crate::Sse41>::new()
+                .expect("the detected backend is available")
+                .run(|$parser| $body),
+            $crate::Backend::Sse2 => $crate::Parser::<$crate::Sse2>::new()
+                .expect("the detected backend is available")
+                .run(|$parser| $body),
+            $crate::Backend::Portable => $crate::Parser::<$crate::Portable>::new()
+                .expect("the detected backend is available")
+                .run(|$parser| $body),
+            _ => $crate::Parser::<$crate::Fallback>::new()
+                .expect("the fallback backend is always available")
+                .run(|$parser| $body),
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ParseErrorKind;
+    use std::mem;
+    static SEP: u8 = b',';
+    static EOL: u8 = b'\n';
+
+    /// Checks every method of `parser` against the expected results
+    fn check_parser<B: ParserBackend>(parser: Parser<B>) {
+        let s = b"-12345678,1234567890123456789012345678901234\n";
+        let long = b"12345678901234567890,1";
+        assert_eq!(parser.last_byte_without_separator(&s[1..], SEP, EOL), 8);
+        assert_eq!(parser.last_digit_byte(&s[1..]), 8);
+        assert_eq!(parser.first_non_digit(&s[10..]), Some(34));
+        assert_eq!(parser.find_separator(&s[10..], SEP, EOL), Some(34));
+        assert_eq!(parser.find_separator(&s[1..], SEP, EOL), Some(8));
+        assert!(!parser.check_all_chars_are_valid(s));
+        assert_eq!(parser.parse_integer(&s[1..]), Some(12345678));
+        assert_eq!(parser.parse_integer_separator(&s[1..], SEP, EOL), Some(12345678));
+        assert_eq!(unsafe { parser.parse_integer_unchecked(b"12345678") }, 12345678);
+        assert_eq!(
+            unsafe { parser.parse_integer_separator_unchecked(&s[1..], SEP, EOL) },
+            12345678
+        );
+        assert_eq!(parser.parse_u64(long), Some(12345678901234567890));
+        assert_eq!(parser.parse_u64_separator(long, SEP, EOL), Some(12345678901234567890));
+        assert_eq!(parser.parse_i32(s), Some(-12345678));
+        assert_eq!(parser.parse_i32_separator(s, SEP, EOL), Some(-12345678));
+        assert_eq!(parser.parse_i64(s), Some(-12345678));
+        assert_eq!(parser.parse_i64_separator(s, SEP, EOL), Some(-12345678));
+        assert_eq!(
+            parser.try_parse_integer(s).unwrap_err().kind(),
+            ParseErrorKind::InvalidDigit
+        );
+        assert_eq!(parser.try_parse_integer_separator(&s[1..], SEP, EOL), Ok(12345678));
+        let separators = SeparatorSet::new(b"; ,").unwrap();
+        assert_eq!(parser.try_parse_integer_separator_set(&s[1..], &separators), Ok(12345678));
+        assert_eq!(parser.parse_integer_prefix(&s[1..]), Some((12345678, 8)));
+        assert_eq!(
+            parser.parse_integer_separator_prefix(&s[1..], SEP, EOL),
+            Some((12345678, 8, Terminator::Separator))
+        );
+        let mut out = [0; 1];
+        assert_eq!(parser.parse_delimited_into(&s[1..], SEP, EOL, &mut out), Ok((1, 9)));
+        assert_eq!(out, [12345678]);
+        let mut iter = parser.integer_iter(&s[1..], SEP, EOL);
+        assert_eq!(iter.next(), Some(Ok(12345678)));
+    }
+
+    #[test]
+    fn with_backend_unsupported() {
+        for &backend in Backend::ALL.iter() {
+            match Parser::with_backend(backend) {
+                Ok(parser) => assert_eq!(parser.backend(), backend),
+                Err(e) => {
+                    assert!(!backend.is_available());
+                    assert_eq!(e.backend(), backend);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn every_available_backend_gives_the_same_results() {
+        for backend in Backend::available() {
+            check_parser(Parser::with_backend(backend).unwrap());
+        }
+    }
+
+    #[test]
+    fn every_available_static_backend_gives_the_same_results() {
+        if let Some(parser) = Parser::<Avx2>::new() {
+            parser.run(check_parser);
+        }
+        if let Some(parser) = Parser::<Sse42>::new() {
+            parser.run(check_parser);
+        }
+        if let Some(parser) = Parser::<Sse41>::new() {
+            parser.run(check_parser);
+        }
+        if let Some(parser) = Parser::<Sse2>::new() {
+            parser.run(check_parser);
+        }
+        if let Some(parser) = Parser::<Portable>::new() {
+            parser.run(check_parser);
+        }
+        Parser::<Fallback>::new().unwrap().run(check_parser);
+    }
+
+    #[test]
+    fn static_parsers_are_zero_sized() {
+        assert_eq!(mem::size_of::<Parser<Avx2>>(), 0);
+        assert_eq!(mem::size_of::<Parser<Fallback>>(), 0);
+    }
+
+    #[test]
+    fn static_parser_new_checks_availability() {
+        assert_eq!(Parser::<Avx2>::new().is_some(), Backend::Avx2.is_available());
+        assert_eq!(Parser::<Sse42>::new().is_some(), Backend::Sse42.is_available());
+        assert_eq!(Parser::<Portable>::new().is_some(), cfg!(feature = "backend-portable"));
+        if let Some(parser) = Parser::<Sse41>::new() {
+            assert_eq!(parser.backend(), Backend::Sse41);
+        }
+    }
+
+    #[test]
+    fn with_best_parser_uses_the_detected_backend() {
+        assert_eq!(with_best_parser!(|parser| parser.backend()), detected_backend());
+    }
+
+    #[test]
+    fn debug_parser() {
+        let parser = Parser::<Fallback>::new().unwrap();
+        assert_eq!(format!("{:?}", parser), "Parser { backend: Fallback }");
+        let parser = Parser::with_backend(Backend::Fallback).unwrap();
+        assert_eq!(format!("{:?}", parser), "Parser { backend: Fallback }");
+    }
+}
@@ -0,0 +1,377 @@
+//! Portable SIMD implementations for parsing a u32 from a string.
+//!
+//! They are written with the vector types of `std::simd`, so the compiler
+//! lowers them to the vector instructions of any target, e.g. NEON on aarch64,
+//! or to scalar code where there aren't any. On x86_64 the backends written
+//! with the intrinsics are faster, so this one is selected only on the other
+//! architectures, or when forced.
+//!
+//! Unlike the intrinsics, the loads are bounds checked: the kernels panic
+//! instead of reading past the end of the input.
+
+use std::simd::cmp::{SimdPartialEq, SimdPartialOrd};
+use std::simd::num::SimdUint;
+use std::simd::{simd_swizzle, u16x8, u32x4, u64x2, u8x16};
+
+use crate::scan;
+
+/// Number of bytes loaded at once
+pub(super) const VECTOR_SIZE: usize = 16;
+
+/// Checks that all the bytes are valid digits, scanning the whole string like
+/// `first_non_digit`
+#[inline]
+pub(super) fn check_all_chars_are_valid(string: &[u8]) -> bool {
+    first_non_digit(string).is_none()
+}
+
+/// Returns the index of the last digit in the string
+///
+/// In case of a string made composed by all digits, the portable
+/// implementation without fallback call will return 32.
+#[inline]
+pub(super) fn last_digit_byte(s: &[u8]) -> u32 {
+    if s.len() < VECTOR_SIZE {
+        return crate::fallback::last_digit_byte_bytes(s);
+    }
+    non_digit_mask(u8x16::from_slice(s)).trailing_zeros()
+}
+
+/// Returns the index of the last char in the string different from `separator`
+/// and `eol`
+///
+/// When the string is composed of all digits, then the returned index will be
+/// 32, i.e a parsing mask made up of all zeros.
+#[inline]
+pub(super) fn last_byte_without_separator(string: &[u8], separator: u8, eol: u8) -> u32 {
+    if string.len() < VECTOR_SIZE {
+        return crate::fallback::last_byte_without_separator_bytes(string, separator, eol);
+    }
+    separator_mask(u8x16::from_slice(string), separator, eol).trailing_zeros()
+}
+
+/// Returns the masks of the separators and of the non digit bytes in the
+/// first 16 chars of the string, both from a single load.
+///
+/// # Panics
+///
+/// Panics if the string has less than 16 chars.
+#[inline]
+pub(super) fn separator_and_non_digit_masks(string: &[u8], separator: u8, eol: u8) -> (u32, u32) {
+    let value = u8x16::from_slice(string);
+    (separator_mask(value, separator, eol), non_digit_mask(value))
+}
+
+/// Returns the offset of the first char of the string which isn't a digit, or
+/// None if they are all digits.
+///
+/// The whole string is scanned, 16 chars at a time; a string shorter than 16
+/// chars is read with a masked load.
+#[inline]
+pub(super) fn first_non_digit(s: &[u8]) -> Option<usize> {
+    first_flagged(s, non_digit_mask)
+}
+
+/// Returns the offset of the first `separator` or `eol` in the string, or None
+/// if there is none, scanning it like `first_non_digit`
+#[inline]
+pub(super) fn find_separator(s: &[u8], separator: u8, eol: u8) -> Option<usize> {
+    first_flagged(s, |value| separator_mask(value, separator, eol))
+}
+
+/// Returns the offset of the first char of the string flagged by `mask`, which
+/// returns the mask of the flagged chars of 16 loaded ones
+#[inline]
+fn first_flagged(s: &[u8], mask: impl Fn(u8x16) -> u32) -> Option<usize> {
+    if s.len() < VECTOR_SIZE {
+        return scan::first_flagged_short(mask(u8x16::load_or_default(s)), s.len());
+    }
+    scan::first_flagged(s.len(), VECTOR_SIZE, |offset| {
+        mask(u8x16::from_slice(&s[offset..]))
+    })
+}
+
+/// Returns the mask of the bytes of `value` equal to `separator` or `eol`,
+/// with the first byte in the lowest bit
+#[inline]
+fn separator_mask(value: u8x16, separator: u8, eol: u8) -> u32 {
+    let separators = value.simd_eq(u8x16::splat(separator)) | value.simd_eq(u8x16::splat(eol));
+    separators.to_bitmask() as u32
+}
+
+/// Returns the mask of the bytes of `value` which aren't digits, with the
+/// first byte in the lowest bit
+#[inline]
+fn non_digit_mask(value: u8x16) -> u32 {
+    // the comparisons are unsigned, so the bytes above 127 are above '9'
+    let non_digits = value.simd_lt(u8x16::splat(b'0')) | value.simd_gt(u8x16::splat(b'9'));
+    non_digits.to_bitmask() as u32
+}
+
+/// Loads the first 16 bytes of `s`, converts them from ASCII to numeric values
+/// and moves the first `n` to the right of the vector, padding with zeros.
+///
+/// # Panics
+///
+/// Panics if `s` has less than 16 chars or if `n` is greater than 16.
+#[inline]
+fn load_right_aligned_digits(s: &[u8], n: usize) -> u8x16 {
+    right_align_digits(u8x16::from_slice(s), n)
+}
+
+/// Converts the chars of `value` from ASCII to numeric values and moves the
+/// first `n` to the right of the vector, padding with zeros.
+///
+/// # Panics
+///
+/// Panics if `n` is greater than 16.
+#[inline]
+fn right_align_digits(value: u8x16, n: usize) -> u8x16 {
+    let digits = value - u8x16::splat(b'0');
+    // a byte shuffle by a variable amount isn't available on every target, so
+    // the vector is shifted as a single 128 bits integer: the first byte of
+    // the input is the lowest one, thus the first `n` bytes move to the
+    // highest positions with a left shift, and shifting by 128 bits gives all
+    // zeros
+    let digits = u128::from_le_bytes(digits.to_array());
+    let digits = digits.checked_shl(8 * (VECTOR_SIZE - n) as u32).unwrap_or(0);
+    u8x16::from_array(digits.to_le_bytes())
+}
+
+/// Combines 16 numeric bytes, the first being the most significant, into a
+/// 16 digits number.
+///
+/// Each step splits the lanes at even and odd positions, and merges them into
+/// lanes twice as wide holding twice the digits.
+#[inline]
+fn combine_16_digits(digits: u8x16) -> u64 {
+    let tens = simd_swizzle!(digits, [0, 2, 4, 6, 8, 10, 12, 14]).cast::<u16>();
+    let units = simd_swizzle!(digits, [1, 3, 5, 7, 9, 11, 13, 15]).cast::<u16>();
+    let pairs: u16x8 = tens * u16x8::splat(10) + units;
+
+    let high = simd_swizzle!(pairs, [0, 2, 4, 6]).cast::<u32>();
+    let low = simd_swizzle!(pairs, [1, 3, 5, 7]).cast::<u32>();
+    let quads: u32x4 = high * u32x4::splat(100) + low;
+
+    let high = simd_swizzle!(quads, [0, 2]).cast::<u64>();
+    let low = simd_swizzle!(quads, [1, 3]).cast::<u64>();
+    let octets: u64x2 = high * u64x2::splat(10_000) + low;
+
+    octets[0] * 100_000_000 + octets[1]
+}
+
+/// Parses the first `len` chars of the input string, which must be all digits.
+///
+/// `len` can be at most 16, so that the result always fits in an `u64`.
+///
+/// # Panics
+///
+/// Panics if the string has less than 16 chars.
+#[inline]
+pub(super) fn parse_digits_simd(s: &[u8], len: usize) -> u64 {
+    combine_16_digits(load_right_aligned_digits(s, len))
+}
+
+/// Returns the number of leading digits among the first 16 chars of the
+/// string, along with their value, validating and combining the digits from a
+/// single load. When all the 16 chars are digits, the number may continue past
+/// them.
+///
+/// # Panics
+///
+/// Panics if the string has less than 16 chars.
+#[inline]
+pub(super) fn parse_prefix_checked_simd(s: &[u8]) -> (u32, u64) {
+    prefix_of(u8x16::from_slice(s), VECTOR_SIZE)
+}
+
+/// Returns the offsets of the first `separator` or `eol` and of the first non
+/// digit among the first 16 chars of the string, 16 if there is none, along
+/// with the value of the chars before the terminator, from a single load.
+///
+/// # Panics
+///
+/// Panics if the string has less than 16 chars.
+#[inline]
+pub(super) fn parse_field_checked_simd(s: &[u8], separator: u8, eol: u8) -> (u32, u32, u64) {
+    field_of(u8x16::from_slice(s), VECTOR_SIZE, separator, eol)
+}
+
+/// Returns the number of leading digits of a string shorter than 16 chars,
+/// along with their value.
+///
+/// The chars are read with a masked load, padding the vector with zeros.
+#[inline]
+pub(super) fn parse_short_prefix(s: &[u8]) -> (u32, u64) {
+    prefix_of(u8x16::load_or_default(s), s.len())
+}
+
+/// Same as `parse_short_prefix`, for the first `len` chars of `s`: the ones
+/// past `len` are padding, and they are loaded but ignored.
+///
+/// # Panics
+///
+/// Panics if the string has less than 16 chars.
+#[inline]
+pub(super) fn parse_padded_prefix(s: &[u8], len: usize) -> (u32, u64) {
+    prefix_of(u8x16::from_slice(s), len.min(VECTOR_SIZE))
+}
+
+/// Returns the number of leading digits among the first `len` chars of
+/// `value`, along with their value
+#[inline]
+fn prefix_of(value: u8x16, len: usize) -> (u32, u64) {
+    // the padding bytes are not part of the number
+    let digits = (non_digit_mask(value) | crate::page::past_end_mask(len)).trailing_zeros();
+    (digits, combine_16_digits(right_align_digits(value, digits as usize)))
+}
+
+/// Returns, for a string shorter than 16 chars, the offset of the first
+/// `separator` or `eol`, which is the length of the string when there is none,
+/// the offset of the first non digit and the value of the chars before the
+/// terminator.
+///
+/// The chars before the terminator are all digits only when the two offsets
+/// are equal; otherwise, the value is meaningless.
+#[inline]
+pub(super) fn parse_short_field(s: &[u8], separator: u8, eol: u8) -> (u32, u32, u64) {
+    field_of(u8x16::load_or_default(s), s.len(), separator, eol)
+}
+
+/// Same as `parse_short_field`, for the first `len` chars of `s`: the ones
+/// past `len` are padding, and they are loaded but ignored.
+///
+/// # Panics
+///
+/// Panics if the string has less than 16 chars.
+#[inline]
+pub(super) fn parse_padded_field(s: &[u8], len: usize, separator: u8, eol: u8) -> (u32, u32, u64) {
+    field_of(u8x16::from_slice(s), len.min(VECTOR_SIZE), separator, eol)
+}
+
+/// Returns the offsets of the terminator and of the first non digit among the
+/// first `len` chars of `value`, along with the value of the chars before the
+/// terminator
+#[inline]
+fn field_of(value: u8x16, len: usize, separator: u8, eol: u8) -> (u32, u32, u64) {
+    // the end of the string terminates the field as well
+    let past_end = crate::page::past_end_mask(len);
+    let end = (separator_mask(value, separator, eol) | past_end).trailing_zeros();
+    let invalid = (non_digit_mask(value) | past_end).trailing_zeros();
+    (end, invalid, combine_16_digits(right_align_digits(value, end as usize)))
+}
+
+/// Parses an u64 from the first `len` chars of the input string, which must
+/// be all digits.
+///
+/// `len` can be at most 20, i.e. the number of digits of `u64::MAX`. If the
+/// number doesn't fit in an `u64`, `None` is returned.
+///
+/// # Panics
+///
+/// Panics if the string has less than `len` or 16 chars.
+#[inline]
+pub(super) fn parse_u64_chars_simd(s: &[u8], len: usize) -> Option<u64> {
+    if len <= VECTOR_SIZE {
+        return Some(parse_digits_simd(s, len));
+    }
+    // the last 16 digits are loaded with a single read ending at the last
+    // digit, while the remaining ones at the beginning are shifted in place
+    let low = load_right_aligned_digits(&s[len - VECTOR_SIZE..], VECTOR_SIZE);
+    let high = load_right_aligned_digits(s, len - VECTOR_SIZE);
+    combine_16_digits(high)
+        .checked_mul(10_000_000_000_000_000)?
+        .checked_add(combine_16_digits(low))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    static SEP: u8 = b',';
+    static EOL: u8 = b'\n';
+
+    #[test]
+    fn check_all_chars_are_valid_simd_valid() {
+        assert!(check_all_chars_are_valid(b"1234567890123456"));
+    }
+
+    #[test]
+    fn check_all_chars_are_valid_simd_invalid() {
+        assert!(!check_all_chars_are_valid(b"123456789,123456"));
+    }
+
+    #[test]
+    fn last_byte_without_separator_more_digit() {
+        assert_eq!(last_byte_without_separator(b"123456,789123456", SEP, EOL), 6);
+    }
+
+    #[test]
+    fn last_byte_without_separator_eol() {
+        assert_eq!(last_byte_without_separator(b"1234567\n89123456", SEP, EOL), 7);
+    }
+
+    #[test]
+    fn last_digit_byte_all_digits() {
+        assert_eq!(last_digit_byte(b"0123456789012345"), 32);
+    }
+
+    #[test]
+    fn last_digit_byte_non_ascii() {
+        assert_eq!(last_digit_byte("12é4567890123456".as_bytes()), 2);
+    }
+
+    #[test]
+    fn parse_digits_simd_every_length() {
+        let s = b"1234567890123456";
+        for len in 0..=VECTOR_SIZE {
+            let expected = s[..len].iter().fold(0, |a, c| a * 10 + (c - b'0') as u64);
+            assert_eq!(parse_digits_simd(s, len), expected);
+        }
+    }
+
+    #[test]
+    fn parse_digits_simd_all_nines() {
+        assert_eq!(parse_digits_simd(b"9999999999999999", 16), 9999999999999999);
+    }
+
+    #[test]
+    fn parse_u64_chars_simd_max_u64() {
+        let s = format!("{}", u64::MAX);
+        assert_eq!(parse_u64_chars_simd(s.as_bytes(), 20), Some(u64::MAX));
+    }
+
+    #[test]
+    fn parse_u64_chars_simd_overflow() {
+        assert_eq!(parse_u64_chars_simd(b"18446744073709551616", 20), None);
+    }
+
+    #[test]
+    fn separator_and_non_digit_masks_invalid_before_separator() {
+        let (separators, non_digits) = separator_and_non_digit_masks(b"12a4,67890123456", SEP, EOL);
+        assert_eq!(separators.trailing_zeros(), 4);
+        assert_eq!(non_digits.trailing_zeros(), 2);
+    }
+    #[test]
+    fn parse_short_prefix_every_length() {
+        let s = b"123456789012345";
+        for len in 0..VECTOR_SIZE {
+            let expected = s[..len].iter().fold(0, |a, c| a * 10 + (c - b'0') as u64);
+            assert_eq!(parse_short_prefix(&s[..len]), (len as u32, expected));
+        }
+    }
+
+    #[test]
+    fn parse_short_prefix_stops_at_non_digit() {
+        assert_eq!(parse_short_prefix(b"42,7"), (2, 42));
+        assert_eq!(parse_short_prefix(b"x42"), (0, 0));
+    }
+
+    #[test]
+    fn parse_short_field_terminators() {
+        assert_eq!(parse_short_field(b"123,4", SEP, EOL), (3, 3, 123));
+        assert_eq!(parse_short_field(b"123", SEP, EOL), (3, 3, 123));
+        assert_eq!(parse_short_field(b"\n", SEP, EOL), (0, 0, 0));
+        assert_eq!(parse_short_field(b"1a3,4", SEP, EOL).1, 1);
+    }
+
+}
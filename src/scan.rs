@@ -0,0 +1,69 @@
+//! Scans of whole inputs, one vector of chars at a time.
+//!
+//! The backends compute the mask of the flagged chars of a vector, e.g. the
+//! non digits, and these helpers turn the masks into the offset of the first
+//! flagged char of the input, the same on every backend.
+
+use crate::page;
+
+/// Returns the offset of the first flagged char of an input of `len` chars,
+/// or None if there is none.
+///
+/// `flags(offset)` returns the mask of the flagged chars among the `width`
+/// ones starting at `offset`, with the first char in the lowest bit. The input
+/// must have at least `width` chars: its last chars are covered by a vector
+/// ending at the end of the input, overlapping chars already scanned, which
+/// are dropped from the mask.
+#[inline(always)]
+pub(crate) fn first_flagged(len: usize, width: usize, flags: impl Fn(usize) -> u32) -> Option<usize> {
+    debug_assert!(len >= width);
+    let mut offset = 0;
+    while offset + width <= len {
+        let mask = flags(offset);
+        if mask != 0 {
+            return Some(offset + mask.trailing_zeros() as usize);
+        }
+        offset += width;
+    }
+    if offset == len {
+        return None;
+    }
+    let last = len - width;
+    first_flagged_short(flags(last) >> (offset - last), len - offset).map(|i| offset + i)
+}
+
+/// Returns the offset of the first flagged char of an input of `len` chars,
+/// less than 32, whose flags are all in `mask`, where the bits past the end of
+/// the input are meaningless
+#[inline(always)]
+pub(crate) fn first_flagged_short(mask: u32, len: usize) -> Option<usize> {
+    let offset = (mask | page::past_end_mask(len)).trailing_zeros() as usize;
+    if offset < len {
+        Some(offset)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_flagged_every_vector() {
+        // one flag every 10 chars, scanned 4 chars at a time
+        let flags = |offset: usize| (0..4).filter(|i| (offset + i) % 10 == 9).map(|i| 1 << i).sum();
+        assert_eq!(first_flagged(9, 4, flags), None);
+        assert_eq!(first_flagged(10, 4, flags), Some(9));
+        assert_eq!(first_flagged(12, 4, flags), Some(9));
+        assert_eq!(first_flagged(8, 4, flags), None);
+        assert_eq!(first_flagged(4, 4, |_| 0b1000), Some(3));
+    }
+
+    #[test]
+    fn first_flagged_short_ignores_past_end() {
+        assert_eq!(first_flagged_short(0b1000, 3), None);
+        assert_eq!(first_flagged_short(0b1100, 4), Some(2));
+        assert_eq!(first_flagged_short(0, 0), None);
+    }
+}
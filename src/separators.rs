@@ -0,0 +1,162 @@
+//! Sets of bytes terminating a field
+
+use std::error::Error;
+use std::fmt;
+
+/// Maximum number of bytes in a `SeparatorSet`, i.e. the length of the operand
+/// of the SSE4.2 string instructions
+pub const MAX_SEPARATORS: usize = 16;
+
+/// Set of up to 16 bytes terminating a field, e.g. all the delimiters of a
+/// tokenizer splitting on commas, semicolons, spaces and line endings.
+///
+/// The bytes are kept in the layout of the SSE4.2 string instructions, which
+/// compare 16 chars of the input against the whole set at once, along with a
+/// bitmap for the scalar lookups.
+///
+/// ```
+/// use simd_parsing::SeparatorSet;
+///
+/// let separators = SeparatorSet::new(b",; \t\n").unwrap();
+/// assert!(separators.contains(b';'));
+/// assert_eq!(separators.len(), 5);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct SeparatorSet {
+    /// The separators, in the order they were given and padded with zeros
+    bytes: [u8; MAX_SEPARATORS],
+    /// Number of separators
+    len: usize,
+    /// One bit per byte value, set for the separators
+    bitmap: [u64; 4],
+}
+
+impl SeparatorSet {
+    /// Creates the set of the distinct bytes of `separators`.
+    ///
+    /// Fails if there are more than 16 distinct bytes.
+    pub fn new(separators: &[u8]) -> Result<Self, TooManySeparators> {
+        let mut set = SeparatorSet {
+            bytes: [0; MAX_SEPARATORS],
+            len: 0,
+            bitmap: [0; 4],
+        };
+        for &byte in separators {
+            if set.contains(byte) {
+                continue;
+            }
+            if set.len == MAX_SEPARATORS {
+                return Err(TooManySeparators { separators: separators.to_vec() });
+            }
+            set.bytes[set.len] = byte;
+            set.len += 1;
+            set.bitmap[byte as usize / 64] |= 1 << (byte % 64);
+        }
+        Ok(set)
+    }
+
+    /// Returns whether `byte` is one of the separators
+    #[inline]
+    pub fn contains(&self, byte: u8) -> bool {
+        self.bitmap[byte as usize / 64] & (1 << (byte % 64)) != 0
+    }
+
+    /// Returns the separators, in the order they were first given
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes[..self.len]
+    }
+
+    /// Returns the number of separators
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the set has no separators, so that no field is ever
+    /// terminated
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the separators padded with zeros to 16 bytes, the operand of
+    /// the SSE4.2 string instructions
+    #[inline]
+    #[cfg_attr(
+        not(all(
+            target_arch = "x86_64",
+            any(feature = "backend-sse42", feature = "backend-avx2")
+        )),
+        allow(dead_code)
+    )]
+    pub(crate) fn padded(&self) -> &[u8; MAX_SEPARATORS] {
+        &self.bytes
+    }
+
+    /// Returns the offset of the first separator in `s`, or its length if
+    /// there is none
+    #[inline]
+    pub(crate) fn find(&self, s: &[u8]) -> usize {
+        s.iter().position(|&byte| self.contains(byte)).unwrap_or(s.len())
+    }
+}
+
+/// Error returned when creating a `SeparatorSet` with more than 16 distinct
+/// bytes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TooManySeparators {
+    /// The separators that were given
+    separators: Vec<u8>,
+}
+
+impl fmt::Display for TooManySeparators {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "a separator set holds at most {} distinct bytes, got {:?}",
+            MAX_SEPARATORS,
+            String::from_utf8_lossy(&self.separators)
+        )
+    }
+}
+
+impl Error for TooManySeparators {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn separator_set_skips_duplicates() {
+        let set = SeparatorSet::new(b",;,\n;").unwrap();
+        assert_eq!(set.as_bytes(), b",;\n");
+        assert_eq!(&set.padded()[..4], b",;\n\0");
+    }
+
+    #[test]
+    fn separator_set_contains() {
+        let set = SeparatorSet::new(b"\0,\xff").unwrap();
+        assert!(set.contains(0) && set.contains(b',') && set.contains(0xff));
+        assert!(!set.contains(b'1') && !set.contains(0xfe));
+    }
+
+    #[test]
+    fn separator_set_find() {
+        let set = SeparatorSet::new(b"; ").unwrap();
+        assert_eq!(set.find(b"123 456;"), 3);
+        assert_eq!(set.find(b"123"), 3);
+        assert_eq!(SeparatorSet::new(b"").unwrap().find(b"1,2"), 3);
+    }
+
+    #[test]
+    fn separator_set_too_many() {
+        let separators = b"abcdefghijklmnopq";
+        assert!(SeparatorSet::new(&separators[..16]).is_ok());
+        let e = SeparatorSet::new(separators).unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "a separator set holds at most 16 distinct bytes, got \"abcdefghijklmnopq\""
+        );
+    }
+}
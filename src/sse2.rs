@@ -0,0 +1,477 @@
+//! SSE2 implementations for parsing a u32 from a string.
+//!
+//! SSE2 is available on every x86_64 CPU, so these kernels give a vector
+//! speedup even where SSE4.1 is missing. They can't use the SSSE3 and SSE4.1
+//! instructions of the other kernels:
+//!   - instead of `_mm_shuffle_epi8`, the digits are right aligned by shifting
+//!     the whole register, split into two 64 bits halves;
+//!   - instead of `_mm_maddubs_epi16`, the pairs of digits are combined with
+//!     16 bits multiplications;
+//!   - instead of `_mm_packus_epi32`, the 4 digits groups are packed with
+//!     signed saturation, which never takes place since they are below 10000.
+
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::*;
+
+use crate::page;
+use crate::scan;
+
+/// Size of __m128i data type
+pub(super) const VECTOR_SIZE: usize = std::mem::size_of::<__m128i>();
+
+/// Checks that all the bytes are valid digits, scanning the whole string like
+/// `first_non_digit`
+#[inline]
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn check_all_chars_are_valid(string: &[u8]) -> bool {
+    first_non_digit(string).is_none()
+}
+
+/// Returns the index of the last digit in the string
+///
+/// In case of a string made composed by all digits, the SSE2 implementation
+/// without fallback call will return 32.
+#[inline]
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn last_digit_byte(s: &[u8]) -> u32 {
+    if s.len() < VECTOR_SIZE {
+        return crate::fallback::last_digit_byte_bytes(s);
+    }
+    let value = _mm_loadu_si128(s.as_ptr() as _);
+    _mm_movemask_epi8(non_digits(value)).trailing_zeros()
+}
+
+/// Returns the index of the last char in the string different from `separator`
+/// and `eol`
+///
+/// When the string is composed of all digits, then the returned index will be
+/// 32, i.e a parsing mask made up of all zeros.
+#[inline]
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn last_byte_without_separator(
+    string: &[u8],
+    separator: u8,
+    eol: u8
+) -> u32 {
+    if string.len() < VECTOR_SIZE {
+        return crate::fallback::last_byte_without_separator_bytes(string, separator, eol);
+    }
+    let value = _mm_loadu_si128(string.as_ptr() as _);
+    _mm_movemask_epi8(separators(value, separator, eol)).trailing_zeros()
+}
+
+/// Returns the movemasks of the separators and of the non digit bytes in the
+/// first 16 chars of the string, both from a single load.
+///
+/// The input string *must have* at least 16 chars, otherwise the internal
+/// operations will load memory outside the string bound.
+#[inline]
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn separator_and_non_digit_masks(
+    string: &[u8],
+    separator: u8,
+    eol: u8
+) -> (u32, u32) {
+    let value = _mm_loadu_si128(string.as_ptr() as _);
+    (
+        _mm_movemask_epi8(separators(value, separator, eol)) as u32,
+        _mm_movemask_epi8(non_digits(value)) as u32,
+    )
+}
+
+/// Returns the offset of the first char of the string which isn't a digit, or
+/// None if they are all digits.
+///
+/// The whole string is scanned, 16 chars at a time; a string shorter than 16
+/// chars is read with a single load, which never crosses its memory page.
+#[inline]
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn first_non_digit(s: &[u8]) -> Option<usize> {
+    first_flagged(s, |value| non_digits(value))
+}
+
+/// Returns the offset of the first `separator` or `eol` in the string, or None
+/// if there is none, scanning it like `first_non_digit`
+#[inline]
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn find_separator(s: &[u8], separator: u8, eol: u8) -> Option<usize> {
+    first_flagged(s, |value| separators(value, separator, eol))
+}
+
+/// Returns the offset of the first char of the string flagged by `flags`,
+/// which sets all the bits of the flagged bytes of 16 loaded ones
+#[inline]
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+unsafe fn first_flagged(s: &[u8], flags: impl Fn(__m128i) -> __m128i) -> Option<usize> {
+    let mask = |value| _mm_movemask_epi8(flags(value)) as u32;
+    if s.len() < VECTOR_SIZE {
+        if s.is_empty() {
+            return None;
+        }
+        return scan::first_flagged_short(mask(page::load_short_128(s)), s.len());
+    }
+    scan::first_flagged(s.len(), VECTOR_SIZE, |offset| {
+        mask(_mm_loadu_si128(s.as_ptr().add(offset) as _))
+    })
+}
+
+/// Sets all the bits of the bytes of `value` equal to `separator` or `eol`
+#[inline]
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+unsafe fn separators(value: __m128i, separator: u8, eol: u8) -> __m128i {
+    _mm_or_si128(
+        _mm_cmpeq_epi8(value, _mm_set1_epi8(separator as i8)),
+        _mm_cmpeq_epi8(value, _mm_set1_epi8(eol as i8)),
+    )
+}
+
+/// Sets all the bits of the bytes of `value` which aren't digits
+#[inline]
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+unsafe fn non_digits(value: __m128i) -> __m128i {
+    // the comparisons are signed, so the bytes above 127 are below '0'
+    _mm_or_si128(
+        _mm_cmplt_epi8(value, _mm_set1_epi8(b'0' as i8)),
+        _mm_cmpgt_epi8(value, _mm_set1_epi8(b'9' as i8)),
+    )
+}
+
+/// Loads 16 bytes from `ptr`, converts them from ASCII to numeric values and
+/// moves the first `n` to the right of the register, padding with zeros.
+///
+/// # Safety
+///
+/// `ptr` must point to at least 16 readable bytes and `n` must be at most 16.
+#[inline]
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+unsafe fn load_right_aligned_digits(ptr: *const u8, n: usize) -> __m128i {
+    right_align_digits(_mm_loadu_si128(ptr as *const _), n)
+}
+
+/// Converts the chars of `chunk` from ASCII to numeric values and moves the
+/// first `n` to the right of the register, padding with zeros.
+///
+/// `n` must be at most 16.
+#[inline]
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+unsafe fn right_align_digits(chunk: __m128i, n: usize) -> __m128i {
+    let chunk = _mm_sub_epi8(chunk, _mm_set1_epi8(b'0' as i8));
+    // SSE2 shifts the whole register only by a constant amount of bytes, so
+    // the shift is done on its two halves, as a single 128 bits integer: the
+    // first byte of the input is the lowest one, thus the first `n` bytes move
+    // to the highest positions with a left shift
+    let low = _mm_cvtsi128_si64(chunk) as u64;
+    let high = _mm_cvtsi128_si64(_mm_unpackhi_epi64(chunk, chunk)) as u64;
+    let digits = ((high as u128) << 64) | low as u128;
+    // shifting by 128 bits, i.e. no digit, gives all zeros
+    let digits = digits.checked_shl(8 * (VECTOR_SIZE - n) as u32).unwrap_or(0);
+    _mm_set_epi64x((digits >> 64) as i64, digits as i64)
+}
+
+/// Combines 16 numeric bytes, the first being the most significant, into a
+/// 16 digits number.
+#[inline]
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+unsafe fn combine_16_digits(chunk: __m128i) -> u64 {
+    // every 16 bits lane holds two digits, the most significant in its lowest
+    // byte
+    let tens = _mm_and_si128(chunk, _mm_set1_epi16(0xFF));
+    let units = _mm_srli_epi16(chunk, 8);
+    let chunk = _mm_add_epi16(_mm_mullo_epi16(tens, _mm_set1_epi16(10)), units);
+
+    let mult = _mm_set_epi16(1, 100, 1, 100, 1, 100, 1, 100);
+    let chunk = _mm_madd_epi16(chunk, mult);
+
+    // the 32 bits lanes are below 10000, so they fit in a signed 16 bits lane
+    let chunk = _mm_packs_epi32(chunk, chunk);
+    let mult = _mm_set_epi16(0, 0, 0, 0, 1, 10000, 1, 10000);
+    let chunk = _mm_madd_epi16(chunk, mult);
+
+    // the lower 32 bits hold the first 8 digits, the upper 32 bits the last 8
+    let chunk = _mm_cvtsi128_si64(chunk) as u64;
+    ((chunk & 0xffffffff) * 100000000) + (chunk >> 32)
+}
+
+/// Parses the first `len` chars of the input string, which must be all digits.
+///
+/// `len` can be at most 16, so that the result always fits in an `u64`.
+///
+/// The input string *must have* at least 16 chars, otherwise the internal
+/// operations will load memory outside the string bound.
+#[inline]
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn parse_digits_simd(s: &[u8], len: usize) -> u64 {
+    combine_16_digits(load_right_aligned_digits(s.as_ptr(), len))
+}
+
+/// Returns the number of leading digits among the first 16 chars of the
+/// string, along with their value, validating and combining the digits from a
+/// single load. When all the 16 chars are digits, the number may continue past
+/// them.
+///
+/// The input string *must have* at least 16 chars, otherwise the internal
+/// operations will load memory outside the string bound.
+#[inline]
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn parse_prefix_checked_simd(s: &[u8]) -> (u32, u64) {
+    prefix_of(_mm_loadu_si128(s.as_ptr() as _), VECTOR_SIZE)
+}
+
+/// Returns the offsets of the first `separator` or `eol` and of the first non
+/// digit among the first 16 chars of the string, 16 if there is none, along
+/// with the value of the chars before the terminator, from a single load.
+///
+/// The input string *must have* at least 16 chars, otherwise the internal
+/// operations will load memory outside the string bound.
+#[inline]
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn parse_field_checked_simd(s: &[u8], separator: u8, eol: u8) -> (u32, u32, u64) {
+    field_of(_mm_loadu_si128(s.as_ptr() as _), VECTOR_SIZE, separator, eol)
+}
+
+/// Returns the number of leading digits of a string shorter than 16 chars,
+/// along with their value.
+///
+/// The chars are read with a single load, which may read past the end of the
+/// string, but never past the end of its memory page.
+#[inline]
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn parse_short_prefix(s: &[u8]) -> (u32, u64) {
+    if s.is_empty() {
+        return (0, 0);
+    }
+    prefix_of(page::load_short_128(s), s.len())
+}
+
+/// Same as `parse_short_prefix`, for the first `len` chars of `s`, which has
+/// at least 16 chars: the ones past `len` are padding, and they are loaded but
+/// ignored.
+#[inline]
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn parse_padded_prefix(s: &[u8], len: usize) -> (u32, u64) {
+    prefix_of(_mm_loadu_si128(s.as_ptr() as _), len.min(VECTOR_SIZE))
+}
+
+/// Returns the number of leading digits among the first `len` chars of
+/// `value`, along with their value
+#[inline]
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+unsafe fn prefix_of(value: __m128i, len: usize) -> (u32, u64) {
+    let non_digits = _mm_movemask_epi8(non_digits(value)) as u32;
+    // the bytes past the end of the string are not part of the number
+    let digits = (non_digits | page::past_end_mask(len)).trailing_zeros();
+    (digits, combine_16_digits(right_align_digits(value, digits as usize)))
+}
+
+/// Returns, for a string shorter than 16 chars, the offset of the first
+/// `separator` or `eol`, which is the length of the string when there is none,
+/// the offset of the first non digit and the value of the chars before the
+/// terminator.
+///
+/// The chars before the terminator are all digits only when the two offsets
+/// are equal; otherwise, the value is meaningless.
+#[inline]
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn parse_short_field(s: &[u8], separator: u8, eol: u8) -> (u32, u32, u64) {
+    if s.is_empty() {
+        return (0, 0, 0);
+    }
+    field_of(page::load_short_128(s), s.len(), separator, eol)
+}
+
+/// Same as `parse_short_field`, for the first `len` chars of `s`, which has at
+/// least 16 chars: the ones past `len` are padding, and they are loaded but
+/// ignored.
+#[inline]
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn parse_padded_field(
+    s: &[u8],
+    len: usize,
+    separator: u8,
+    eol: u8
+) -> (u32, u32, u64) {
+    field_of(_mm_loadu_si128(s.as_ptr() as _), len.min(VECTOR_SIZE), separator, eol)
+}
+
+/// Returns the offsets of the terminator and of the first non digit among the
+/// first `len` chars of `value`, along with the value of the chars before the
+/// terminator
+#[inline]
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+unsafe fn field_of(value: __m128i, len: usize, separator: u8, eol: u8) -> (u32, u32, u64) {
+    // the end of the string terminates the field as well
+    let past_end = page::past_end_mask(len);
+    let separators = _mm_movemask_epi8(separators(value, separator, eol)) as u32;
+    let non_digits = _mm_movemask_epi8(non_digits(value)) as u32;
+    let end = (separators | past_end).trailing_zeros();
+    let invalid = (non_digits | past_end).trailing_zeros();
+    (end, invalid, combine_16_digits(right_align_digits(value, end as usize)))
+}
+
+/// Parses an u64 from the first `len` chars of the input string, which must
+/// be all digits.
+///
+/// `len` can be at most 20, i.e. the number of digits of `u64::MAX`. If the
+/// number doesn't fit in an `u64`, `None` is returned.
+///
+/// The input string *must have* at least 16 chars, otherwise the internal
+/// operations will load memory outside the string bound.
+#[inline]
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse2")]
+pub(super) unsafe fn parse_u64_chars_simd(s: &[u8], len: usize) -> Option<u64> {
+    if len <= VECTOR_SIZE {
+        return Some(parse_digits_simd(s, len));
+    }
+    // the last 16 digits are loaded with a single read ending at the last
+    // digit, while the remaining ones at the beginning are shifted in place
+    let low = load_right_aligned_digits(s.as_ptr().add(len - VECTOR_SIZE), VECTOR_SIZE);
+    let high = load_right_aligned_digits(s.as_ptr(), len - VECTOR_SIZE);
+    combine_16_digits(high)
+        .checked_mul(10_000_000_000_000_000)?
+        .checked_add(combine_16_digits(low))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    static SEP: u8 = b',';
+    static EOL: u8 = b'\n';
+
+    #[test]
+    fn check_all_chars_are_valid_simd_valid() {
+        let s = b"1234567890123456";
+        unsafe {
+            assert!(check_all_chars_are_valid(s));
+        }
+    }
+
+    #[test]
+    fn check_all_chars_are_valid_simd_invalid() {
+        let s = b"123456789,123456";
+        unsafe {
+            assert!(!check_all_chars_are_valid(s));
+        }
+    }
+
+    #[test]
+    fn last_byte_without_separator_more_digit() {
+        let s = b"123456,789123456";
+        unsafe {
+            assert_eq!(last_byte_without_separator(s, SEP, EOL), 6);
+        }
+    }
+
+    #[test]
+    fn last_byte_without_separator_eol() {
+        let s = b"1234567\n89123456";
+        unsafe {
+            assert_eq!(last_byte_without_separator(s, SEP, EOL), 7);
+        }
+    }
+
+    #[test]
+    fn last_digit_byte_all_digits() {
+        let s = b"0123456789012345";
+        unsafe {
+            assert_eq!(last_digit_byte(s), 32);
+        }
+    }
+
+    #[test]
+    fn last_digit_byte_non_ascii() {
+        let s = "12é4567890123456".as_bytes();
+        unsafe {
+            assert_eq!(last_digit_byte(s), 2);
+        }
+    }
+
+    #[test]
+    fn parse_digits_simd_every_length() {
+        let s = b"1234567890123456";
+        for len in 0..=VECTOR_SIZE {
+            let expected = s[..len].iter().fold(0, |a, c| a * 10 + (c - b'0') as u64);
+            unsafe {
+                assert_eq!(parse_digits_simd(s, len), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn parse_digits_simd_all_nines() {
+        let s = b"9999999999999999";
+        unsafe {
+            assert_eq!(parse_digits_simd(s, 16), 9999999999999999);
+        }
+    }
+
+    #[test]
+    fn parse_u64_chars_simd_20_digits() {
+        let s = b"12345678901234567890,";
+        unsafe {
+            assert_eq!(parse_u64_chars_simd(s, 20), Some(12345678901234567890));
+        }
+    }
+
+    #[test]
+    fn parse_u64_chars_simd_overflow() {
+        let s = b"18446744073709551616";
+        unsafe {
+            assert_eq!(parse_u64_chars_simd(s, 20), None);
+        }
+    }
+
+    #[test]
+    fn separator_and_non_digit_masks_invalid_before_separator() {
+        let s = b"12a4,67890123456";
+        unsafe {
+            let (separators, non_digits) = separator_and_non_digit_masks(s, SEP, EOL);
+            assert_eq!(separators.trailing_zeros(), 4);
+            assert_eq!(non_digits.trailing_zeros(), 2);
+        }
+    }
+    #[test]
+    fn parse_short_prefix_every_length() {
+        let s = b"123456789012345";
+        for len in 0..VECTOR_SIZE {
+            let expected = s[..len].iter().fold(0, |a, c| a * 10 + (c - b'0') as u64);
+            assert_eq!(unsafe { parse_short_prefix(&s[..len]) }, (len as u32, expected));
+        }
+    }
+
+    #[test]
+    fn parse_short_prefix_stops_at_non_digit() {
+        assert_eq!(unsafe { parse_short_prefix(b"42,7") }, (2, 42));
+        assert_eq!(unsafe { parse_short_prefix(b"x42") }, (0, 0));
+    }
+
+    #[test]
+    fn parse_short_field_terminators() {
+        assert_eq!(unsafe { parse_short_field(b"123,4", SEP, EOL) }, (3, 3, 123));
+        assert_eq!(unsafe { parse_short_field(b"123", SEP, EOL) }, (3, 3, 123));
+        assert_eq!(unsafe { parse_short_field(b"\n", SEP, EOL) }, (0, 0, 0));
+        assert_eq!(unsafe { parse_short_field(b"1a3,4", SEP, EOL) }.1, 1);
+    }
+
+}
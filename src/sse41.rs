@@ -1,24 +1,49 @@
 //! SSE4.1 implementations for parsing a u32 from a string.
+// Without the SSE4.1 and SSE4.2 backends, only the kernels shared with AVX2 are
+// used.
+#![cfg_attr(
+    not(any(feature = "backend-sse41", feature = "backend-sse42")),
+    allow(dead_code)
+)]
 
 #[cfg(target_arch = "x86_64")]
 use std::arch::x86_64::*;
 
+use crate::page;
+use crate::scan;
+use crate::separators::SeparatorSet;
+
 /// Size of __m128i data type
 pub(super) const VECTOR_SIZE: usize = std::mem::size_of::<__m128i>();
 
-/// Checks that all the bytes are valid digits
+/// Shuffle masks for `_mm_shuffle_epi8`: the mask at index `n` moves the first
+/// `n` bytes of a register to its rightmost positions, zeroing the others.
+pub(super) static RIGHT_ALIGN_MASKS: [[u8; VECTOR_SIZE]; VECTOR_SIZE + 1] = right_align_masks();
+
+/// Builds the table of `RIGHT_ALIGN_MASKS`
+const fn right_align_masks() -> [[u8; VECTOR_SIZE]; VECTOR_SIZE + 1] {
+    // the most significant bit set in a mask byte zeroes the output byte
+    let mut masks = [[0x80u8; VECTOR_SIZE]; VECTOR_SIZE + 1];
+    let mut n = 0;
+    while n <= VECTOR_SIZE {
+        let mut i = VECTOR_SIZE - n;
+        while i < VECTOR_SIZE {
+            masks[n][i] = (i + n - VECTOR_SIZE) as u8;
+            i += 1;
+        }
+        n += 1;
+    }
+    masks
+}
+
+/// Checks that all the bytes are valid digits, scanning the whole string like
+/// `first_non_digit`
 #[allow(dead_code)]
 #[inline]
 #[cfg(target_arch = "x86_64")]
 #[target_feature(enable = "sse4.1")]
-pub(super) unsafe fn check_all_chars_are_valid(string: &str) -> bool {
-    if string.len() < VECTOR_SIZE {
-        return crate::fallback::check_all_chars_are_valid(string);
-    }
-    // since `last_digit_byte` counts the trailing zeros of the resulting mask,
-    // if the mask is made of all 0s, meaning that the string is made of all
-    // digits, the results will be 32, i.e. a u32 mask with all 0s
-    last_digit_byte(string) == 32 
+pub(super) unsafe fn check_all_chars_are_valid(string: &[u8]) -> bool {
+    first_non_digit(string).is_none()
 }
 
 /// Returns the index of the last digit in the string
@@ -28,9 +53,9 @@ pub(super) unsafe fn check_all_chars_are_valid(string: &str) -> bool {
 #[inline]
 #[cfg(target_arch = "x86_64")]
 #[target_feature(enable = "sse4.1")]
-pub(super) unsafe fn last_digit_byte(s: &str) -> u32 {
+pub(super) unsafe fn last_digit_byte(s: &[u8]) -> u32 {
     if s.len() < VECTOR_SIZE {
-        return crate::fallback::last_digit_byte(s);
+        return crate::fallback::last_digit_byte_bytes(s);
     }
     // initialize the constants
     let zeros = _mm_set1_epi8(b'0' as i8);
@@ -63,12 +88,12 @@ pub(super) unsafe fn last_digit_byte(s: &str) -> u32 {
 #[cfg(target_arch = "x86_64")]
 #[target_feature(enable = "sse2")]
 pub(super) unsafe fn last_byte_without_separator(
-    string: &str,
+    string: &[u8],
     separator: u8,
     eol: u8
 ) -> u32 {
     if string.len() < VECTOR_SIZE {
-        return crate::fallback::last_byte_without_separator(
+        return crate::fallback::last_byte_without_separator_bytes(
             string,
             separator,
             eol);
@@ -95,42 +120,116 @@ pub(super) unsafe fn last_byte_without_separator(
     movemask.trailing_zeros()
 }
 
-/// Parses 8 integers from input string using SIMD instructions.
+/// Returns the movemasks of the separators and of the non digit bytes in the
+/// first 16 chars of the string.
+///
+/// Both masks come from a single load, so that the checked parsing can locate
+/// the end of the field and the first invalid byte at once: their trailing
+/// zeros are the respective offsets, 32 if the mask is empty.
 ///
 /// The input string *must have* at least 16 chars, otherwise the internal
 /// operations will load memory outside the string bound.
+#[inline]
 #[cfg(target_arch = "x86_64")]
 #[target_feature(enable = "sse4.1")]
-pub(super) unsafe fn parse_8_chars_simd(s: &str) -> u32 {
-    let mut chunk = _mm_lddqu_si128(s.as_ptr() as *const _);
-    // do not touch last 8 chars, since we don't know what they contain, avoiding
-    // any kind of underflow
-    let zeros = _mm_set_epi8(
-        0, 0, 0, 0, 0, 0, 0, 0, b'0' as i8, b'0' as i8, b'0' as i8, b'0' as i8, b'0' as i8,
-        b'0' as i8, b'0' as i8, b'0' as i8,
+pub(super) unsafe fn separator_and_non_digit_masks(
+    string: &[u8],
+    separator: u8,
+    eol: u8
+) -> (u32, u32) {
+    let value = _mm_loadu_si128(string.as_ptr() as _);
+    masks_of(value, separator, eol)
+}
+
+/// Returns the movemasks of the bytes of `value` equal to `separator` or
+/// `eol`, and of the ones which aren't digits
+#[inline]
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse4.1")]
+unsafe fn masks_of(value: __m128i, separator: u8, eol: u8) -> (u32, u32) {
+    // same comparisons of `last_byte_without_separator`
+    let separators = _mm_or_si128(
+        _mm_cmpeq_epi8(value, _mm_set1_epi8(separator as i8)),
+        _mm_cmpeq_epi8(value, _mm_set1_epi8(eol as i8)),
+    );
+    // same comparisons of `last_digit_byte`
+    let non_digits = _mm_or_si128(
+        _mm_cmplt_epi8(value, _mm_set1_epi8(b'0' as i8)),
+        _mm_cmpgt_epi8(value, _mm_set1_epi8(b'9' as i8)),
     );
-    chunk = _mm_sub_epi16(chunk, zeros);
 
-    let mult = _mm_set_epi8(0, 0, 0, 0, 0, 0, 0, 0, 1, 10, 1, 10, 1, 10, 1, 10);
-    chunk = _mm_maddubs_epi16(chunk, mult);
+    (
+        _mm_movemask_epi8(separators) as u32,
+        _mm_movemask_epi8(non_digits) as u32,
+    )
+}
 
-    let mult = _mm_set_epi16(1, 100, 1, 100, 1, 100, 1, 100);
-    chunk = _mm_madd_epi16(chunk, mult);
+/// Returns the offset of the first char of the string which isn't a digit, or
+/// None if they are all digits.
+///
+/// The whole string is scanned, 16 chars at a time; a string shorter than 16
+/// chars is read with a single load, which never crosses its memory page.
+#[inline]
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse4.1")]
+pub(super) unsafe fn first_non_digit(s: &[u8]) -> Option<usize> {
+    first_flagged(s, |value| masks_of(value, 0, 0).1)
+}
 
-    chunk = _mm_packus_epi32(chunk, chunk);
-    let mult = _mm_set_epi16(1, 10000, 1, 10000, 1, 10000, 1, 10000);
-    chunk = _mm_madd_epi16(chunk, mult);
+/// Returns the offset of the first `separator` or `eol` in the string, or None
+/// if there is none, scanning it like `first_non_digit`
+#[inline]
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse4.1")]
+pub(super) unsafe fn find_separator(s: &[u8], separator: u8, eol: u8) -> Option<usize> {
+    first_flagged(s, |value| masks_of(value, separator, eol).0)
+}
+
+/// Returns the offset of the first char of the string flagged by `mask`, which
+/// returns the movemask of the flagged chars of 16 loaded ones
+#[inline]
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse4.1")]
+unsafe fn first_flagged(s: &[u8], mask: impl Fn(__m128i) -> u32) -> Option<usize> {
+    if s.len() < VECTOR_SIZE {
+        if s.is_empty() {
+            return None;
+        }
+        return scan::first_flagged_short(mask(page::load_short_128(s)), s.len());
+    }
+    scan::first_flagged(s.len(), VECTOR_SIZE, |offset| {
+        mask(_mm_loadu_si128(s.as_ptr().add(offset) as _))
+    })
+}
 
-    _mm_cvtsi128_si32(chunk) as u32
+/// Returns the movemask of the chars equal to any byte of `separators` in the
+/// first 16 chars of the string.
+///
+/// Without the string instructions of SSE4.2, the chunk is compared against
+/// one separator at a time.
+///
+/// The input string *must have* at least 16 chars, otherwise the internal
+/// operations will load memory outside the string bound.
+#[inline]
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse4.1")]
+pub(super) unsafe fn separator_set_mask(string: &[u8], separators: &SeparatorSet) -> u32 {
+    let value = _mm_loadu_si128(string.as_ptr() as _);
+    let mut found = _mm_setzero_si128();
+    for &separator in separators.as_bytes() {
+        found = _mm_or_si128(found, _mm_cmpeq_epi8(value, _mm_set1_epi8(separator as i8)));
+    }
+    _mm_movemask_epi8(found) as u32
 }
 
 /// Parses an u32 from the given string made of all numbers.
 ///
 /// The input string *must have* at least 16 chars, otherwise the internal
 /// operations will load memory outside the string bound.
+#[inline]
 #[cfg(target_arch = "x86_64")]
 #[target_feature(enable = "sse4.1")]
-pub(super) unsafe fn parse_integer_simd_all_numbers(s: &str) -> u32 {
+pub(super) unsafe fn parse_integer_simd_all_numbers(s: &[u8]) -> u32 {
     let mut chunk = _mm_lddqu_si128(s.as_ptr() as *const _);
     let zeros = _mm_set1_epi8(b'0' as i8);
     chunk = _mm_sub_epi16(chunk, zeros);
@@ -150,158 +249,218 @@ pub(super) unsafe fn parse_integer_simd_all_numbers(s: &str) -> u32 {
     (((chunk & 0xffffffff) * 100000000) + (chunk >> 32)) as u32
 }
 
-/// Parses 5 integers from input string using SIMD instructions.
+/// Loads 16 bytes from `ptr`, converts them from ASCII to numeric values and
+/// moves the first `n` to the right of the register, padding with zeros.
 ///
-/// The input string *must have* at least 16 chars, otherwise the internal
-/// operations will load memory outside the string bound.
+/// # Safety
+///
+/// `ptr` must point to at least 16 readable bytes and `n` must be at most 16.
+#[inline]
 #[cfg(target_arch = "x86_64")]
 #[target_feature(enable = "sse4.1")]
-pub(super) unsafe fn parse_5_chars_simd(s: &str) -> u32 {
-    let mut chunk = _mm_loadu_si128(s.as_ptr() as *const _);
-    let zeros = _mm_set1_epi8(b'0' as i8);
-    chunk = _mm_sub_epi16(chunk, zeros);
+pub(super) unsafe fn load_right_aligned_digits(ptr: *const u8, n: usize) -> __m128i {
+    right_align_digits(_mm_loadu_si128(ptr as *const _), n)
+}
 
-    let mult = _mm_set_epi8(0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 10, 1, 10);
-    chunk = _mm_maddubs_epi16(chunk, mult);
-    // make first two 16 bit chunks a 4 digit number, while leaving the next 16
-    // bits untouched
-    let mult = _mm_set_epi16(0, 0, 0, 0, 0, 1, 1, 100);
-    chunk = _mm_madd_epi16(chunk, mult);
+/// Converts the chars of `chunk` from ASCII to numeric values and moves the
+/// first `n` to the right of the register, padding with zeros.
+///
+/// `n` must be at most 16.
+#[inline]
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse4.1")]
+unsafe fn right_align_digits(chunk: __m128i, n: usize) -> __m128i {
+    let chunk = _mm_sub_epi8(chunk, _mm_set1_epi8(b'0' as i8));
+    // the shuffle takes place after the subtraction, so the padding bytes are
+    // real zeros
+    let mask = _mm_loadu_si128(RIGHT_ALIGN_MASKS[n].as_ptr() as *const _);
+    _mm_shuffle_epi8(chunk, mask)
+}
 
-    chunk = _mm_packus_epi32(chunk, chunk);
-    // make room for the 1 digit number and sum them up
-    let mult = _mm_set_epi16(0, 0, 0, 0, 0, 0, 1, 10);
-    chunk = _mm_madd_epi16(chunk, mult);
+/// Combines 16 numeric bytes, the first being the most significant, into a
+/// 16 digits number.
+#[inline]
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse4.1")]
+unsafe fn combine_16_digits(chunk: __m128i) -> u64 {
+    let mult = _mm_set_epi8(1, 10, 1, 10, 1, 10, 1, 10, 1, 10, 1, 10, 1, 10, 1, 10);
+    let chunk = _mm_maddubs_epi16(chunk, mult);
+
+    let mult = _mm_set_epi16(1, 100, 1, 100, 1, 100, 1, 100);
+    let chunk = _mm_madd_epi16(chunk, mult);
+
+    let chunk = _mm_packus_epi32(chunk, chunk);
+    let mult = _mm_set_epi16(0, 0, 0, 0, 1, 10000, 1, 10000);
+    let chunk = _mm_madd_epi16(chunk, mult);
 
-    _mm_cvtsi128_si32(chunk) as u32
+    // the lower 32 bits hold the first 8 digits, the upper 32 bits the last 8
+    let chunk = _mm_cvtsi128_si64(chunk) as u64;
+    ((chunk & 0xffffffff) * 100000000) + (chunk >> 32)
 }
 
-/// Parses 4 integers from input string using SIMD instructions.
+/// Parses the first `len` chars of the input string, which must be all digits.
+///
+/// `len` can be at most 16, so that the result always fits in an `u64`.
 ///
 /// The input string *must have* at least 16 chars, otherwise the internal
 /// operations will load memory outside the string bound.
+#[inline]
 #[cfg(target_arch = "x86_64")]
 #[target_feature(enable = "sse4.1")]
-pub(super) unsafe fn parse_4_chars_simd(s: &str) -> u32 {
-    let mut chunk = _mm_loadu_si128(s.as_ptr() as *const _);
-    let zeros = _mm_set1_epi8(b'0' as i8);
-    chunk = _mm_sub_epi16(chunk, zeros);
-
-    let mult = _mm_set_epi8(0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 10, 1, 10);
-    chunk = _mm_maddubs_epi16(chunk, mult);
-    // make first two 16 bit chunks a 4 digit number, while leaving the next 16
-    // bits untouched
-    let mult = _mm_set_epi16(0, 0, 0, 0, 0, 0, 1, 100);
-    chunk = _mm_madd_epi16(chunk, mult);
-    _mm_cvtsi128_si32(chunk) as u32
+pub(super) unsafe fn parse_digits_simd(s: &[u8], len: usize) -> u64 {
+    combine_16_digits(load_right_aligned_digits(s.as_ptr(), len))
 }
 
-/// Parses 6 integers from input string using SIMD instructions.
+/// Returns the number of leading digits among the first 16 chars of the
+/// string, along with their value, in a single pass.
+///
+/// The digit range mask and the multiply-add reduction come from the same
+/// load, and the trailing zeros of the mask index the shuffle which right
+/// aligns the digits: every length takes the same path, with no branch on the
+/// number of digits. When all the 16 chars are digits, the number may continue
+/// past them.
 ///
 /// The input string *must have* at least 16 chars, otherwise the internal
 /// operations will load memory outside the string bound.
+#[inline]
 #[cfg(target_arch = "x86_64")]
 #[target_feature(enable = "sse4.1")]
-pub(super) unsafe fn parse_6_chars_simd(s: &str) -> u32 {
-    let mut chunk = _mm_loadu_si128(s.as_ptr() as *const _);
-    let zeros = _mm_set1_epi8(b'0' as i8);
-    chunk = _mm_sub_epi16(chunk, zeros);
-
-    let mult = _mm_set_epi8(0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 10, 1, 10, 1, 10);
-    chunk = _mm_maddubs_epi16(chunk, mult);
-    // make first two 16 bit chunks a 4 digit number, while leaving the next 16
-    // bits untouched
-    let mult = _mm_set_epi16(0, 0, 0, 0, 0, 1, 1, 100);
-    chunk = _mm_madd_epi16(chunk, mult);
-
-    chunk = _mm_packus_epi32(chunk, chunk);
-    // make room for the 2 digit number and sum them up
-    let mult = _mm_set_epi16(0, 0, 0, 0, 0, 0, 1, 100);
-    chunk = _mm_madd_epi16(chunk, mult);
-
-    _mm_cvtsi128_si32(chunk) as u32
+pub(super) unsafe fn parse_prefix_checked_simd(s: &[u8]) -> (u32, u64) {
+    prefix_of(_mm_loadu_si128(s.as_ptr() as _), VECTOR_SIZE)
 }
 
-/// Parses 7 integers from input string using SIMD instructions.
+/// Returns the offsets of the first `separator` or `eol` and of the first non
+/// digit among the first 16 chars of the string, 16 if there is none, along
+/// with the value of the chars before the terminator, in a single pass like
+/// `parse_prefix_checked_simd`.
 ///
 /// The input string *must have* at least 16 chars, otherwise the internal
 /// operations will load memory outside the string bound.
+#[inline]
 #[cfg(target_arch = "x86_64")]
 #[target_feature(enable = "sse4.1")]
-pub(super) unsafe fn parse_7_chars_simd(s: &str) -> u32 {
-    let mut chunk = _mm_loadu_si128(s.as_ptr() as *const _);
-    let zeros = _mm_set1_epi8(b'0' as i8);
-    chunk = _mm_sub_epi16(chunk, zeros);
-
-    let mult = _mm_set_epi8(0, 0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 10, 1, 10, 1, 10);
-    chunk = _mm_maddubs_epi16(chunk, mult);
-    // make first two 16 bit chunks a 4 digit number, while leaving the next 16
-    // bits untouched
-    let mult = _mm_set_epi16(0, 0, 0, 0, 1, 10, 1, 100);
-    chunk = _mm_madd_epi16(chunk, mult);
-
-    chunk = _mm_packus_epi32(chunk, chunk);
-    // make room for the 3 digit number and sum them up
-    let mult = _mm_set_epi16(0, 0, 0, 0, 0, 0, 1, 1000);
-    chunk = _mm_madd_epi16(chunk, mult);
+pub(super) unsafe fn parse_field_checked_simd(s: &[u8], separator: u8, eol: u8) -> (u32, u32, u64) {
+    field_of(_mm_loadu_si128(s.as_ptr() as _), VECTOR_SIZE, separator, eol)
+}
 
-    _mm_cvtsi128_si32(chunk) as u32
+/// Returns the value of the first `n` chars of `chunk`, which must be digits,
+/// where `n` is at most 16
+#[inline]
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse4.1")]
+#[cfg_attr(not(feature = "backend-avx2"), allow(dead_code))]
+pub(super) unsafe fn combine_leading_digits(chunk: __m128i, n: usize) -> u64 {
+    combine_16_digits(right_align_digits(chunk, n))
 }
 
-/// Parses 9 integers from input string using SIMD instructions.
+/// Returns the number of leading digits of a string shorter than 16 chars,
+/// along with their value.
 ///
-/// The input string *must have* at least 16 chars, otherwise the internal
-/// operations will load memory outside the string bound.
+/// The chars are read with a single load, which may read past the end of the
+/// string, but never past the end of its memory page.
+#[inline]
 #[cfg(target_arch = "x86_64")]
 #[target_feature(enable = "sse4.1")]
-pub(super) unsafe fn parse_9_chars_simd(s: &str) -> u32 {
-    let mut chunk = _mm_loadu_si128(s.as_ptr() as *const _);
-    let zeros = _mm_set1_epi8(b'0' as i8);
-    chunk = _mm_sub_epi16(chunk, zeros);
+pub(super) unsafe fn parse_short_prefix(s: &[u8]) -> (u32, u64) {
+    if s.is_empty() {
+        return (0, 0);
+    }
+    prefix_of(page::load_short_128(s), s.len())
+}
 
-    // in all the below `_mm_set_epi8` operations, the first 1 starting from
-    // left ensures that the last digit among the 9 is untouched
-    let mult = _mm_set_epi8(0, 0, 0, 0, 0, 0, 0, 1, 1, 10, 1, 10, 1, 10, 1, 10);
-    let chunk = _mm_maddubs_epi16(chunk, mult);
+/// Same as `parse_short_prefix`, for the first `len` chars of `s`, which has
+/// at least 16 chars: the ones past `len` are padding, and they are loaded but
+/// ignored.
+#[inline]
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse4.1")]
+pub(super) unsafe fn parse_padded_prefix(s: &[u8], len: usize) -> (u32, u64) {
+    prefix_of(_mm_loadu_si128(s.as_ptr() as _), len.min(VECTOR_SIZE))
+}
 
-    let mult = _mm_set_epi16(0, 0, 0, 1, 1, 100, 1, 100);
-    let chunk = _mm_madd_epi16(chunk, mult);
+/// Returns the number of leading digits among the first `len` chars of
+/// `value`, along with their value
+#[inline]
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse4.1")]
+unsafe fn prefix_of(value: __m128i, len: usize) -> (u32, u64) {
+    let (_, non_digits) = masks_of(value, 0, 0);
+    // the bytes past the end of the string are not part of the number
+    let digits = (non_digits | page::past_end_mask(len)).trailing_zeros();
+    (digits, combine_16_digits(right_align_digits(value, digits as usize)))
+}
 
-    let chunk = _mm_packus_epi32(chunk, chunk);
-    let mult = _mm_set_epi16(0, 0, 0, 0, 0, 1, 1, 10000);
-    let chunk = _mm_madd_epi16(chunk, mult);
+/// Returns, for a string shorter than 16 chars, the offset of the first
+/// `separator` or `eol`, which is the length of the string when there is none,
+/// the offset of the first non digit and the value of the chars before the
+/// terminator.
+///
+/// The terminator is a non digit itself, so the chars before it are all digits
+/// only when the two offsets are equal; otherwise, the value is meaningless.
+/// The chars are read with a single load, which may read past the end of the
+/// string, but never past the end of its memory page.
+#[inline]
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse4.1")]
+pub(super) unsafe fn parse_short_field(s: &[u8], separator: u8, eol: u8) -> (u32, u32, u64) {
+    if s.is_empty() {
+        return (0, 0, 0);
+    }
+    field_of(page::load_short_128(s), s.len(), separator, eol)
+}
 
-    let chunk = _mm_cvtsi128_si64(chunk) as u64;
-    // make room to place the remeaning digit
-    (((chunk & 0x00000000ffffffff) * 10) + (chunk >> 32)) as u32
+/// Same as `parse_short_field`, for the first `len` chars of `s`, which has at
+/// least 16 chars: the ones past `len` are padding, and they are loaded but
+/// ignored.
+#[inline]
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse4.1")]
+pub(super) unsafe fn parse_padded_field(
+    s: &[u8],
+    len: usize,
+    separator: u8,
+    eol: u8
+) -> (u32, u32, u64) {
+    field_of(_mm_loadu_si128(s.as_ptr() as _), len.min(VECTOR_SIZE), separator, eol)
 }
 
-/// Parses 10 integers from input string using SIMD instructions.
+/// Returns the offsets of the terminator and of the first non digit among the
+/// first `len` chars of `value`, along with the value of the chars before the
+/// terminator
+#[inline]
+#[cfg(target_arch = "x86_64")]
+#[target_feature(enable = "sse4.1")]
+unsafe fn field_of(value: __m128i, len: usize, separator: u8, eol: u8) -> (u32, u32, u64) {
+    let (separators, non_digits) = masks_of(value, separator, eol);
+    // the end of the string terminates the field as well
+    let past_end = page::past_end_mask(len);
+    let end = (separators | past_end).trailing_zeros();
+    let invalid = (non_digits | past_end).trailing_zeros();
+    (end, invalid, combine_16_digits(right_align_digits(value, end as usize)))
+}
+
+/// Parses an u64 from the first `len` chars of the input string, which must
+/// be all digits.
+///
+/// `len` can be at most 20, i.e. the number of digits of `u64::MAX`. If the
+/// number doesn't fit in an `u64`, `None` is returned.
 ///
 /// The input string *must have* at least 16 chars, otherwise the internal
 /// operations will load memory outside the string bound.
+#[inline]
 #[cfg(target_arch = "x86_64")]
 #[target_feature(enable = "sse4.1")]
-pub(super) unsafe fn parse_10_chars_simd(s: &str) -> u32 {
-    let mut chunk = _mm_loadu_si128(s.as_ptr() as *const _);
-    let zeros = _mm_set1_epi8(b'0' as i8);
-    chunk = _mm_sub_epi16(chunk, zeros);
-
-    let mult = _mm_set_epi8(0, 0, 0, 0, 0, 0, 1, 10, 1, 10, 1, 10, 1, 10, 1, 10);
-    let chunk = _mm_maddubs_epi16(chunk, mult);
-
-    // in all the below `_mm_set_epi8` operations, the first 1 starting from
-    // left ensures that the last digit among the 9 is untouched
-    let mult = _mm_set_epi16(0, 0, 0, 1, 1, 100, 1, 100);
-    let chunk = _mm_madd_epi16(chunk, mult);
-
-    let chunk = _mm_packus_epi32(chunk, chunk);
-    let mult = _mm_set_epi16(0, 0, 0, 0, 0, 1, 1, 10000);
-    let chunk = _mm_madd_epi16(chunk, mult);
-
-    let chunk = _mm_cvtsi128_si64(chunk) as u64;
-    // make room to place the 2 remeaning digits
-    (((chunk & 0x00000000ffffffff) * 100) + (chunk >> 32)) as u32
+pub(super) unsafe fn parse_u64_chars_simd(s: &[u8], len: usize) -> Option<u64> {
+    if len <= VECTOR_SIZE {
+        return Some(parse_digits_simd(s, len));
+    }
+    // the last 16 digits are loaded with a single read ending at the last
+    // digit, while the remaining ones at the beginning are shifted in place
+    let low = load_right_aligned_digits(s.as_ptr().add(len - VECTOR_SIZE), VECTOR_SIZE);
+    let high = load_right_aligned_digits(s.as_ptr(), len - VECTOR_SIZE);
+    combine_16_digits(high)
+        .checked_mul(10_000_000_000_000_000)?
+        .checked_add(combine_16_digits(low))
 }
 
 #[cfg(test)]
@@ -312,7 +471,7 @@ mod tests {
 
     #[test]
     fn check_all_chars_are_valid_simd_valid() {
-        let s = "1234567890123456";
+        let s = b"1234567890123456";
         unsafe {
             assert!(check_all_chars_are_valid(s));
         }
@@ -320,7 +479,7 @@ mod tests {
 
     #[test]
     fn check_all_chars_are_valid_simd_invalid() {
-        let s = "123456789,123456";
+        let s = b"123456789,123456";
         unsafe {
             assert!(!check_all_chars_are_valid(s));
         }
@@ -328,7 +487,7 @@ mod tests {
 
     #[test]
     fn last_byte_without_separator_first_digit() {
-        let s = "1,23456789123456";
+        let s = b"1,23456789123456";
         unsafe {
             assert_eq!(last_byte_without_separator(s, SEP, EOL), 1);
         }
@@ -336,7 +495,7 @@ mod tests {
 
     #[test]
     fn last_byte_without_separator_more_digit() {
-        let s = "123456,789123456";
+        let s = b"123456,789123456";
         unsafe {
             assert_eq!(last_byte_without_separator(s, SEP, EOL), 6);
         }
@@ -344,7 +503,7 @@ mod tests {
 
     #[test]
     fn last_byte_without_separator_first_separator() {
-        let s = ",123456789123456";
+        let s = b",123456789123456";
         unsafe {
             assert_eq!(last_byte_without_separator(s, SEP, EOL), 0);
         }
@@ -352,7 +511,7 @@ mod tests {
 
     #[test]
     fn last_digit_byte_all_digits() {
-        let s = "0123456789012345";
+        let s = b"0123456789012345";
         unsafe {
             assert_eq!(last_digit_byte(s), 32);
         }
@@ -360,7 +519,7 @@ mod tests {
 
     #[test]
     fn last_digit_byte_no_digit() {
-        let s = "/!23456789012345";
+        let s = b"/!23456789012345";
         unsafe {
             assert_eq!(last_digit_byte(s), 0);
         }
@@ -368,88 +527,168 @@ mod tests {
 
     #[test]
     fn last_digit_byte_some_digits() {
-        let s = "1223!56789012345";
+        let s = b"1223!56789012345";
         unsafe {
             assert_eq!(last_digit_byte(s), 4);
         }
     }
 
     #[test]
-    fn test_parse_10_chars_simd() {
-        let s = "1234567890123456";
+    fn parse_integer_simd_all_numbers_only_padding() {
+        let s = b"0000000000000000";
         unsafe {
-            assert_eq!(parse_10_chars_simd(s), 1234567890);
+            assert_eq!(parse_integer_simd_all_numbers(s), 0);
         }
     }
 
     #[test]
-    fn test_parse_9_chars_simd() {
-        let s = "1234567890123456";
+    fn parse_integer_simd_all_numbers_one_digit_padding() {
+        let s = b"0000000000000001";
         unsafe {
-            assert_eq!(parse_9_chars_simd(s), 123456789);
+            assert_eq!(parse_integer_simd_all_numbers(s), 1);
         }
     }
+
     #[test]
-    fn test_parse_8_chars_simd() {
-        let s = "1234567890123456";
+    fn parse_integer_simd_all_numbers_mode_digits_padding() {
+        let s = b"0000000000012345";
         unsafe {
-            assert_eq!(parse_8_chars_simd(s), 12345678);
+            assert_eq!(parse_integer_simd_all_numbers(s), 12345);
         }
     }
 
     #[test]
-    fn test_parse_7_chars_simd() {
-        let s = "1234567890123456";
+    fn parse_digits_simd_short() {
+        let s = b"123,456789012345";
         unsafe {
-            assert_eq!(parse_7_chars_simd(s), 1234567);
+            assert_eq!(parse_digits_simd(s, 3), 123);
         }
     }
 
     #[test]
-    fn test_parse_6_chars_simd() {
-        let s = "1234567890123456";
+    fn parse_digits_simd_every_length() {
+        let s = b"4294967295123456";
+        for len in 0..=VECTOR_SIZE {
+            let expected = s[..len].iter().fold(0, |a, c| a * 10 + (c - b'0') as u64);
+            unsafe {
+                assert_eq!(parse_digits_simd(s, len), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn parse_u64_chars_simd_10_digits() {
+        let s = b"9876543210,23456";
         unsafe {
-            assert_eq!(parse_6_chars_simd(s), 123456);
+            assert_eq!(parse_u64_chars_simd(s, 10), Some(9876543210));
         }
     }
 
     #[test]
-    fn test_parse_5_chars_simd() {
-        let s = "1234567890123456";
+    fn parse_u64_chars_simd_16_digits() {
+        let s = b"1234567890123456";
         unsafe {
-            assert_eq!(parse_5_chars_simd(s), 12345);
+            assert_eq!(parse_u64_chars_simd(s, 16), Some(1234567890123456));
         }
     }
 
     #[test]
-    fn test_parse_4_chars_simd() {
-        let s = "1234567890123456";
+    fn parse_u64_chars_simd_20_digits() {
+        let s = b"12345678901234567890,";
         unsafe {
-            assert_eq!(parse_4_chars_simd(s), 1234);
+            assert_eq!(parse_u64_chars_simd(s, 20), Some(12345678901234567890));
         }
     }
 
     #[test]
-    fn parse_integer_simd_all_numbers_only_padding() {
-        let s = "0000000000000000";
+    fn parse_u64_chars_simd_max_u64() {
+        let s = format!("{}", u64::MAX);
         unsafe {
-            assert_eq!(parse_integer_simd_all_numbers(s), 0);
+            assert_eq!(parse_u64_chars_simd(s.as_bytes(), 20), Some(u64::MAX));
         }
     }
 
     #[test]
-    fn parse_integer_simd_all_numbers_one_digit_padding() {
-        let s = "0000000000000001";
+    fn parse_u64_chars_simd_overflow() {
+        let s = b"18446744073709551616";
         unsafe {
-            assert_eq!(parse_integer_simd_all_numbers(s), 1);
+            assert_eq!(parse_u64_chars_simd(s, 20), None);
         }
     }
 
     #[test]
-    fn parse_integer_simd_all_numbers_mode_digits_padding() {
-        let s = "0000000000012345";
+    fn separator_and_non_digit_masks_invalid_before_separator() {
+        let s = b"12a4,67890123456";
         unsafe {
-            assert_eq!(parse_integer_simd_all_numbers(s), 12345);
+            let (separators, non_digits) = separator_and_non_digit_masks(s, SEP, EOL);
+            assert_eq!(separators.trailing_zeros(), 4);
+            assert_eq!(non_digits.trailing_zeros(), 2);
+        }
+    }
+
+    #[test]
+    fn separator_set_mask_every_separator() {
+        let set = SeparatorSet::new(b" ;\t").unwrap();
+        let s = b"12 34;56\t7890123";
+        unsafe {
+            assert_eq!(separator_set_mask(s, &set), 0b1_0010_0100);
+        }
+    }
+
+    #[test]
+    fn separator_and_non_digit_masks_no_separator() {
+        let s = b"1234567890123456";
+        unsafe {
+            let (separators, non_digits) = separator_and_non_digit_masks(s, SEP, EOL);
+            assert_eq!(separators.trailing_zeros(), 32);
+            assert_eq!(non_digits.trailing_zeros(), 32);
+        }
+    }
+    #[test]
+    fn parse_short_prefix_every_length() {
+        let s = b"123456789012345";
+        for len in 0..VECTOR_SIZE {
+            let expected = s[..len].iter().fold(0, |a, c| a * 10 + (c - b'0') as u64);
+            assert_eq!(unsafe { parse_short_prefix(&s[..len]) }, (len as u32, expected));
+        }
+    }
+
+    #[test]
+    fn parse_short_prefix_stops_at_non_digit() {
+        assert_eq!(unsafe { parse_short_prefix(b"42,7") }, (2, 42));
+        assert_eq!(unsafe { parse_short_prefix(b"x42") }, (0, 0));
+    }
+
+    #[test]
+    fn parse_short_field_terminators() {
+        assert_eq!(unsafe { parse_short_field(b"123,4", SEP, EOL) }, (3, 3, 123));
+        assert_eq!(unsafe { parse_short_field(b"123", SEP, EOL) }, (3, 3, 123));
+        assert_eq!(unsafe { parse_short_field(b"\n", SEP, EOL) }, (0, 0, 0));
+        assert_eq!(unsafe { parse_short_field(b"1a3,4", SEP, EOL) }.1, 1);
+    }
+
+    #[test]
+    fn parse_prefix_checked_simd_every_length() {
+        let digits = b"4294967295123456";
+        for len in 0..=VECTOR_SIZE {
+            let mut s = *digits;
+            if len < VECTOR_SIZE {
+                s[len] = b',';
+            }
+            let expected = s[..len].iter().fold(0, |a, c| a * 10 + (c - b'0') as u64);
+            assert_eq!(unsafe { parse_prefix_checked_simd(&s) }, (len as u32, expected));
         }
     }
+
+    #[test]
+    fn parse_field_checked_simd_terminators() {
+        unsafe {
+            let field = parse_field_checked_simd(b"4294967296,12345", SEP, EOL);
+            assert_eq!(field, (10, 10, 4294967296));
+            assert_eq!(parse_field_checked_simd(b"\n234567890123456", SEP, EOL), (0, 0, 0));
+            assert_eq!(parse_field_checked_simd(b"12x4,67890123456", SEP, EOL).1, 2);
+            assert_eq!(parse_field_checked_simd(b"1234567890123456", SEP, EOL).0, 16);
+        }
+    }
+
 }
@@ -3,42 +3,49 @@
 #[cfg(target_arch = "x86_64")]
 use std::arch::x86_64::*;
 
+use crate::separators::SeparatorSet;
+
 /// Size of _m128i data type
 pub(super) const VECTOR_SIZE: usize = std::mem::size_of::<__m128i>();
 
+/// Smallest separator set worth the string instructions: their latency is
+/// higher than a few byte comparisons, so that up to 7 separators comparing
+/// the chars against one separator at a time is faster
+pub(super) const MIN_STRING_SEPARATORS: usize = 8;
+
 /// Byte array to determine if the chars in a string are in range '0'..'9'
 const NUMERIC_RANGE: &[u8; 16] = b"09\0\0\0\0\0\0\0\0\0\0\0\0\0\0";
-/// Byte array to determine if a string is made of all numbers
-const NUMERIC_VALUES: &[u8; 16] = b"1234567890\0\0\0\0\0\0";
 
 /// Returns true if the string is composed by only digits
 /// 
+/// Every chunk of 16 chars is checked, the last one ending at the end of the
+/// string, so that it overlaps the previous one when the length isn't a
+/// multiple of 16.
+/// 
 /// # Safety
 /// 
 /// Since this function is enabled only when SSE4.2 cpu flag is detected, it
 /// will be called only in this circumstance. The intrinics work with a string
 /// of at least length 16: in case of less chars, an iterative process will be
 /// called.
+#[inline]
 #[target_feature(enable = "sse4.2")]
-pub unsafe fn check_all_chars_are_valid(s: &str) -> bool {
+pub unsafe fn check_all_chars_are_valid(s: &[u8]) -> bool {
     if s.len() < VECTOR_SIZE {
-        return crate::fallback::check_all_chars_are_valid(s);
+        return crate::fallback::check_all_chars_are_valid_bytes(s);
     }
-    let to_cmp = _mm_loadu_si128(s.as_ptr() as *const _);
-    let range = _mm_loadu_si128(NUMERIC_RANGE.as_ptr() as *const _);
-    let idx = _mm_cmpistri(
-        range,
-        to_cmp,
-        _SIDD_CMP_RANGES | _SIDD_MASKED_NEGATIVE_POLARITY,
-    );
-    idx == 16
+    let last = s.len() - VECTOR_SIZE;
+    (0..last)
+        .step_by(VECTOR_SIZE)
+        .chain(std::iter::once(last))
+        .all(|offset| first_non_digit(&s[offset..]) == VECTOR_SIZE as i32)
 }
 
 /// Returns the index of the last digit in the string
 /// 
 /// In case of a string made of all numbers, the call to the SSE4.2 will return
-/// 32, since the mask has value 0. This happens only when the string has length
-/// at least 16 and the intrinisic is called
+/// 32, like the other SIMD implementations. This happens only when the string
+/// has length at least 16 and the intrinisic is called
 /// 
 /// # Safety
 /// 
@@ -46,30 +53,21 @@ pub unsafe fn check_all_chars_are_valid(s: &str) -> bool {
 /// will be called only in this circumstance. The intrinics work with a string
 /// of at least length 16: in case of less chars, an iterative process will be
 /// called.
+#[inline]
 #[target_feature(enable = "sse4.2")]
-pub unsafe fn last_digit_byte(s: &str) -> u32 {
+pub unsafe fn last_digit_byte(s: &[u8]) -> u32 {
     if s.len() < VECTOR_SIZE {
-        return crate::fallback::last_digit_byte(s);
+        return crate::fallback::last_digit_byte_bytes(s);
     }
-    let to_cmp = _mm_loadu_si128(s.as_ptr() as *const _);
-    let valid_nums = _mm_loadu_si128(NUMERIC_VALUES.as_ptr() as *const _);
-    let mask = _mm_cmpistrm(
-        valid_nums,
-        to_cmp,
-        // cmp for any match | negate the result | create a byte mask
-        _SIDD_CMP_EQUAL_ANY | _SIDD_NEGATIVE_POLARITY | _SIDD_UNIT_MASK,
-    );
-    // translate the mask into an integer
-    let idx = _mm_movemask_epi8(mask);
-    idx.trailing_zeros()
+    index_or_32(first_non_digit(s))
 }
 
 /// Returns the index of the last char in the string different from `separator`
 /// and `eol`
 /// 
 /// In case of a string without the given separators, the call to the SSE4.2
-/// will return 32, since the mask has value 0. This happens only when the
-/// string has length at least 16 and the intrinisic is called
+/// will return 32, like the other SIMD implementations. This happens only when
+/// the string has length at least 16 and the intrinisic is called
 /// 
 /// # Safety
 /// 
@@ -77,22 +75,73 @@ pub unsafe fn last_digit_byte(s: &str) -> u32 {
 /// will be called only in this circumstance. The intrinics work with a string
 /// of at least length 16: in case of less chars, an iterative process will be
 /// called.
+#[inline]
 #[target_feature(enable = "sse4.2")]
-pub unsafe fn last_byte_without_separator(s: &str, separator: u8, eol: u8) -> u32 {
+pub unsafe fn last_byte_without_separator(s: &[u8], separator: u8, eol: u8) -> u32 {
     if s.len() < VECTOR_SIZE {
-        return crate::fallback::last_byte_without_separator(s, separator, eol);
+        return crate::fallback::last_byte_without_separator_bytes(s, separator, eol);
     }
+    // the set made of `separator` and `eol`, in its two lowest bytes
+    let separators = _mm_cvtsi32_si128(separator as i32 | (eol as i32) << 8);
+    index_or_32(first_of_set(s, separators, 2))
+}
+
+/// Returns the index of the first char in the string equal to any byte of
+/// `separators`
+///
+/// When none of the first 16 chars is a separator, the returned index will be
+/// 32, like `last_byte_without_separator`.
+///
+/// # Safety
+///
+/// The CPU must support SSE4.2. The input string *must have* at least 16
+/// chars, otherwise the internal operations will load memory outside the
+/// string bound.
+#[inline]
+#[target_feature(enable = "sse4.2")]
+pub(super) unsafe fn first_separator_in_set(s: &[u8], separators: &SeparatorSet) -> u32 {
+    let set = _mm_loadu_si128(separators.padded().as_ptr() as *const _);
+    index_or_32(first_of_set(s, set, separators.len() as i32))
+}
+
+/// Returns the index of the first of the first 16 chars of the string which
+/// isn't a digit, or 16 if they are all digits
+///
+/// The lengths of the operands are explicit, so a NUL byte doesn't end the
+/// string like it does with the implicit length instructions.
+#[inline]
+#[target_feature(enable = "sse4.2")]
+unsafe fn first_non_digit(s: &[u8]) -> i32 {
     let to_cmp = _mm_loadu_si128(s.as_ptr() as *const _);
-    let valid_nums = _mm_loadu_si128(NUMERIC_VALUES.as_ptr() as *const _);
-    let mask = _mm_cmpistrm(
-        valid_nums,
+    let range = _mm_loadu_si128(NUMERIC_RANGE.as_ptr() as *const _);
+    _mm_cmpestri(
+        range,
+        2,
         to_cmp,
-        // cmp for any match | negate the result | create a byte mask
-        _SIDD_CMP_EQUAL_ANY | _SIDD_NEGATIVE_POLARITY | _SIDD_UNIT_MASK,
-    );
-    // translate the mask into an integer
-    let idx = _mm_movemask_epi8(mask);
-    idx.trailing_zeros()
+        VECTOR_SIZE as i32,
+        // cmp with the range | negate the result
+        _SIDD_CMP_RANGES | _SIDD_NEGATIVE_POLARITY,
+    )
+}
+
+/// Returns the index of the first of the first 16 chars of the string equal to
+/// any of the first `len` bytes of `set`, or 16 if there is none
+#[inline]
+#[target_feature(enable = "sse4.2")]
+unsafe fn first_of_set(s: &[u8], set: __m128i, len: i32) -> i32 {
+    let to_cmp = _mm_loadu_si128(s.as_ptr() as *const _);
+    _mm_cmpestri(set, len, to_cmp, VECTOR_SIZE as i32, _SIDD_CMP_EQUAL_ANY)
+}
+
+/// Turns the index returned by the string instructions when there is no match,
+/// i.e. 16, into the 32 returned by the other SIMD implementations
+#[inline(always)]
+fn index_or_32(index: i32) -> u32 {
+    if index == VECTOR_SIZE as i32 {
+        32
+    } else {
+        index as u32
+    }
 }
 
 #[cfg(test)]
@@ -103,7 +152,7 @@ mod tests {
 
     #[test]
     fn last_byte_without_separator_no_digit() {
-        let s = ",1234.4321\n    ";
+        let s = b",1234.4321\n    ";
         unsafe {
             assert_eq!(last_byte_without_separator(s, SEP, EOL), 0);
         }
@@ -111,7 +160,7 @@ mod tests {
 
     #[test]
     fn last_byte_without_separator_one_digit() {
-        let s = "1,2343211234432542";
+        let s = b"1,2343211234432542";
         unsafe {
             assert_eq!(last_byte_without_separator(s, SEP, EOL), 1);
         }
@@ -119,7 +168,7 @@ mod tests {
 
     #[test]
     fn last_byte_without_separator_more_digits() {
-        let s = "123,44321\n12345";
+        let s = b"123,44321\n12345";
         unsafe {
             assert_eq!(last_byte_without_separator(s, SEP, EOL), 3);
         }
@@ -127,7 +176,7 @@ mod tests {
     
     #[test]
     fn last_digit_byte_all_numbers() {
-        let s = "1239443218123459";
+        let s = b"1239443218123459";
         unsafe {
             assert_eq!(last_digit_byte(s), 32);
         }
@@ -135,7 +184,7 @@ mod tests {
 
     #[test]
     fn last_digit_byte_no_number() {
-        let s = "/2.944321812345";
+        let s = b"/2.944321812345";
         unsafe {
             assert_eq!(last_digit_byte(s), 0);
         }
@@ -143,7 +192,7 @@ mod tests {
 
     #[test]
     fn last_digit_byte_some_digits() {
-        let s = "129,44321812345";
+        let s = b"129,44321812345";
         unsafe {
             assert_eq!(last_digit_byte(s), 3);
         }
@@ -151,15 +200,63 @@ mod tests {
 
     #[test]
     fn check_all_chars_are_valid_valid() {
-        let s = "1234567890123456";
+        let s = b"1234567890123456";
         unsafe {
             assert!(check_all_chars_are_valid(s));
         }
     }
 
+    #[test]
+    fn last_byte_without_separator_other_separators() {
+        let s = b"12;34|5678901234";
+        unsafe {
+            assert_eq!(last_byte_without_separator(s, b'|', b';'), 2);
+            assert_eq!(last_byte_without_separator(s, b'|', EOL), 5);
+            assert_eq!(last_byte_without_separator(s, SEP, EOL), 32);
+        }
+    }
+
+    #[test]
+    fn nul_byte_is_not_a_digit() {
+        let s = b"123\0456789012345";
+        unsafe {
+            assert_eq!(last_digit_byte(s), 3);
+            assert!(!check_all_chars_are_valid(s));
+        }
+    }
+
+    #[test]
+    fn first_separator_in_set_any_byte() {
+        let set = SeparatorSet::new(b" ;\t\n").unwrap();
+        unsafe {
+            assert_eq!(first_separator_in_set(b"1234\t5678 90;123", &set), 4);
+            assert_eq!(first_separator_in_set(b"1234567890123456;", &set), 32);
+            assert_eq!(first_separator_in_set(b"\0\0\0;\0\0\0\0\0\0\0\0\0\0\0\0", &set), 3);
+        }
+    }
+
+    #[test]
+    fn first_separator_in_set_empty() {
+        let set = SeparatorSet::new(b"").unwrap();
+        unsafe {
+            assert_eq!(first_separator_in_set(b"\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0\0", &set), 32);
+        }
+    }
+
+    #[test]
+    fn check_all_chars_are_valid_every_chunk() {
+        let s = b"1234567890123456789012345678901234x67890";
+        unsafe {
+            assert!(check_all_chars_are_valid(&s[..34]));
+            assert!(!check_all_chars_are_valid(s));
+            assert!(!check_all_chars_are_valid(&s[..35]));
+            assert!(!check_all_chars_are_valid(&s[19..]));
+        }
+    }
+
     #[test]
     fn check_all_chars_are_valid_invalid() {
-        let s = "123456789,123456";
+        let s = b"123456789,123456";
         unsafe {
             assert!(!check_all_chars_are_valid(s));
         }
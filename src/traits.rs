@@ -0,0 +1,214 @@
+//! Generic parsing API over the supported integer types.
+//!
+//! `SimdParse` routes every integer type to the dispatched implementation of
+//! the library API, while `SimdParseExt` exposes it as methods of `str` and
+//! `[u8]`, so that generic code can be written once for every width.
+
+use std::convert::TryFrom;
+
+/// Integer types that can be parsed by the library.
+///
+/// Types narrower than 32 bits are parsed with the 32 bits implementations and
+/// then checked against their bounds.
+pub trait SimdParse: Sized {
+    /// Parses `Self` from the input bytes as far as there are digits.
+    ///
+    /// In case of empty input or arithmetic overflow, it will return None.
+    fn parse(s: &[u8]) -> Option<Self>;
+
+    /// Parses `Self` from the input bytes up to the first occurrence of
+    /// `separator` or `eol`.
+    ///
+    /// In case of empty input, arithmetic overflow or absence of number to
+    /// parse, it will return None.
+    fn parse_separator(s: &[u8], separator: u8, eol: u8) -> Option<Self>;
+}
+
+impl SimdParse for u32 {
+    #[inline]
+    fn parse(s: &[u8]) -> Option<Self> {
+        crate::parse_integer_bytes(s)
+    }
+
+    #[inline]
+    fn parse_separator(s: &[u8], separator: u8, eol: u8) -> Option<Self> {
+        crate::parse_integer_separator_bytes(s, separator, eol)
+    }
+}
+
+impl SimdParse for u64 {
+    #[inline]
+    fn parse(s: &[u8]) -> Option<Self> {
+        crate::parse_u64_bytes(s)
+    }
+
+    #[inline]
+    fn parse_separator(s: &[u8], separator: u8, eol: u8) -> Option<Self> {
+        crate::parse_u64_separator_bytes(s, separator, eol)
+    }
+}
+
+impl SimdParse for i32 {
+    #[inline]
+    fn parse(s: &[u8]) -> Option<Self> {
+        crate::parse_i32_bytes(s)
+    }
+
+    #[inline]
+    fn parse_separator(s: &[u8], separator: u8, eol: u8) -> Option<Self> {
+        crate::parse_i32_separator_bytes(s, separator, eol)
+    }
+}
+
+impl SimdParse for i64 {
+    #[inline]
+    fn parse(s: &[u8]) -> Option<Self> {
+        crate::parse_i64_bytes(s)
+    }
+
+    #[inline]
+    fn parse_separator(s: &[u8], separator: u8, eol: u8) -> Option<Self> {
+        crate::parse_i64_separator_bytes(s, separator, eol)
+    }
+}
+
+/// Implements `SimdParse` for a type narrower than `$wide`, narrowing the
+/// result of the `$wide` implementation
+macro_rules! impl_simd_parse_narrow {
+    ($($ty:ty => $wide:ty),*) => {
+        $(
+            impl SimdParse for $ty {
+                #[inline]
+                fn parse(s: &[u8]) -> Option<Self> {
+                    <$wide as SimdParse>::parse(s).and_then(|n| <$ty>::try_from(n).ok())
+                }
+
+                #[inline]
+                fn parse_separator(s: &[u8], separator: u8, eol: u8) -> Option<Self> {
+                    <$wide as SimdParse>::parse_separator(s, separator, eol)
+                        .and_then(|n| <$ty>::try_from(n).ok())
+                }
+            }
+        )*
+    };
+}
+
+impl_simd_parse_narrow!(u8 => u32, u16 => u32, i8 => i32, i16 => i32);
+
+/// Extension methods to parse any `SimdParse` type from strings and bytes.
+///
+/// ```
+/// use simd_parsing::SimdParseExt;
+///
+/// assert_eq!("1234".simd_parse::<u16>(), Some(1234));
+/// assert_eq!(b"-42,7\n".simd_parse_until::<i64>(b',', b'\n'), Some(-42));
+/// ```
+pub trait SimdParseExt {
+    /// Parses a `T` from `self` as far as there are digits.
+    fn simd_parse<T: SimdParse>(&self) -> Option<T>;
+
+    /// Parses a `T` from `self` up to the first occurrence of `separator` or
+    /// `eol`.
+    fn simd_parse_until<T: SimdParse>(&self, separator: u8, eol: u8) -> Option<T>;
+}
+
+impl SimdParseExt for str {
+    #[inline]
+    fn simd_parse<T: SimdParse>(&self) -> Option<T> {
+        T::parse(self.as_bytes())
+    }
+
+    #[inline]
+    fn simd_parse_until<T: SimdParse>(&self, separator: u8, eol: u8) -> Option<T> {
+        T::parse_separator(self.as_bytes(), separator, eol)
+    }
+}
+
+impl SimdParseExt for [u8] {
+    #[inline]
+    fn simd_parse<T: SimdParse>(&self) -> Option<T> {
+        T::parse(self)
+    }
+
+    #[inline]
+    fn simd_parse_until<T: SimdParse>(&self, separator: u8, eol: u8) -> Option<T> {
+        T::parse_separator(self, separator, eol)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    static SEP: u8 = b',';
+    static EOL: u8 = b'\n';
+
+    #[test]
+    fn simd_parse_u8() {
+        assert_eq!("255".simd_parse::<u8>(), Some(255));
+        assert_eq!("256".simd_parse::<u8>(), None);
+    }
+
+    #[test]
+    fn simd_parse_u16() {
+        assert_eq!("1234".simd_parse::<u16>(), Some(1234));
+        assert_eq!("65536".simd_parse::<u16>(), None);
+    }
+
+    #[test]
+    fn simd_parse_u32() {
+        let s = format!("{}", u32::MAX);
+        assert_eq!(s.simd_parse::<u32>(), Some(u32::MAX));
+    }
+
+    #[test]
+    fn simd_parse_u64() {
+        let s = format!("{}", u64::MAX);
+        assert_eq!(s.simd_parse::<u64>(), Some(u64::MAX));
+    }
+
+    #[test]
+    fn simd_parse_i8() {
+        assert_eq!("-128".simd_parse::<i8>(), Some(i8::MIN));
+        assert_eq!("128".simd_parse::<i8>(), None);
+    }
+
+    #[test]
+    fn simd_parse_i16() {
+        assert_eq!("-32768".simd_parse::<i16>(), Some(i16::MIN));
+        assert_eq!("-32769".simd_parse::<i16>(), None);
+    }
+
+    #[test]
+    fn simd_parse_i32() {
+        assert_eq!("-2147483648".simd_parse::<i32>(), Some(i32::MIN));
+    }
+
+    #[test]
+    fn simd_parse_i64() {
+        assert_eq!("+9223372036854775807".simd_parse::<i64>(), Some(i64::MAX));
+    }
+
+    #[test]
+    fn simd_parse_until_str() {
+        let s = "1234,5678\n";
+        assert_eq!(s.simd_parse_until::<u16>(SEP, EOL), Some(1234));
+    }
+
+    #[test]
+    fn simd_parse_until_bytes() {
+        let s: &[u8] = b"12345678901,1\n";
+        assert_eq!(s.simd_parse_until::<u64>(SEP, EOL), Some(12345678901));
+    }
+
+    #[test]
+    fn simd_parse_bytes_invalid_utf8_after_number() {
+        let s: &[u8] = b"1234,\xff\xfe";
+        assert_eq!(s.simd_parse::<u32>(), Some(1234));
+    }
+
+    #[test]
+    fn simd_parse_bytes_empty() {
+        let s: &[u8] = b"";
+        assert_eq!(s.simd_parse::<i32>(), None);
+    }
+}